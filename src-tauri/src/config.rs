@@ -0,0 +1,686 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::SqlitePool;
+use tokio::sync::watch;
+use tracing::warn;
+use crate::database::{migrations, DbSetting};
+use crate::error::{CommandResult, GalphaError};
+use crate::lcu::default_lockfile_paths;
+use crate::riot_api::Platform;
+use crate::secrets::{self, Secrets};
+
+pub const CONFIG_CHANGED_EVENT: &str = "config-changed";
+
+/// Sparse JSON object with the values changed from the app
+const OVERRIDES_KEY: &str = "config_overrides";
+/// Plaintext row of the API key before it moved to the secrets
+const LEGACY_API_KEY_SETTING: &str = "riot_api_key";
+/// `GALPHA_<SECTION>__<KEY>`, e.g. `GALPHA_APP__REFRESH_INTERVAL=60`
+const ENV_PREFIX: &str = "GALPHA_";
+/// Placeholder of config.example.toml
+const EXAMPLE_API_KEY: &str = "RGAPI-YOUR-KEY-HERE";
+const REFRESH_INTERVAL_RANGE: (u64, u64) = (10, 3600);
+const INITIAL_MATCH_COUNT_RANGE: (usize, usize) = (1, 100);
+
+/// Settings of config.toml, section by section
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    pub riot_api: RiotApiConfig,
+    pub app: AppConfig,
+    pub database: DatabaseConfig,
+    pub features: FeaturesConfig,
+    pub lcu: LcuConfig,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiotApiConfig {
+    pub api_key: Option<String>,
+    /// Platform used when a command doesn't specify one
+    pub region: Platform,
+}
+
+impl fmt::Debug for RiotApiConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RiotApiConfig")
+            .field("api_key", &self.api_key.as_deref().map(redact_api_key))
+            .field("region", &self.region)
+            .finish()
+    }
+}
+
+/// Enough of the key to recognize it: "RGAPI-…" and its last 4 characters
+pub fn redact_api_key(api_key: &str) -> String {
+    let chars: Vec<char> = api_key.chars().collect();
+    let tail: String = chars[chars.len().saturating_sub(4)..].iter().collect();
+    format!("RGAPI-…{}", tail)
+}
+
+impl Default for RiotApiConfig {
+    fn default() -> Self {
+        Self {
+            api_key: None,
+            region: Platform::Euw1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    /// Start monitoring the client when the app launches
+    pub auto_start: bool,
+    /// Seconds between two match syncs
+    pub refresh_interval: u64,
+    /// Matches downloaded the first time a PUUID is synced
+    pub initial_match_count: usize,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            auto_start: true,
+            refresh_interval: 30,
+            initial_match_count: 20,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DatabaseConfig {
+    /// Empty for the default location
+    pub path: String,
+}
+
+impl DatabaseConfig {
+    pub fn path(&self) -> Option<PathBuf> {
+        (!self.path.trim().is_empty()).then(|| PathBuf::from(self.path.trim()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeaturesConfig {
+    pub enable_overlay: bool,
+    pub enable_notifications: bool,
+    pub enable_analytics: bool,
+}
+
+impl Default for FeaturesConfig {
+    fn default() -> Self {
+        Self {
+            enable_overlay: false,
+            enable_notifications: true,
+            enable_analytics: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LcuConfig {
+    /// Lockfiles tried before the default locations, when the client process can't be inspected
+    pub lockfile_paths: Vec<String>,
+}
+
+impl LcuConfig {
+    /// The configured lockfiles, then the default ones
+    pub fn lockfile_paths(&self) -> Vec<PathBuf> {
+        self.lockfile_paths.iter()
+            .map(|path| PathBuf::from(path.trim()))
+            .chain(default_lockfile_paths())
+            .collect()
+    }
+}
+
+impl Config {
+    /// Copy for the webview, which never sees the API key itself
+    pub fn redacted(&self) -> Self {
+        let mut config = self.clone();
+        config.riot_api.api_key = config.riot_api.api_key.as_deref().map(redact_api_key);
+        config
+    }
+
+    /// Parse merged layers section by section so errors name the section
+    fn parse(value: &Value) -> CommandResult<Self> {
+        let mut config = Self {
+            riot_api: section(value, "riot_api")?,
+            app: section(value, "app")?,
+            database: section(value, "database")?,
+            features: section(value, "features")?,
+            lcu: section(value, "lcu")?,
+        };
+
+        config.riot_api.api_key = config.riot_api.api_key
+            .map(|key| key.trim().to_string())
+            .filter(|key| !key.is_empty() && key != EXAMPLE_API_KEY);
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> CommandResult<()> {
+        if let Some(api_key) = &self.riot_api.api_key {
+            if !api_key.starts_with("RGAPI-") {
+                return Err(invalid("riot_api.api_key", "must start with \"RGAPI-\""));
+            }
+        }
+
+        let (min, max) = REFRESH_INTERVAL_RANGE;
+        if !(min..=max).contains(&self.app.refresh_interval) {
+            return Err(invalid("app.refresh_interval", format!("must be between {} and {} seconds", min, max)));
+        }
+
+        let (min, max) = INITIAL_MATCH_COUNT_RANGE;
+        if !(min..=max).contains(&self.app.initial_match_count) {
+            return Err(invalid("app.initial_match_count", format!("must be between {} and {}", min, max)));
+        }
+
+        if self.database.path().is_some_and(|path| !path.is_absolute()) {
+            return Err(invalid("database.path", "must be an absolute path"));
+        }
+
+        if self.lcu.lockfile_paths.iter().any(|path| !PathBuf::from(path.trim()).is_absolute()) {
+            return Err(invalid("lcu.lockfile_paths", "must be absolute paths"));
+        }
+
+        Ok(())
+    }
+}
+
+fn invalid(field: &str, reason: impl Into<String>) -> GalphaError {
+    GalphaError::InvalidConfig { field: field.to_string(), reason: reason.into() }
+}
+
+fn section<T: DeserializeOwned + Default>(value: &Value, name: &str) -> CommandResult<T> {
+    match value.get(name) {
+        None | Some(Value::Null) => Ok(T::default()),
+        Some(section) => serde_json::from_value(section.clone()).map_err(|e| invalid(name, e.to_string())),
+    }
+}
+
+/// Layers, lowest priority first: config.toml, the `settings` table, the current session, environment variables.
+/// Running services subscribe to the resolved `Config`.
+#[derive(Clone)]
+pub struct ConfigStore {
+    tx: Arc<watch::Sender<Config>>,
+    file: Arc<Value>,
+    env: Arc<Value>,
+    stored: Arc<Mutex<Value>>,
+    session: Arc<Mutex<Value>>,
+    /// The API key is kept there instead of `OVERRIDES_KEY`
+    secrets: Arc<Secrets>,
+}
+
+impl ConfigStore {
+    /// config.toml and environment variables, `reload` adds the `settings` table once the database is open
+    pub fn load(secrets: Arc<Secrets>) -> Self {
+        Self::from_layers(read_file(), read_env(std::env::vars()), secrets)
+    }
+
+    fn from_layers(file: Value, env: Value, secrets: Arc<Secrets>) -> Self {
+        let store = Self {
+            tx: Arc::new(watch::Sender::new(Config::default())),
+            file: Arc::new(file),
+            env: Arc::new(env),
+            stored: Arc::new(Mutex::new(empty())),
+            session: Arc::new(Mutex::new(empty())),
+            secrets,
+        };
+        store.publish();
+        store
+    }
+
+    pub fn get(&self) -> Config {
+        self.tx.borrow().clone()
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<Config> {
+        self.tx.subscribe()
+    }
+
+    /// Re-read the `settings` table
+    pub async fn reload(&self, pool: &SqlitePool) -> Result<Config> {
+        let stored = read_stored(pool, &self.secrets).await?;
+        *self.stored.lock().unwrap() = stored;
+        Ok(self.publish())
+    }
+
+    /// Merge a partial config (`null` resets a value) into the `settings` table.
+    /// Nothing is saved if the result doesn't validate.
+    pub async fn update(&self, pool: &SqlitePool, mut patch: Value) -> CommandResult<Config> {
+        check_known_keys(&patch, &serde_json::to_value(Config::default()).unwrap(), "")?;
+        self.drop_redacted_api_key(&mut patch);
+
+        let mut stored = read_stored(pool, &self.secrets).await?;
+        merge(&mut stored, &patch);
+        let below = self.merged(&[&self.file]);
+        Config::parse(&overlay(&below, &stored))?;
+
+        let mut overrides = stored.clone();
+        let api_key = overrides.get_mut("riot_api")
+            .and_then(Value::as_object_mut)
+            .and_then(|riot_api| riot_api.remove("api_key"));
+        if let Some(api_key) = api_key.as_ref().and_then(Value::as_str) {
+            self.secrets.set(pool, secrets::RIOT_API_KEY, api_key).await
+        } else {
+            self.secrets.delete(pool, secrets::RIOT_API_KEY).await
+        }
+        .context("Failed to save the API key")?;
+        DbSetting::set(pool, OVERRIDES_KEY, &overrides.to_string()).await
+            .context("Failed to save settings")?;
+
+        // An explicit change wins over the session values
+        remove_keys(&mut self.session.lock().unwrap(), &patch);
+        *self.stored.lock().unwrap() = stored;
+        Ok(self.publish())
+    }
+
+    /// Like `update` for the current run only
+    pub fn update_session(&self, patch: Value) -> CommandResult<Config> {
+        check_known_keys(&patch, &serde_json::to_value(Config::default()).unwrap(), "")?;
+
+        let mut session = self.session.lock().unwrap().clone();
+        merge(&mut session, &patch);
+        let stored = self.stored.lock().unwrap().clone();
+        Config::parse(&overlay(&self.merged(&[&self.file, &stored]), &session))?;
+
+        *self.session.lock().unwrap() = session;
+        Ok(self.publish())
+    }
+
+    /// A config from `redacted` sent back as is keeps the current key
+    fn drop_redacted_api_key(&self, patch: &mut Value) {
+        let Some(current) = self.get().riot_api.api_key else {
+            return;
+        };
+        if let Some(riot_api) = patch.get_mut("riot_api").and_then(Value::as_object_mut) {
+            if riot_api.get("api_key").and_then(Value::as_str) == Some(redact_api_key(&current).as_str()) {
+                riot_api.remove("api_key");
+            }
+        }
+    }
+
+    /// Merge layers in order, skipping (with a warning) each unknown key and each value that doesn't validate
+    fn merged(&self, layers: &[&Value]) -> Value {
+        let template = serde_json::to_value(Config::default()).unwrap();
+        let mut value = empty();
+        for layer in layers {
+            for (field, leaf) in leaves(layer) {
+                if let Err(e) = check_known_keys(&leaf, &template, "") {
+                    warn!("Ignoring config value {}: {}", field, e);
+                    continue;
+                }
+
+                let candidate = overlay(&value, &leaf);
+                match Config::parse(&candidate) {
+                    Ok(_) => value = candidate,
+                    Err(e) => warn!("Ignoring config value {}: {}", field, e),
+                }
+            }
+        }
+        value
+    }
+
+    fn publish(&self) -> Config {
+        let stored = self.stored.lock().unwrap().clone();
+        let session = self.session.lock().unwrap().clone();
+        let value = self.merged(&[&self.file, &stored, &session, &self.env]);
+        let config = Config::parse(&value).unwrap_or_default();
+
+        self.tx.send_if_modified(|current| {
+            let changed = *current != config;
+            *current = config.clone();
+            changed
+        });
+        config
+    }
+}
+
+fn empty() -> Value {
+    Value::Object(Map::new())
+}
+
+/// `overrides` on top of `base`, `null` removing a value
+fn overlay(base: &Value, overrides: &Value) -> Value {
+    let mut value = base.clone();
+    merge(&mut value, overrides);
+    value
+}
+
+fn merge(base: &mut Value, overrides: &Value) {
+    let (Some(base), Some(overrides)) = (base.as_object_mut(), overrides.as_object()) else {
+        return;
+    };
+
+    for (key, value) in overrides {
+        match value {
+            Value::Null => {
+                base.remove(key);
+            }
+            Value::Object(_) => {
+                let entry = base.entry(key.clone()).or_insert_with(empty);
+                if !entry.is_object() {
+                    *entry = empty();
+                }
+                merge(entry, value);
+            }
+            _ => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Split a layer into one single-value layer per `section.key`
+fn leaves(layer: &Value) -> Vec<(String, Value)> {
+    let Some(sections) = layer.as_object() else {
+        return Vec::new();
+    };
+
+    let mut leaves = Vec::new();
+    for (section, keys) in sections {
+        match keys.as_object() {
+            Some(keys) => leaves.extend(keys.iter().map(|(key, value)| {
+                (format!("{}.{}", section, key), serde_json::json!({ section: { key: value } }))
+            })),
+            None => leaves.push((section.clone(), serde_json::json!({ section: keys }))),
+        }
+    }
+    leaves
+}
+
+/// Drop from `value` every leaf set by `patch`
+fn remove_keys(value: &mut Value, patch: &Value) {
+    let (Some(value), Some(patch)) = (value.as_object_mut(), patch.as_object()) else {
+        return;
+    };
+
+    for (key, patched) in patch {
+        match (value.get_mut(key), patched) {
+            (Some(nested), Value::Object(_)) => remove_keys(nested, patched),
+            _ => {
+                value.remove(key);
+            }
+        }
+    }
+}
+
+/// Reject typos instead of silently storing them
+fn check_known_keys(patch: &Value, template: &Value, prefix: &str) -> CommandResult<()> {
+    let Some(patch) = patch.as_object() else {
+        return Err(invalid(prefix.trim_end_matches('.'), "expected an object"));
+    };
+
+    for (key, value) in patch {
+        let field = format!("{}{}", prefix, key);
+        match template.get(key) {
+            None => return Err(invalid(&field, "unknown setting")),
+            Some(Value::Object(_)) if !value.is_null() => {
+                check_known_keys(value, &template[key], &format!("{}.", field))?
+            }
+            Some(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+async fn read_stored(pool: &SqlitePool, secrets: &Secrets) -> Result<Value> {
+    // Saved in plaintext by older versions
+    if let Some(api_key) = DbSetting::get(pool, LEGACY_API_KEY_SETTING).await? {
+        secrets.set(pool, secrets::RIOT_API_KEY, &api_key).await?;
+        migrations::scrub_setting(pool, LEGACY_API_KEY_SETTING).await?;
+    }
+
+    let mut stored = match DbSetting::get(pool, OVERRIDES_KEY).await? {
+        Some(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+            warn!("Ignoring invalid stored settings: {}", e);
+            empty()
+        }),
+        None => empty(),
+    };
+
+    match secrets.get(pool, secrets::RIOT_API_KEY).await {
+        Ok(Some(api_key)) => merge(&mut stored, &serde_json::json!({ "riot_api": { "api_key": api_key } })),
+        Ok(None) => {}
+        Err(e) => warn!("Ignoring the stored API key: {:#}", e),
+    }
+
+    Ok(stored)
+}
+
+/// The app config directory first, then the working directory (development)
+fn config_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+
+    if let Some(config_dir) = dirs::config_dir() {
+        paths.push(config_dir.join("galpha").join("config.toml"));
+    }
+    paths.push(PathBuf::from("config.toml"));
+
+    paths
+}
+
+fn read_file() -> Value {
+    let Some(path) = config_paths().into_iter().find(|p| p.exists()) else {
+        return empty();
+    };
+
+    let parsed = std::fs::read_to_string(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|content| Ok(toml::from_str::<Value>(&content)?));
+
+    match parsed {
+        Ok(value) => value,
+        Err(e) => {
+            warn!("Ignoring invalid config file {:?}: {}", path, e);
+            empty()
+        }
+    }
+}
+
+/// `RIOT_API_KEY` and `GALPHA_<SECTION>__<KEY>` variables. Values are read as JSON when they parse, as strings otherwise.
+fn read_env(vars: impl Iterator<Item = (String, String)>) -> Value {
+    let mut env = empty();
+
+    for (name, raw) in vars {
+        let path = if name == "RIOT_API_KEY" {
+            vec!["riot_api".to_string(), "api_key".to_string()]
+        } else if let Some(rest) = name.strip_prefix(ENV_PREFIX) {
+            rest.to_lowercase().split("__").map(str::to_string).collect()
+        } else {
+            continue;
+        };
+
+        let [section, key] = path.as_slice() else {
+            continue;
+        };
+        let value = serde_json::from_str::<Value>(&raw)
+            .ok()
+            .filter(|value| !value.is_object() && !value.is_array() && !value.is_null())
+            .unwrap_or(Value::String(raw));
+
+        merge(&mut env, &serde_json::json!({ section: { key: value } }));
+    }
+
+    env
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::database::Database;
+
+    fn secret_store(dir: &tempfile::TempDir) -> Arc<Secrets> {
+        Arc::new(Secrets::new(dir.path().join("master.key")))
+    }
+
+    fn env(vars: &[(&str, &str)]) -> Value {
+        read_env(vars.iter().map(|(k, v)| (k.to_string(), v.to_string())))
+    }
+
+    #[test]
+    fn reads_environment_variables() {
+        let env = env(&[
+            ("RIOT_API_KEY", "RGAPI-env"),
+            ("GALPHA_APP__REFRESH_INTERVAL", "60"),
+            ("GALPHA_APP__INITIAL_MATCH_COUNT", "50"),
+            ("GALPHA_RIOT_API__REGION", "na1"),
+            ("PATH", "/usr/bin"),
+        ]);
+
+        assert_eq!(env, json!({
+            "riot_api": { "api_key": "RGAPI-env", "region": "na1" },
+            "app": { "refresh_interval": 60, "initial_match_count": 50 },
+        }));
+    }
+
+    #[test]
+    fn skips_invalid_values() {
+        let file = toml::from_str::<Value>(r#"
+            [riot_api]
+            api_key = "RGAPI-YOUR-KEY-HERE"
+            region = "KR"
+
+            [app]
+            auto_start = false
+            refresh_interval = 120
+            initial_match_count = 1000
+            refersh_interval = 90
+
+            [features]
+            enable_overlay = "yes"
+            enable_analytics = false
+
+            [legacy]
+            theme = "dark"
+        "#).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let env = env(&[
+            ("RIOT_API_KEY", "RGAPI-env"),
+            ("GALPHA_APP__INITIAL_MATCH_COUNT", "lots"),
+            ("GALPHA_APP__REFRESH_INTERVAL", "60"),
+            ("GALPHA_RIOT_API__REGION", "atlantis"),
+            ("GALPHA_FEATURES__ENABLE_NOTIFICATIONS", "false"),
+            ("GALPHA_FEATURES__ENABLE_SOUNDS", "true"),
+        ]);
+        let store = ConfigStore::from_layers(file, env, secret_store(&dir));
+
+        // Only the invalid values and unknown keys are dropped, the rest of their layer applies
+        let config = store.get();
+        assert!(!config.app.auto_start);
+        assert_eq!(config.features, FeaturesConfig {
+            enable_overlay: false,
+            enable_notifications: false,
+            enable_analytics: false,
+        });
+        assert_eq!(config.riot_api.api_key.as_deref(), Some("RGAPI-env"));
+        assert_eq!(config.riot_api.region, Platform::Kr);
+        assert_eq!(config.app.refresh_interval, 60);
+        assert_eq!(config.app.initial_match_count, 20);
+    }
+
+    #[test]
+    fn tries_configured_lockfiles_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = json!({ "lcu": { "lockfile_paths": ["/games/league/lockfile"] } });
+        let store = ConfigStore::from_layers(file, empty(), secret_store(&dir));
+
+        let paths = store.get().lcu.lockfile_paths();
+        assert_eq!(paths[0], PathBuf::from("/games/league/lockfile"));
+        assert_eq!(paths[1..], default_lockfile_paths()[..]);
+
+        let file = json!({ "lcu": { "lockfile_paths": ["league/lockfile"] } });
+        let store = ConfigStore::from_layers(file, empty(), secret_store(&dir));
+        assert!(store.get().lcu.lockfile_paths.is_empty());
+    }
+
+    #[tokio::test]
+    async fn persists_updates_and_notifies_subscribers() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(Some(dir.path().join("galpha.db"))).await.unwrap();
+        let file = json!({ "app": { "refresh_interval": 120 } });
+        let store = ConfigStore::from_layers(file.clone(), env(&[("GALPHA_APP__INITIAL_MATCH_COUNT", "50")]), secret_store(&dir));
+        let mut changes = store.subscribe();
+
+        let error = store.update(db.pool(), json!({ "app": { "refresh_interval": 1 } })).await.unwrap_err();
+        assert_eq!(error.code(), "INVALID_CONFIG");
+        let error = store.update(db.pool(), json!({ "app": { "refersh_interval": 60 } })).await.unwrap_err();
+        assert!(matches!(error, GalphaError::InvalidConfig { field, .. } if field == "app.refersh_interval"));
+        assert!(!changes.has_changed().unwrap());
+
+        store.update(db.pool(), json!({
+            "riot_api": { "api_key": "RGAPI-saved", "region": "na1" },
+            "app": { "initial_match_count": 10 },
+        })).await.unwrap();
+        assert!(changes.has_changed().unwrap());
+        let config = changes.borrow_and_update().clone();
+        assert_eq!(config.riot_api.region, Platform::Na1);
+        assert_eq!(config.app.refresh_interval, 120);
+        // Environment variables win
+        assert_eq!(config.app.initial_match_count, 50);
+
+        assert_eq!(secret_store(&dir).get(db.pool(), secrets::RIOT_API_KEY).await.unwrap().as_deref(), Some("RGAPI-saved"));
+
+        let reopened = ConfigStore::from_layers(file, empty(), secret_store(&dir));
+        reopened.reload(db.pool()).await.unwrap();
+        assert_eq!(reopened.get().riot_api.api_key.as_deref(), Some("RGAPI-saved"));
+        assert_eq!(reopened.get().riot_api.region, Platform::Na1);
+
+        reopened.update_session(json!({ "riot_api": { "region": "kr" } })).unwrap();
+        assert_eq!(reopened.get().riot_api.region, Platform::Kr);
+        reopened.update(db.pool(), json!({ "riot_api": { "region": null } })).await.unwrap();
+        assert_eq!(reopened.get().riot_api.region, Platform::Euw1);
+    }
+
+    #[tokio::test]
+    async fn moves_the_plaintext_api_key_to_the_secrets() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("galpha.db");
+        let backup_path = dir.path().join("galpha.db.v5.bak");
+        let db = Database::new(Some(db_path.clone())).await.unwrap();
+        DbSetting::set(db.pool(), LEGACY_API_KEY_SETTING, "RGAPI-plain").await.unwrap();
+        // Like the copy made before the secrets migration
+        sqlx::query("VACUUM INTO ?").bind(backup_path.to_string_lossy().into_owned()).execute(db.pool()).await.unwrap();
+
+        let store = ConfigStore::from_layers(empty(), empty(), secret_store(&dir));
+        store.reload(db.pool()).await.unwrap();
+
+        assert_eq!(store.get().riot_api.api_key.as_deref(), Some("RGAPI-plain"));
+        assert_eq!(DbSetting::get(db.pool(), LEGACY_API_KEY_SETTING).await.unwrap(), None);
+        assert_eq!(secret_store(&dir).get(db.pool(), secrets::RIOT_API_KEY).await.unwrap().as_deref(), Some("RGAPI-plain"));
+
+        // Neither in freed pages nor in the backup
+        for path in [db_path, backup_path] {
+            let bytes = std::fs::read(&path).unwrap();
+            assert!(!bytes.windows(b"RGAPI-plain".len()).any(|w| w == b"RGAPI-plain"), "{:?}", path);
+        }
+    }
+
+    #[tokio::test]
+    async fn redacts_the_api_key_for_the_webview() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(Some(dir.path().join("galpha.db"))).await.unwrap();
+        let store = ConfigStore::from_layers(empty(), empty(), secret_store(&dir));
+        store.update(db.pool(), json!({ "riot_api": { "api_key": "RGAPI-1234-abcd" } })).await.unwrap();
+
+        let redacted = store.get().redacted();
+        assert_eq!(redacted.riot_api.api_key.as_deref(), Some("RGAPI-…abcd"));
+        assert!(!format!("{:?}", store.get()).contains("1234"));
+
+        // The redacted config sent back keeps the key
+        let mut patch = serde_json::to_value(&redacted).unwrap();
+        patch["app"]["refresh_interval"] = json!(60);
+        store.update(db.pool(), patch).await.unwrap();
+        assert_eq!(store.get().riot_api.api_key.as_deref(), Some("RGAPI-1234-abcd"));
+        assert_eq!(store.get().app.refresh_interval, 60);
+    }
+}
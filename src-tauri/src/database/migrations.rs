@@ -0,0 +1,721 @@
+//! Numbered, forward-only schema migrations. A released migration is never
+//! edited: schema changes go in a new migration appended to `MIGRATIONS`.
+
+use std::path::{Path, PathBuf};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use futures_util::future::BoxFuture;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Connection, SqliteConnection, SqlitePool};
+use tracing::{debug, info};
+use crate::error::GalphaError;
+use super::models::{DbParticipantStat, DbSetting};
+
+/// Set in the transaction of a migration asking for a participant_stats rebuild, cleared in
+/// the rebuild's own, so a failed rebuild is retried at the next startup
+const REBUILD_PENDING_SETTING: &str = "participant_stats_rebuild_pending";
+
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    /// Runs inside the migration's transaction
+    apply: fn(&mut SqliteConnection) -> BoxFuture<'_, Result<()>>,
+    /// Refill participant_stats from matches.data once every migration ran. Done with the
+    /// current model code, which only matches the latest schema.
+    /// See `REBUILD_PENDING_SETTING`.
+    rebuild_participant_stats: bool,
+}
+
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "initial schema",
+        apply: initial_schema,
+        rebuild_participant_stats: false,
+    },
+    Migration {
+        version: 2,
+        description: "participant_stats for every player of a match",
+        apply: participant_stats_for_all_players,
+        rebuild_participant_stats: false,
+    },
+    Migration {
+        version: 3,
+        description: "ranked_snapshots per queue replace ranked_stats_cache",
+        apply: ranked_snapshots,
+        rebuild_participant_stats: false,
+    },
+    Migration {
+        version: 4,
+        description: "team and individual positions in participant_stats",
+        apply: participant_positions,
+        rebuild_participant_stats: true,
+    },
+    Migration {
+        version: 5,
+        description: "early surrender flag in participant_stats",
+        apply: participant_early_surrender,
+        rebuild_participant_stats: true,
+    },
+    Migration {
+        version: 6,
+        description: "encrypted secrets",
+        apply: secrets,
+        rebuild_participant_stats: false,
+    },
+    Migration {
+        version: 7,
+        description: "tracked accounts and account groups",
+        apply: tracked_accounts,
+        rebuild_participant_stats: false,
+    },
+    Migration {
+        version: 8,
+        description: "match sync can be paused per tracked account",
+        apply: tracked_account_sync,
+        rebuild_participant_stats: false,
+    },
+    Migration {
+        version: 9,
+        description: "draft_timelines keyed by platform and game ID",
+        apply: draft_platforms,
+        rebuild_participant_stats: false,
+    },
+];
+
+/// Schema version this build writes
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+/// Bring the database up to `latest_version`, backing it up first if it already has data
+pub async fn run(pool: &SqlitePool, db_path: &Path) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    let current = current_version(pool).await?;
+    let latest = latest_version();
+
+    if current > latest {
+        return Err(GalphaError::DbVersionTooNew { found: current, supported: latest }.into());
+    }
+
+    let pending: Vec<&Migration> = MIGRATIONS.iter().filter(|m| m.version > current).collect();
+    if pending.is_empty() {
+        debug!("Database schema is up to date (version {})", current);
+    } else if has_user_tables(pool).await? {
+        let backup = backup(pool, db_path, current).await?;
+        info!("Backed up database to {:?} before migrating from version {}", backup, current);
+    }
+
+    for migration in pending {
+        let mut tx = pool.begin().await?;
+
+        (migration.apply)(&mut tx).await
+            .with_context(|| format!("Migration {} ({}) failed", migration.version, migration.description))?;
+
+        if migration.rebuild_participant_stats {
+            sqlx::query("INSERT OR REPLACE INTO settings (key, value, updated_at) VALUES (?, '1', ?)")
+                .bind(REBUILD_PENDING_SETTING)
+                .bind(Utc::now().timestamp())
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query("INSERT INTO schema_version (version, description, applied_at) VALUES (?, ?, ?)")
+            .bind(migration.version)
+            .bind(migration.description)
+            .bind(Utc::now().timestamp())
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        info!("Applied migration {}: {}", migration.version, migration.description);
+    }
+
+    if DbSetting::get(pool, REBUILD_PENDING_SETTING).await?.is_some() {
+        let mut tx = pool.begin().await?;
+        let rebuilt = DbParticipantStat::rebuild_from_matches(&mut tx).await
+            .context("Failed to rebuild participant stats")?;
+        sqlx::query("DELETE FROM settings WHERE key = ?")
+            .bind(REBUILD_PENDING_SETTING)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        info!("Rebuilt participant stats from {} cached matches", rebuilt);
+    }
+
+    Ok(())
+}
+
+/// Highest applied migration, 0 for a new or pre-versioning database
+pub async fn current_version(pool: &SqlitePool) -> Result<i64> {
+    let (version,): (Option<i64>,) = sqlx::query_as("SELECT MAX(version) FROM schema_version")
+        .fetch_one(pool)
+        .await?;
+
+    Ok(version.unwrap_or(0))
+}
+
+async fn has_user_tables(pool: &SqlitePool) -> Result<bool> {
+    let (count,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name != 'schema_version'"
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count > 0)
+}
+
+fn db_file_name(db_path: &Path) -> String {
+    db_path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "galpha.db".to_string())
+}
+
+/// Consistent copy of the database next to it, e.g. `galpha.db.v1.bak`
+async fn backup(pool: &SqlitePool, db_path: &Path, version: i64) -> Result<PathBuf> {
+    let backup_path = db_path.with_file_name(format!("{}.v{}.bak", db_file_name(db_path), version));
+
+    // VACUUM INTO refuses to overwrite
+    if backup_path.exists() {
+        std::fs::remove_file(&backup_path)
+            .context("Failed to replace the previous database backup")?;
+    }
+
+    sqlx::query("VACUUM INTO ?")
+        .bind(backup_path.to_string_lossy().into_owned())
+        .execute(pool)
+        .await
+        .context("Failed to back up the database")?;
+
+    Ok(backup_path)
+}
+
+/// Every backup written by `backup`
+fn backup_paths(db_path: &Path) -> Result<Vec<PathBuf>> {
+    let Some(dir) = db_path.parent() else {
+        return Ok(Vec::new());
+    };
+    let prefix = format!("{}.v", db_file_name(db_path));
+
+    let paths = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_str().is_some_and(|name| name.starts_with(&prefix) && name.ends_with(".bak")))
+        .map(|entry| entry.path())
+        .collect();
+
+    Ok(paths)
+}
+
+/// Delete a setting from the database and from the backups, then rewrite the files so
+/// the value doesn't survive in freed pages. For values that moved to the secrets.
+pub async fn scrub_setting(pool: &SqlitePool, key: &str) -> Result<()> {
+    DbSetting::delete(pool, key).await?;
+    sqlx::query("VACUUM").execute(pool).await
+        .context("Failed to vacuum the database")?;
+
+    // Empty for an in-memory database
+    let (db_path,): (String,) = sqlx::query_as("SELECT file FROM pragma_database_list WHERE name = 'main'")
+        .fetch_one(pool)
+        .await?;
+    if db_path.is_empty() {
+        return Ok(());
+    }
+
+    for backup_path in backup_paths(Path::new(&db_path))? {
+        scrub_backup(&backup_path, key).await
+            .with_context(|| format!("Failed to scrub the backup {:?}", backup_path))?;
+    }
+
+    Ok(())
+}
+
+async fn scrub_backup(path: &Path, key: &str) -> Result<()> {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .pragma("secure_delete", "ON");
+    let mut conn = SqliteConnection::connect_with(&options).await?;
+
+    let deleted = sqlx::query("DELETE FROM settings WHERE key = ?")
+        .bind(key)
+        .execute(&mut conn)
+        .await?
+        .rows_affected();
+    if deleted > 0 {
+        sqlx::query("VACUUM").execute(&mut conn).await?;
+        info!("Removed setting {} from the backup {:?}", key, path);
+    }
+
+    conn.close().await?;
+    Ok(())
+}
+
+async fn execute_all(conn: &mut SqliteConnection, statements: &[&str]) -> Result<()> {
+    for statement in statements {
+        sqlx::query(statement).execute(&mut *conn).await?;
+    }
+
+    Ok(())
+}
+
+/// The schema from before versioning, written with IF NOT EXISTS so older databases pass through
+fn initial_schema(conn: &mut SqliteConnection) -> BoxFuture<'_, Result<()>> {
+    Box::pin(execute_all(conn, &[
+        r#"
+        CREATE TABLE IF NOT EXISTS summoner (
+            puuid TEXT PRIMARY KEY,
+            game_name TEXT NOT NULL,
+            tag_line TEXT NOT NULL,
+            summoner_id INTEGER,
+            account_id INTEGER,
+            summoner_level INTEGER,
+            profile_icon_id INTEGER,
+            last_updated INTEGER NOT NULL
+        )
+        "#,
+        r#"
+        CREATE TABLE IF NOT EXISTS matches (
+            match_id TEXT PRIMARY KEY,
+            game_creation INTEGER NOT NULL,
+            game_duration INTEGER NOT NULL,
+            game_mode TEXT NOT NULL,
+            game_type TEXT NOT NULL,
+            queue_id INTEGER NOT NULL,
+            map_id INTEGER NOT NULL,
+            platform_id TEXT NOT NULL,
+            game_version TEXT NOT NULL,
+            data JSON NOT NULL,
+            created_at INTEGER NOT NULL
+        )
+        "#,
+        r#"
+        CREATE TABLE IF NOT EXISTS participant_stats (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            match_id TEXT NOT NULL,
+            puuid TEXT NOT NULL,
+            champion_id INTEGER NOT NULL,
+            champion_name TEXT NOT NULL,
+            team_id INTEGER NOT NULL,
+            role TEXT NOT NULL,
+            win INTEGER NOT NULL,
+            kills INTEGER NOT NULL,
+            deaths INTEGER NOT NULL,
+            assists INTEGER NOT NULL,
+            damage_dealt INTEGER NOT NULL,
+            damage_taken INTEGER NOT NULL,
+            gold_earned INTEGER NOT NULL,
+            cs INTEGER NOT NULL,
+            vision_score INTEGER NOT NULL,
+            created_at INTEGER NOT NULL,
+            FOREIGN KEY (match_id) REFERENCES matches(match_id),
+            FOREIGN KEY (puuid) REFERENCES summoner(puuid)
+        )
+        "#,
+        "CREATE INDEX IF NOT EXISTS idx_participant_stats_puuid ON participant_stats(puuid)",
+        "CREATE INDEX IF NOT EXISTS idx_participant_stats_match_id ON participant_stats(match_id)",
+        "CREATE INDEX IF NOT EXISTS idx_matches_game_creation ON matches(game_creation)",
+        r#"
+        CREATE TABLE IF NOT EXISTS match_timelines (
+            match_id TEXT PRIMARY KEY,
+            data JSON NOT NULL,
+            created_at INTEGER NOT NULL,
+            FOREIGN KEY (match_id) REFERENCES matches(match_id)
+        )
+        "#,
+        r#"
+        CREATE TABLE IF NOT EXISTS ranked_stats_cache (
+            puuid TEXT PRIMARY KEY,
+            tier TEXT,
+            rank_value TEXT,
+            league_points INTEGER,
+            wins INTEGER,
+            losses INTEGER,
+            queue_type TEXT NOT NULL,
+            cached_at INTEGER NOT NULL,
+            FOREIGN KEY (puuid) REFERENCES summoner(puuid)
+        )
+        "#,
+        r#"
+        CREATE TABLE IF NOT EXISTS match_cache_metadata (
+            puuid TEXT PRIMARY KEY,
+            last_match_id TEXT,
+            last_fetched INTEGER NOT NULL,
+            total_cached INTEGER NOT NULL,
+            FOREIGN KEY (puuid) REFERENCES summoner(puuid)
+        )
+        "#,
+        r#"
+        CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            updated_at INTEGER NOT NULL
+        )
+        "#,
+        r#"
+        CREATE TABLE IF NOT EXISTS draft_timelines (
+            game_id INTEGER PRIMARY KEY,
+            data JSON NOT NULL,
+            created_at INTEGER NOT NULL
+        )
+        "#,
+        r#"
+        CREATE TABLE IF NOT EXISTS ready_check_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            queue_id INTEGER,
+            action TEXT NOT NULL,
+            reason TEXT,
+            delay_ms INTEGER NOT NULL,
+            decided_at INTEGER NOT NULL
+        )
+        "#,
+    ]))
+}
+
+/// Drop the summoner(puuid) reference, which rejected the other nine players of a match,
+/// and refill the table from the cached match JSON
+fn participant_stats_for_all_players(conn: &mut SqliteConnection) -> BoxFuture<'_, Result<()>> {
+    Box::pin(async move {
+        execute_all(conn, &[
+            "DROP TABLE participant_stats",
+            r#"
+            CREATE TABLE participant_stats (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                match_id TEXT NOT NULL,
+                puuid TEXT NOT NULL,
+                champion_id INTEGER NOT NULL,
+                champion_name TEXT NOT NULL,
+                team_id INTEGER NOT NULL,
+                role TEXT NOT NULL,
+                win INTEGER NOT NULL,
+                kills INTEGER NOT NULL,
+                deaths INTEGER NOT NULL,
+                assists INTEGER NOT NULL,
+                damage_dealt INTEGER NOT NULL,
+                damage_taken INTEGER NOT NULL,
+                gold_earned INTEGER NOT NULL,
+                cs INTEGER NOT NULL,
+                vision_score INTEGER NOT NULL,
+                created_at INTEGER NOT NULL,
+                FOREIGN KEY (match_id) REFERENCES matches(match_id)
+            )
+            "#,
+            "CREATE INDEX idx_participant_stats_puuid ON participant_stats(puuid)",
+            "CREATE UNIQUE INDEX idx_participant_stats_match_puuid ON participant_stats(match_id, puuid)",
+        ]).await?;
+
+        let rebuilt = refill_participant_stats_v2(conn).await?;
+        info!("Rebuilt participant stats from {} cached matches", rebuilt);
+
+        Ok(())
+    })
+}
+
+/// The refill of migration 2 with the columns of that version. It first went through
+/// `DbParticipantStat`, which now writes columns added by later migrations.
+async fn refill_participant_stats_v2(conn: &mut SqliteConnection) -> Result<u64> {
+    let (matches,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM matches WHERE json_valid(data)")
+        .fetch_one(&mut *conn)
+        .await?;
+
+    sqlx::query(
+        r#"
+        INSERT OR REPLACE INTO participant_stats (match_id, puuid, champion_id, champion_name, team_id, role, win, kills, deaths, assists, damage_dealt, damage_taken, gold_earned, cs, vision_score, created_at)
+        SELECT
+            m.match_id,
+            json_extract(p.value, '$.puuid'),
+            json_extract(p.value, '$.championId'),
+            json_extract(p.value, '$.championName'),
+            json_extract(p.value, '$.teamId'),
+            CASE
+                WHEN COALESCE(json_extract(p.value, '$.teamPosition'), '') NOT IN ('', 'Invalid') THEN json_extract(p.value, '$.teamPosition')
+                WHEN COALESCE(json_extract(p.value, '$.individualPosition'), '') NOT IN ('', 'Invalid') THEN json_extract(p.value, '$.individualPosition')
+                ELSE ''
+            END,
+            json_extract(p.value, '$.win'),
+            json_extract(p.value, '$.kills'),
+            json_extract(p.value, '$.deaths'),
+            json_extract(p.value, '$.assists'),
+            json_extract(p.value, '$.totalDamageDealtToChampions'),
+            json_extract(p.value, '$.totalDamageTaken'),
+            json_extract(p.value, '$.goldEarned'),
+            json_extract(p.value, '$.totalMinionsKilled') + json_extract(p.value, '$.neutralMinionsKilled'),
+            json_extract(p.value, '$.visionScore'),
+            ?
+        FROM matches m, json_each(m.data, '$.info.participants') p
+        WHERE json_valid(m.data)
+        "#,
+    )
+    .bind(Utc::now().timestamp())
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(matches as u64)
+}
+
+/// Keep every rank per queue instead of one row per player, starting from the cached one
+fn ranked_snapshots(conn: &mut SqliteConnection) -> BoxFuture<'_, Result<()>> {
+    Box::pin(execute_all(conn, &[
+        r#"
+        CREATE TABLE ranked_snapshots (
+            puuid TEXT NOT NULL,
+            queue_type TEXT NOT NULL,
+            taken_at INTEGER NOT NULL,
+            tier TEXT NOT NULL,
+            division TEXT NOT NULL,
+            league_points INTEGER NOT NULL,
+            wins INTEGER NOT NULL,
+            losses INTEGER NOT NULL,
+            PRIMARY KEY (puuid, queue_type, taken_at)
+        )
+        "#,
+        r#"
+        INSERT INTO ranked_snapshots (puuid, queue_type, taken_at, tier, division, league_points, wins, losses)
+        SELECT puuid, queue_type, cached_at, tier, rank_value, league_points, wins, losses
+        FROM ranked_stats_cache
+        WHERE tier IS NOT NULL AND rank_value IS NOT NULL AND league_points IS NOT NULL
+            AND wins IS NOT NULL AND losses IS NOT NULL
+        "#,
+        "DROP TABLE ranked_stats_cache",
+    ]))
+}
+
+/// Keep both positions of match-v5 next to the derived role
+fn participant_positions(conn: &mut SqliteConnection) -> BoxFuture<'_, Result<()>> {
+    Box::pin(execute_all(conn, &[
+        "ALTER TABLE participant_stats ADD COLUMN team_position TEXT NOT NULL DEFAULT ''",
+        "ALTER TABLE participant_stats ADD COLUMN individual_position TEXT NOT NULL DEFAULT ''",
+    ]))
+}
+
+/// Remakes are left out of every aggregate
+fn participant_early_surrender(conn: &mut SqliteConnection) -> BoxFuture<'_, Result<()>> {
+    Box::pin(execute_all(conn, &[
+        "ALTER TABLE participant_stats ADD COLUMN early_surrender INTEGER NOT NULL DEFAULT 0",
+    ]))
+}
+
+/// Values sealed by `crate::secrets`. Plaintext rows of `settings` move there on first use,
+/// the master key isn't available to migrations.
+fn secrets(conn: &mut SqliteConnection) -> BoxFuture<'_, Result<()>> {
+    Box::pin(execute_all(conn, &[
+        r#"
+        CREATE TABLE secrets (
+            name TEXT PRIMARY KEY,
+            key_id TEXT NOT NULL,
+            nonce BLOB NOT NULL,
+            ciphertext BLOB NOT NULL,
+            updated_at INTEGER NOT NULL
+        )
+        "#,
+    ]))
+}
+
+/// The user's accounts, replacing the `sync_tracked_puuids` setting. At most one is primary.
+fn tracked_accounts(conn: &mut SqliteConnection) -> BoxFuture<'_, Result<()>> {
+    Box::pin(execute_all(conn, &[
+        r#"
+        CREATE TABLE tracked_accounts (
+            puuid TEXT PRIMARY KEY,
+            region TEXT NOT NULL,
+            label TEXT,
+            is_primary INTEGER NOT NULL DEFAULT 0,
+            added_at INTEGER NOT NULL,
+            FOREIGN KEY (puuid) REFERENCES summoner(puuid) ON DELETE CASCADE
+        )
+        "#,
+        "CREATE UNIQUE INDEX idx_tracked_accounts_primary ON tracked_accounts(is_primary) WHERE is_primary = 1",
+        r#"
+        CREATE TABLE account_group_members (
+            group_name TEXT NOT NULL,
+            puuid TEXT NOT NULL,
+            PRIMARY KEY (group_name, puuid),
+            FOREIGN KEY (puuid) REFERENCES tracked_accounts(puuid) ON DELETE CASCADE
+        )
+        "#,
+        r#"
+        INSERT OR IGNORE INTO tracked_accounts (puuid, region, added_at)
+        SELECT json_extract(tracked.value, '$.puuid'), json_extract(tracked.value, '$.region'), settings.updated_at
+        FROM settings, json_each(settings.value) AS tracked
+        WHERE settings.key = 'sync_tracked_puuids'
+            AND json_extract(tracked.value, '$.puuid') IN (SELECT puuid FROM summoner)
+        "#,
+        "UPDATE tracked_accounts SET is_primary = 1 WHERE rowid = (SELECT MIN(rowid) FROM tracked_accounts)",
+        "DELETE FROM settings WHERE key = 'sync_tracked_puuids'",
+    ]))
+}
+
+fn tracked_account_sync(conn: &mut SqliteConnection) -> BoxFuture<'_, Result<()>> {
+    Box::pin(execute_all(conn, &[
+        "ALTER TABLE tracked_accounts ADD COLUMN sync_enabled INTEGER NOT NULL DEFAULT 1",
+    ]))
+}
+
+/// Game IDs are only unique within a platform. Existing drafts take the platform of their
+/// cached match, or stay empty when the match isn't cached.
+fn draft_platforms(conn: &mut SqliteConnection) -> BoxFuture<'_, Result<()>> {
+    Box::pin(execute_all(conn, &[
+        r#"
+        CREATE TABLE draft_timelines_new (
+            platform_id TEXT NOT NULL,
+            game_id INTEGER NOT NULL,
+            data JSON NOT NULL,
+            created_at INTEGER NOT NULL,
+            PRIMARY KEY (platform_id, game_id)
+        )
+        "#,
+        r#"
+        INSERT OR IGNORE INTO draft_timelines_new (platform_id, game_id, data, created_at)
+        SELECT
+            COALESCE((SELECT m.platform_id FROM matches m WHERE m.match_id = m.platform_id || '_' || d.game_id LIMIT 1), ''),
+            d.game_id,
+            d.data,
+            d.created_at
+        FROM draft_timelines d
+        "#,
+        "DROP TABLE draft_timelines",
+        "ALTER TABLE draft_timelines_new RENAME TO draft_timelines",
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::Connection;
+    use super::*;
+    use crate::database::{Database, DbMatch};
+    use crate::riot_api::MatchDetails;
+
+    const MATCH_FIXTURE: &str = include_str!("../../tests/fixtures/riot/match.json");
+
+    async fn count(pool: &SqlitePool, sql: &str) -> i64 {
+        let (count,): (i64,) = sqlx::query_as(sql).fetch_one(pool).await.unwrap();
+        count
+    }
+
+    #[tokio::test]
+    async fn creates_a_fresh_database_without_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(Some(dir.path().join("galpha.db"))).await.unwrap();
+
+        assert_eq!(current_version(db.pool()).await.unwrap(), latest_version());
+        assert_eq!(count(db.pool(), "SELECT COUNT(*) FROM schema_version").await, MIGRATIONS.len() as i64);
+        assert!(!dir.path().join("galpha.db.v0.bak").exists());
+
+        // Reopening is a no-op
+        drop(db);
+        let db = Database::new(Some(dir.path().join("galpha.db"))).await.unwrap();
+        assert_eq!(count(db.pool(), "SELECT COUNT(*) FROM schema_version").await, MIGRATIONS.len() as i64);
+    }
+
+    #[tokio::test]
+    async fn upgrades_an_unversioned_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("galpha.db");
+        let details: MatchDetails = serde_json::from_str(MATCH_FIXTURE).unwrap();
+
+        // A database written before versioning: initial schema, a cached match and rank, no participant rows
+        {
+            let db = Database::new(Some(path.clone())).await.unwrap();
+            DbMatch::from_details(&details).insert(db.pool()).await.unwrap();
+            db.pool().close().await;
+
+            let url = format!("sqlite://{}", path.display());
+            let mut conn = SqliteConnection::connect(&url).await.unwrap();
+            execute_all(&mut conn, &["DROP TABLE schema_version", "DROP TABLE participant_stats", "DROP TABLE ranked_snapshots", "DROP TABLE secrets", "DROP TABLE account_group_members", "DROP TABLE tracked_accounts", "DROP TABLE draft_timelines"]).await.unwrap();
+            initial_schema(&mut conn).await.unwrap();
+            execute_all(&mut conn, &[
+                r#"INSERT INTO draft_timelines VALUES (7212345678, '{"gameId":7212345678}', 1760000000)"#,
+                r#"INSERT INTO draft_timelines VALUES (42, '{"gameId":42}', 1760000000)"#,
+                "INSERT INTO summoner VALUES ('player', 'Galnir', 'EUW', NULL, NULL, 412, 6269, 1760000000)",
+                "INSERT INTO ranked_stats_cache VALUES ('player', 'GOLD', 'I', 75, 30, 28, 'RANKED_SOLO_5x5', 1760000000)",
+                r#"INSERT INTO settings VALUES ('sync_tracked_puuids', '[{"puuid":"player","region":"euw1"},{"puuid":"unsaved","region":"na1"}]', 1760000000)"#,
+            ]).await.unwrap();
+            conn.close().await.unwrap();
+        }
+
+        let db = Database::new(Some(path)).await.unwrap();
+
+        assert_eq!(current_version(db.pool()).await.unwrap(), latest_version());
+        assert_eq!(count(db.pool(), "SELECT COUNT(*) FROM participant_stats").await, 10);
+        assert_eq!(
+            count(db.pool(), "SELECT COUNT(*) FROM pragma_foreign_key_list('participant_stats') WHERE \"table\" = 'summoner'").await,
+            0
+        );
+        assert_eq!(count(db.pool(), "SELECT COUNT(*) FROM ranked_snapshots WHERE tier = 'GOLD' AND division = 'I'").await, 1);
+        assert_eq!(count(db.pool(), "SELECT COUNT(*) FROM tracked_accounts WHERE puuid = 'player' AND is_primary = 1").await, 1);
+        assert_eq!(count(db.pool(), "SELECT COUNT(*) FROM tracked_accounts").await, 1);
+        assert_eq!(count(db.pool(), "SELECT COUNT(*) FROM settings WHERE key = 'sync_tracked_puuids'").await, 0);
+        assert_eq!(count(db.pool(), "SELECT COUNT(*) FROM draft_timelines WHERE platform_id = 'EUW1' AND game_id = 7212345678").await, 1);
+        assert_eq!(count(db.pool(), "SELECT COUNT(*) FROM draft_timelines WHERE platform_id = '' AND game_id = 42").await, 1);
+        assert!(dir.path().join("galpha.db.v0.bak").exists());
+    }
+
+    #[tokio::test]
+    async fn retries_a_failed_participant_stats_rebuild() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("galpha.db");
+        let url = format!("sqlite://{}", path.display());
+        let details: MatchDetails = serde_json::from_str(MATCH_FIXTURE).unwrap();
+
+        // A version 4 database whose rebuild fails once the later migrations ran
+        {
+            let db = Database::new(Some(path.clone())).await.unwrap();
+            DbMatch::from_details(&details).insert(db.pool()).await.unwrap();
+            db.pool().close().await;
+
+            let mut conn = SqliteConnection::connect(&url).await.unwrap();
+            execute_all(&mut conn, &[
+                "DELETE FROM schema_version WHERE version > 4",
+                "ALTER TABLE participant_stats DROP COLUMN early_surrender",
+                "DROP TABLE secrets",
+                "DROP TABLE account_group_members",
+                "DROP TABLE tracked_accounts",
+                "CREATE TRIGGER failing_rebuild BEFORE INSERT ON participant_stats BEGIN SELECT RAISE(ABORT, 'disk full'); END",
+            ]).await.unwrap();
+            conn.close().await.unwrap();
+        }
+
+        assert!(Database::new(Some(path.clone())).await.is_err());
+
+        {
+            let mut conn = SqliteConnection::connect(&url).await.unwrap();
+            let (version,): (i64,) = sqlx::query_as("SELECT MAX(version) FROM schema_version").fetch_one(&mut conn).await.unwrap();
+            assert_eq!(version, latest_version());
+            execute_all(&mut conn, &["DROP TRIGGER failing_rebuild"]).await.unwrap();
+            conn.close().await.unwrap();
+        }
+
+        let db = Database::new(Some(path)).await.unwrap();
+        assert_eq!(count(db.pool(), "SELECT COUNT(*) FROM participant_stats").await, 10);
+        assert_eq!(count(db.pool(), "SELECT COUNT(*) FROM settings WHERE key = 'participant_stats_rebuild_pending'").await, 0);
+    }
+
+    #[tokio::test]
+    async fn refuses_a_newer_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("galpha.db");
+
+        {
+            let db = Database::new(Some(path.clone())).await.unwrap();
+            sqlx::query("INSERT INTO schema_version (version, description, applied_at) VALUES (?, 'from the future', 0)")
+                .bind(latest_version() + 1)
+                .execute(db.pool())
+                .await
+                .unwrap();
+            db.pool().close().await;
+        }
+
+        let error = GalphaError::from(Database::new(Some(path)).await.err().unwrap());
+        assert_eq!(error.code(), "DB_VERSION_TOO_NEW");
+    }
+}
@@ -1,9 +1,9 @@
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{FromRow, SqliteConnection, SqliteExecutor, SqlitePool};
 use anyhow::Result;
 use chrono::Utc;
-
-const CACHE_TTL_SECONDS: i64 = 300; // 5 minutes cache
+use tracing::warn;
+use crate::riot_api::{MatchDetails, Participant, QueueType};
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct DbSummoner {
@@ -59,6 +59,183 @@ impl DbSummoner {
     }
 }
 
+/// One of the user's accounts, joined with its summoner
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TrackedAccount {
+    pub puuid: String,
+    /// Platform, e.g. "euw1"
+    pub region: String,
+    pub label: Option<String>,
+    pub is_primary: bool,
+    /// False while the match sync is stopped for this account
+    pub sync_enabled: bool,
+    pub added_at: i64,
+    pub game_name: String,
+    pub tag_line: String,
+    pub summoner_level: Option<i32>,
+    pub profile_icon_id: Option<i32>,
+    #[sqlx(skip)]
+    pub groups: Vec<String>,
+}
+
+impl TrackedAccount {
+    /// Primary account first, then by date added
+    pub async fn list(pool: &SqlitePool) -> Result<Vec<Self>> {
+        let mut accounts = sqlx::query_as::<_, Self>(
+            r#"
+            SELECT t.puuid, t.region, t.label, t.is_primary, t.sync_enabled, t.added_at,
+                s.game_name, s.tag_line, s.summoner_level, s.profile_icon_id
+            FROM tracked_accounts t
+            JOIN summoner s ON s.puuid = t.puuid
+            ORDER BY t.is_primary DESC, t.added_at ASC, t.rowid ASC
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let memberships = sqlx::query_as::<_, (String, String)>(
+            "SELECT puuid, group_name FROM account_group_members ORDER BY group_name"
+        )
+        .fetch_all(pool)
+        .await?;
+
+        for account in &mut accounts {
+            account.groups = memberships.iter()
+                .filter(|(puuid, _)| *puuid == account.puuid)
+                .map(|(_, group)| group.clone())
+                .collect();
+        }
+
+        Ok(accounts)
+    }
+
+    pub async fn get(pool: &SqlitePool, puuid: &str) -> Result<Option<Self>> {
+        Ok(Self::list(pool).await?.into_iter().find(|account| account.puuid == puuid))
+    }
+
+    pub async fn primary(pool: &SqlitePool) -> Result<Option<Self>> {
+        Ok(Self::list(pool).await?.into_iter().find(|account| account.is_primary))
+    }
+
+    /// Track a saved summoner, or update its region and label and resume its sync. The first account becomes primary.
+    pub async fn add(pool: &SqlitePool, puuid: &str, region: &str, label: Option<&str>) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO tracked_accounts (puuid, region, label, is_primary, added_at)
+            VALUES (?, ?, ?, NOT EXISTS (SELECT 1 FROM tracked_accounts WHERE is_primary = 1), ?)
+            ON CONFLICT(puuid) DO UPDATE SET
+                region = excluded.region,
+                label = COALESCE(excluded.label, tracked_accounts.label),
+                sync_enabled = 1
+            "#,
+        )
+        .bind(puuid)
+        .bind(region)
+        .bind(label)
+        .bind(Utc::now().timestamp())
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Stop tracking an account, the oldest remaining one becomes primary if needed
+    pub async fn remove(pool: &SqlitePool, puuid: &str) -> Result<()> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("DELETE FROM tracked_accounts WHERE puuid = ?")
+            .bind(puuid)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE tracked_accounts SET is_primary = 1
+            WHERE NOT EXISTS (SELECT 1 FROM tracked_accounts WHERE is_primary = 1)
+                AND rowid = (SELECT rowid FROM tracked_accounts ORDER BY added_at ASC, rowid ASC LIMIT 1)
+            "#,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Pause or resume the match sync of an account, returns false if it isn't tracked
+    pub async fn set_sync_enabled(pool: &SqlitePool, puuid: &str, enabled: bool) -> Result<bool> {
+        let updated = sqlx::query("UPDATE tracked_accounts SET sync_enabled = ? WHERE puuid = ?")
+            .bind(enabled)
+            .bind(puuid)
+            .execute(pool)
+            .await?
+            .rows_affected();
+
+        Ok(updated > 0)
+    }
+
+    /// Returns false if the account isn't tracked
+    pub async fn set_primary(pool: &SqlitePool, puuid: &str) -> Result<bool> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("UPDATE tracked_accounts SET is_primary = 0 WHERE is_primary = 1")
+            .execute(&mut *tx)
+            .await?;
+
+        let updated = sqlx::query("UPDATE tracked_accounts SET is_primary = 1 WHERE puuid = ?")
+            .bind(puuid)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        // Keep the previous primary account
+        if updated == 0 {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Replace the groups of an account
+    pub async fn set_groups(pool: &SqlitePool, puuid: &str, groups: &[String]) -> Result<()> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("DELETE FROM account_group_members WHERE puuid = ?")
+            .bind(puuid)
+            .execute(&mut *tx)
+            .await?;
+
+        for group in groups.iter().map(|g| g.trim()).filter(|g| !g.is_empty()) {
+            sqlx::query("INSERT OR IGNORE INTO account_group_members (group_name, puuid) VALUES (?, ?)")
+                .bind(group)
+                .bind(puuid)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn group_members(pool: &SqlitePool, group: &str) -> Result<Vec<String>> {
+        let members = sqlx::query_as::<_, (String,)>(
+            r#"
+            SELECT m.puuid FROM account_group_members m
+            JOIN tracked_accounts t ON t.puuid = m.puuid
+            WHERE m.group_name = ?
+            ORDER BY t.added_at ASC, t.rowid ASC
+            "#,
+        )
+        .bind(group)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(members.into_iter().map(|(puuid,)| puuid).collect())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct DbMatch {
     pub match_id: String,
@@ -75,7 +252,23 @@ pub struct DbMatch {
 }
 
 impl DbMatch {
-    pub async fn insert(&self, pool: &SqlitePool) -> Result<()> {
+    pub fn from_details(details: &MatchDetails) -> Self {
+        Self {
+            match_id: details.metadata.match_id.clone(),
+            game_creation: details.info.game_creation,
+            game_duration: details.info.game_duration,
+            game_mode: details.info.game_mode.clone(),
+            game_type: details.info.game_type.clone(),
+            queue_id: details.info.queue_id,
+            map_id: details.info.map_id,
+            platform_id: details.info.platform_id.clone(),
+            game_version: details.info.game_version.clone(),
+            data: serde_json::to_string(details).unwrap(),
+            created_at: Utc::now().timestamp(),
+        }
+    }
+
+    pub async fn insert<'e, E: SqliteExecutor<'e>>(&self, executor: E) -> Result<()> {
         sqlx::query(
             r#"
             INSERT OR IGNORE INTO matches (match_id, game_creation, game_duration, game_mode, game_type, queue_id, map_id, platform_id, game_version, data, created_at)
@@ -93,12 +286,26 @@ impl DbMatch {
         .bind(&self.game_version)
         .bind(&self.data)
         .bind(self.created_at)
-        .execute(pool)
+        .execute(executor)
         .await?;
 
         Ok(())
     }
 
+    /// Store a match and the stats of all its participants in one transaction
+    pub async fn store(pool: &SqlitePool, details: &MatchDetails) -> Result<()> {
+        let mut tx = pool.begin().await?;
+
+        Self::from_details(details).insert(&mut *tx).await?;
+        for participant in &details.info.participants {
+            DbParticipantStat::from_participant(details, participant).insert(&mut *tx).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
     pub async fn exists(pool: &SqlitePool, match_id: &str) -> Result<bool> {
         let count: (i64,) = sqlx::query_as(
             "SELECT COUNT(*) FROM matches WHERE match_id = ?"
@@ -130,8 +337,15 @@ pub struct DbParticipantStat {
     pub champion_id: i32,
     pub champion_name: String,
     pub team_id: i32,
+    /// `team_position`, else `individual_position`
     pub role: String,
+    /// Position assigned by matchmaking, empty in modes without lanes
+    pub team_position: String,
+    /// Position Riot guessed from where the player actually went
+    pub individual_position: String,
     pub win: i32,
+    /// Remake: the game doesn't count
+    pub early_surrender: i32,
     pub kills: i32,
     pub deaths: i32,
     pub assists: i32,
@@ -144,11 +358,44 @@ pub struct DbParticipantStat {
 }
 
 impl DbParticipantStat {
-    pub async fn insert(&self, pool: &SqlitePool) -> Result<()> {
+    pub fn from_participant(details: &MatchDetails, participant: &Participant) -> Self {
+        // teamPosition is empty in modes without lanes, individualPosition is Riot's guess
+        let role = [&participant.team_position, &participant.individual_position]
+            .into_iter()
+            .find(|position| !position.is_empty() && position.as_str() != "Invalid")
+            .cloned()
+            .unwrap_or_default();
+
+        Self {
+            id: None,
+            match_id: details.metadata.match_id.clone(),
+            puuid: participant.puuid.clone(),
+            champion_id: participant.champion_id,
+            champion_name: participant.champion_name.clone(),
+            team_id: participant.team_id,
+            role,
+            team_position: participant.team_position.clone(),
+            individual_position: participant.individual_position.clone(),
+            win: participant.win as i32,
+            early_surrender: participant.game_ended_in_early_surrender as i32,
+            kills: participant.kills,
+            deaths: participant.deaths,
+            assists: participant.assists,
+            damage_dealt: participant.total_damage_dealt_to_champions,
+            damage_taken: participant.total_damage_taken,
+            gold_earned: participant.gold_earned,
+            cs: participant.total_minions_killed + participant.neutral_minions_killed,
+            vision_score: participant.vision_score,
+            created_at: Utc::now().timestamp(),
+        }
+    }
+
+    /// Replaces the row of the same match and player
+    pub async fn insert<'e, E: SqliteExecutor<'e>>(&self, executor: E) -> Result<()> {
         sqlx::query(
             r#"
-            INSERT INTO participant_stats (match_id, puuid, champion_id, champion_name, team_id, role, win, kills, deaths, assists, damage_dealt, damage_taken, gold_earned, cs, vision_score, created_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT OR REPLACE INTO participant_stats (match_id, puuid, champion_id, champion_name, team_id, role, team_position, individual_position, win, early_surrender, kills, deaths, assists, damage_dealt, damage_taken, gold_earned, cs, vision_score, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&self.match_id)
@@ -157,7 +404,10 @@ impl DbParticipantStat {
         .bind(&self.champion_name)
         .bind(self.team_id)
         .bind(&self.role)
+        .bind(&self.team_position)
+        .bind(&self.individual_position)
         .bind(self.win)
+        .bind(self.early_surrender)
         .bind(self.kills)
         .bind(self.deaths)
         .bind(self.assists)
@@ -167,12 +417,42 @@ impl DbParticipantStat {
         .bind(self.cs)
         .bind(self.vision_score)
         .bind(self.created_at)
-        .execute(pool)
+        .execute(executor)
         .await?;
 
         Ok(())
     }
 
+    /// Rebuild the whole table from the JSON of the cached matches, returns the number of matches read.
+    /// Meant to run inside a transaction.
+    pub async fn rebuild_from_matches(conn: &mut SqliteConnection) -> Result<usize> {
+        sqlx::query("DELETE FROM participant_stats")
+            .execute(&mut *conn)
+            .await?;
+
+        let matches = sqlx::query_as::<_, (String, String)>("SELECT match_id, data FROM matches")
+            .fetch_all(&mut *conn)
+            .await?;
+
+        let mut rebuilt = 0;
+        for (match_id, data) in matches {
+            let details: MatchDetails = match serde_json::from_str(&data) {
+                Ok(details) => details,
+                Err(e) => {
+                    warn!("Skipping match {} with unreadable data: {}", match_id, e);
+                    continue;
+                }
+            };
+
+            for participant in &details.info.participants {
+                Self::from_participant(&details, participant).insert(&mut *conn).await?;
+            }
+            rebuilt += 1;
+        }
+
+        Ok(rebuilt)
+    }
+
     pub async fn get_by_puuid(pool: &SqlitePool, puuid: &str, limit: i32) -> Result<Vec<Self>> {
         let stats = sqlx::query_as::<_, Self>(
             r#"
@@ -192,7 +472,33 @@ impl DbParticipantStat {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Mean of a per-game metric with its sample variance
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct MetricSummary {
+    pub mean: f64,
+    pub variance: f64,
+}
+
+impl MetricSummary {
+    pub fn from_values(values: impl IntoIterator<Item = f64>) -> Self {
+        let values: Vec<f64> = values.into_iter().collect();
+        if values.is_empty() {
+            return Self::default();
+        }
+
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let variance = if values.len() < 2 {
+            0.0
+        } else {
+            values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        };
+
+        Self { mean, variance }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PlayerStats {
     pub total_games: i32,
     pub wins: i32,
@@ -205,215 +511,1436 @@ pub struct PlayerStats {
     pub avg_cs: f64,
     pub avg_damage_dealt: f64,
     pub avg_vision_score: f64,
+    /// Per-game rates, so short and long games weigh the same
+    pub cs_per_min: MetricSummary,
+    pub damage_per_min: MetricSummary,
+    pub gold_per_min: MetricSummary,
+    pub vision_per_min: MetricSummary,
+    /// Fractions of the team total, 0 to 1
+    pub kill_participation: MetricSummary,
+    pub damage_share: MetricSummary,
 }
 
+/// Games aggregated into `PlayerStats`, per account
+const PLAYER_STATS_GAMES: usize = 100;
+
 impl PlayerStats {
-    pub async fn calculate(pool: &SqlitePool, puuid: &str) -> Result<Self> {
-        let stats = DbParticipantStat::get_by_puuid(pool, puuid, 100).await?;
-
-        if stats.is_empty() {
-            return Ok(Self {
-                total_games: 0,
-                wins: 0,
-                losses: 0,
-                winrate: 0.0,
-                avg_kills: 0.0,
-                avg_deaths: 0.0,
-                avg_assists: 0.0,
-                kda: 0.0,
-                avg_cs: 0.0,
-                avg_damage_dealt: 0.0,
-                avg_vision_score: 0.0,
-            });
+    /// Last 100 games, remakes excluded
+    pub async fn calculate(pool: &SqlitePool, puuid: &str, queue: Option<QueueType>) -> Result<Self> {
+        let games = Self::recent_games(pool, puuid, queue).await?;
+        Ok(Self::from_games(&games))
+    }
+
+    async fn recent_games(pool: &SqlitePool, puuid: &str, queue: Option<QueueType>) -> Result<Vec<GameSample>> {
+        let filter = StatsFilter { queue, ..Default::default() };
+        let mut games = GameSample::fetch(pool, puuid, &filter).await?;
+        games.truncate(PLAYER_STATS_GAMES);
+        Ok(games)
+    }
+
+    fn from_games(games: &[GameSample]) -> Self {
+        if games.is_empty() {
+            return Self::default();
         }
 
-        let total_games = stats.len() as i32;
-        let wins = stats.iter().filter(|s| s.win == 1).count() as i32;
-        let losses = total_games - wins;
-        let winrate = (wins as f64 / total_games as f64) * 100.0;
-
-        let total_kills: i32 = stats.iter().map(|s| s.kills).sum();
-        let total_deaths: i32 = stats.iter().map(|s| s.deaths).sum();
-        let total_assists: i32 = stats.iter().map(|s| s.assists).sum();
-        let total_cs: i32 = stats.iter().map(|s| s.cs).sum();
-        let total_damage: i32 = stats.iter().map(|s| s.damage_dealt).sum();
-        let total_vision: i32 = stats.iter().map(|s| s.vision_score).sum();
-
-        let avg_kills = total_kills as f64 / total_games as f64;
-        let avg_deaths = total_deaths as f64 / total_games as f64;
-        let avg_assists = total_assists as f64 / total_games as f64;
-        let avg_cs = total_cs as f64 / total_games as f64;
-        let avg_damage_dealt = total_damage as f64 / total_games as f64;
-        let avg_vision_score = total_vision as f64 / total_games as f64;
-
-        let kda = if avg_deaths == 0.0 {
-            (avg_kills + avg_assists) * 10.0 // Perfect KDA
-        } else {
-            (avg_kills + avg_assists) / avg_deaths
-        };
+        let mut totals = StatTotals::default();
+        for game in games {
+            totals.add(game);
+        }
+        let summary = |metric: fn(&GameSample) -> f64| MetricSummary::from_values(games.iter().map(metric));
 
-        Ok(Self {
-            total_games,
-            wins,
-            losses,
-            winrate,
-            avg_kills,
-            avg_deaths,
-            avg_assists,
-            kda,
-            avg_cs,
-            avg_damage_dealt,
-            avg_vision_score,
-        })
+        Self {
+            total_games: totals.games,
+            wins: totals.wins,
+            losses: totals.games - totals.wins,
+            winrate: totals.winrate(),
+            avg_kills: totals.per_game(totals.kills),
+            avg_deaths: totals.per_game(totals.deaths),
+            avg_assists: totals.per_game(totals.assists),
+            kda: totals.kda(),
+            avg_cs: totals.per_game(totals.cs),
+            avg_damage_dealt: totals.per_game(totals.damage_dealt),
+            avg_vision_score: totals.per_game(totals.vision_score),
+            cs_per_min: summary(|g| g.cs as f64 / g.minutes()),
+            damage_per_min: summary(|g| g.damage_dealt as f64 / g.minutes()),
+            gold_per_min: summary(|g| g.gold_earned as f64 / g.minutes()),
+            vision_per_min: summary(|g| g.vision_score as f64 / g.minutes()),
+            kill_participation: summary(GameSample::kill_participation),
+            damage_share: summary(GameSample::damage_share),
+        }
     }
 }
 
-// Ranked Stats Cache
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
-pub struct RankedStatsCache {
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountStats {
     pub puuid: String,
-    pub tier: Option<String>,
-    pub rank_value: Option<String>,
-    pub league_points: Option<i32>,
-    pub wins: Option<i32>,
-    pub losses: Option<i32>,
-    pub queue_type: String,
-    pub cached_at: i64,
+    pub stats: PlayerStats,
 }
 
-impl RankedStatsCache {
-    pub async fn upsert(pool: &SqlitePool, puuid: &str, stats: Option<&serde_json::Value>) -> Result<()> {
-        let now = Utc::now().timestamp();
+/// `PlayerStats` over the last games of every account of a group
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupStats {
+    pub group: String,
+    pub combined: PlayerStats,
+    pub accounts: Vec<AccountStats>,
+}
 
-        if let Some(ranked_data) = stats {
-            // Parse ranked data if available
-            let tier = ranked_data.get("tier").and_then(|v| v.as_str()).map(String::from);
-            let rank = ranked_data.get("rank").and_then(|v| v.as_str()).map(String::from);
-            let lp = ranked_data.get("leaguePoints").and_then(|v| v.as_i64()).map(|v| v as i32);
-            let wins = ranked_data.get("wins").and_then(|v| v.as_i64()).map(|v| v as i32);
-            let losses = ranked_data.get("losses").and_then(|v| v.as_i64()).map(|v| v as i32);
-            let queue = ranked_data.get("queueType")
-                .and_then(|v| v.as_str())
-                .unwrap_or("RANKED_SOLO_5x5")
-                .to_string();
-
-            sqlx::query(
-                r#"
-                INSERT INTO ranked_stats_cache (puuid, tier, rank_value, league_points, wins, losses, queue_type, cached_at)
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
-                ON CONFLICT(puuid) DO UPDATE SET
-                    tier = excluded.tier,
-                    rank_value = excluded.rank_value,
-                    league_points = excluded.league_points,
-                    wins = excluded.wins,
-                    losses = excluded.losses,
-                    queue_type = excluded.queue_type,
-                    cached_at = excluded.cached_at
-                "#,
-            )
-            .bind(puuid)
-            .bind(tier)
-            .bind(rank)
-            .bind(lp)
-            .bind(wins)
-            .bind(losses)
-            .bind(queue)
-            .bind(now)
-            .execute(pool)
-            .await?;
+impl GroupStats {
+    pub async fn calculate(pool: &SqlitePool, group: &str, queue: Option<QueueType>) -> Result<Self> {
+        let mut combined = Vec::new();
+        let mut accounts = Vec::new();
+
+        for puuid in TrackedAccount::group_members(pool, group).await? {
+            let games = PlayerStats::recent_games(pool, &puuid, queue).await?;
+            accounts.push(AccountStats { stats: PlayerStats::from_games(&games), puuid });
+            combined.extend(games);
         }
 
-        Ok(())
+        Ok(Self {
+            group: group.to_string(),
+            combined: PlayerStats::from_games(&combined),
+            accounts,
+        })
     }
+}
 
-    pub async fn get(pool: &SqlitePool, puuid: &str) -> Result<Option<Self>> {
-        let now = Utc::now().timestamp();
-        let cache_cutoff = now - CACHE_TTL_SECONDS;
+/// (kills + assists) / deaths, deathless games count as ten times the takedowns
+pub fn kda_ratio(kills: f64, deaths: f64, assists: f64) -> f64 {
+    if deaths == 0.0 {
+        (kills + assists) * 10.0
+    } else {
+        (kills + assists) / deaths
+    }
+}
+
+/// Filters shared by the aggregated stats queries
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsFilter {
+    pub queue: Option<QueueType>,
+    /// TOP, JUNGLE, MIDDLE, BOTTOM or UTILITY
+    pub role: Option<String>,
+    /// Epoch seconds, inclusive
+    pub start_time: Option<i64>,
+    /// Epoch seconds, exclusive
+    pub end_time: Option<i64>,
+    /// "15.20" matches every 15.20.x build
+    pub patch: Option<String>,
+}
+
+impl StatsFilter {
+    fn queue_id(&self) -> Option<i32> {
+        self.queue.map(QueueType::id)
+    }
 
-        let cached = sqlx::query_as::<_, Self>(
-            "SELECT * FROM ranked_stats_cache WHERE puuid = ? AND cached_at > ?"
+    /// `game_creation` bounds in milliseconds
+    fn creation_range(&self) -> (i64, i64) {
+        (
+            self.start_time.map_or(0, |t| t * 1000),
+            self.end_time.map_or(i64::MAX, |t| t * 1000),
         )
-        .bind(puuid)
-        .bind(cache_cutoff)
-        .fetch_optional(pool)
-        .await?;
+    }
 
-        Ok(cached)
+    /// LIKE pattern of `game_version`, with `\` as the escape character
+    fn patch_pattern(&self) -> Option<String> {
+        self.patch.as_ref().map(|patch| {
+            let escaped = patch.trim_end_matches('.')
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            format!("{}.%", escaped)
+        })
     }
 }
 
-// Match Cache Metadata
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
-pub struct MatchCacheMetadata {
-    pub puuid: String,
-    pub last_match_id: Option<String>,
-    pub last_fetched: i64,
-    pub total_cached: i32,
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChampionStatsQuery {
+    #[serde(flatten)]
+    pub filter: StatsFilter,
+    /// One row per champion and role
+    #[serde(default)]
+    pub by_role: bool,
+    /// One row per champion and queue
+    #[serde(default)]
+    pub by_queue: bool,
 }
 
-impl MatchCacheMetadata {
-    pub async fn upsert(pool: &SqlitePool, puuid: &str, last_match_id: Option<&str>, total: i32) -> Result<()> {
-        let now = Utc::now().timestamp();
+#[derive(FromRow)]
+struct ChampionStatsRow {
+    champion_id: i32,
+    champion_name: String,
+    role: Option<String>,
+    queue_id: Option<i32>,
+    games: i64,
+    wins: i64,
+    kills: i64,
+    deaths: i64,
+    assists: i64,
+    cs: i64,
+    damage_dealt: i64,
+    vision_score: i64,
+    seconds_played: i64,
+    gold_share: f64,
+    last_played: i64,
+}
 
-        sqlx::query(
-            r#"
-            INSERT INTO match_cache_metadata (puuid, last_match_id, last_fetched, total_cached)
-            VALUES (?, ?, ?, ?)
-            ON CONFLICT(puuid) DO UPDATE SET
-                last_match_id = excluded.last_match_id,
-                last_fetched = excluded.last_fetched,
-                total_cached = excluded.total_cached
-            "#,
-        )
-        .bind(puuid)
-        .bind(last_match_id)
-        .bind(now)
-        .bind(total)
-        .execute(pool)
-        .await?;
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChampionStats {
+    pub champion_id: i32,
+    pub champion_name: String,
+    /// Set when grouping by role
+    pub role: Option<String>,
+    /// Set when grouping by queue
+    pub queue_id: Option<i32>,
+    pub games: i32,
+    pub wins: i32,
+    pub losses: i32,
+    pub winrate: f64,
+    pub avg_kills: f64,
+    pub avg_deaths: f64,
+    pub avg_assists: f64,
+    pub kda: f64,
+    pub cs_per_min: f64,
+    pub damage_per_min: f64,
+    pub vision_per_min: f64,
+    /// Average share of the team's gold, 0 to 1
+    pub gold_share: f64,
+    /// `game_creation` (epoch milliseconds) of the latest game
+    pub last_played: i64,
+}
 
-        Ok(())
+impl From<ChampionStatsRow> for ChampionStats {
+    fn from(row: ChampionStatsRow) -> Self {
+        let games = row.games as f64;
+        let minutes = (row.seconds_played as f64 / 60.0).max(1.0);
+        let avg_kills = row.kills as f64 / games;
+        let avg_deaths = row.deaths as f64 / games;
+        let avg_assists = row.assists as f64 / games;
+
+        Self {
+            champion_id: row.champion_id,
+            champion_name: row.champion_name,
+            role: row.role,
+            queue_id: row.queue_id,
+            games: row.games as i32,
+            wins: row.wins as i32,
+            losses: (row.games - row.wins) as i32,
+            winrate: row.wins as f64 / games * 100.0,
+            avg_kills,
+            avg_deaths,
+            avg_assists,
+            kda: kda_ratio(avg_kills, avg_deaths, avg_assists),
+            cs_per_min: row.cs as f64 / minutes,
+            damage_per_min: row.damage_dealt as f64 / minutes,
+            vision_per_min: row.vision_score as f64 / minutes,
+            gold_share: row.gold_share,
+            last_played: row.last_played,
+        }
     }
+}
 
-    pub async fn get(pool: &SqlitePool, puuid: &str) -> Result<Option<Self>> {
-        let metadata = sqlx::query_as::<_, Self>(
-            "SELECT * FROM match_cache_metadata WHERE puuid = ?"
+impl ChampionStats {
+    /// Most played first
+    pub async fn query(pool: &SqlitePool, puuid: &str, query: &ChampionStatsQuery) -> Result<Vec<Self>> {
+        let filter = &query.filter;
+        let (created_after, created_before) = filter.creation_range();
+
+        let rows = sqlx::query_as::<_, ChampionStatsRow>(
+            r#"
+            SELECT
+                ps.champion_id,
+                MAX(ps.champion_name) AS champion_name,
+                CASE WHEN ? THEN ps.role END AS role,
+                CASE WHEN ? THEN m.queue_id END AS queue_id,
+                COUNT(*) AS games,
+                SUM(ps.win) AS wins,
+                SUM(ps.kills) AS kills,
+                SUM(ps.deaths) AS deaths,
+                SUM(ps.assists) AS assists,
+                SUM(ps.cs) AS cs,
+                SUM(ps.damage_dealt) AS damage_dealt,
+                SUM(ps.vision_score) AS vision_score,
+                SUM(m.game_duration) AS seconds_played,
+                AVG(CAST(ps.gold_earned AS REAL) / MAX(team.gold, 1)) AS gold_share,
+                MAX(m.game_creation) AS last_played
+            FROM participant_stats ps
+            JOIN matches m ON m.match_id = ps.match_id
+            JOIN (
+                SELECT match_id, team_id, SUM(gold_earned) AS gold
+                FROM participant_stats
+                GROUP BY match_id, team_id
+            ) team ON team.match_id = ps.match_id AND team.team_id = ps.team_id
+            WHERE ps.puuid = ? AND ps.early_surrender = 0
+                AND (? IS NULL OR m.queue_id = ?)
+                AND (? IS NULL OR ps.role = ?)
+                AND m.game_creation >= ? AND m.game_creation < ?
+                AND (? IS NULL OR m.game_version LIKE ? ESCAPE '\')
+            GROUP BY ps.champion_id, 3, 4
+            ORDER BY games DESC, last_played DESC
+            "#,
         )
+        .bind(query.by_role)
+        .bind(query.by_queue)
         .bind(puuid)
-        .fetch_optional(pool)
+        .bind(filter.queue_id())
+        .bind(filter.queue_id())
+        .bind(&filter.role)
+        .bind(&filter.role)
+        .bind(created_after)
+        .bind(created_before)
+        .bind(filter.patch_pattern())
+        .bind(filter.patch_pattern())
+        .fetch_all(pool)
         .await?;
 
-        Ok(metadata)
+        Ok(rows.into_iter().map(Self::from).collect())
     }
 }
 
-impl DbMatch {
-    pub async fn get_by_match_id(pool: &SqlitePool, match_id: &str) -> Result<Option<Self>> {
-        let match_data = sqlx::query_as::<_, Self>(
-            "SELECT * FROM matches WHERE match_id = ?"
-        )
-        .bind(match_id)
-        .fetch_optional(pool)
-        .await?;
+/// Positions of Summoner's Rift, as reported by match-v5
+pub const ROLES: [&str; 5] = ["TOP", "JUNGLE", "MIDDLE", "BOTTOM", "UTILITY"];
+/// Games after which a role's recommendation is half trusted
+const ROLE_CONFIDENCE_GAMES: f64 = 10.0;
+/// Fewer games than this never make a role best or worst
+const MIN_ROLE_GAMES: i32 = 3;
 
-        Ok(match_data)
-    }
+/// One game of a player, with what the aggregates need from the rest of the team
+#[derive(Debug, Clone, FromRow)]
+pub struct GameSample {
+    pub match_id: String,
+    pub champion_id: i32,
+    pub role: String,
+    pub queue_id: i32,
+    pub win: i32,
+    pub kills: i32,
+    pub deaths: i32,
+    pub assists: i32,
+    pub cs: i32,
+    pub damage_dealt: i32,
+    pub vision_score: i32,
+    pub gold_earned: i32,
+    pub team_gold: i64,
+    pub team_kills: i64,
+    pub team_damage: i64,
+    /// Seconds
+    pub game_duration: i64,
+    pub game_creation: i64,
+}
 
-    pub async fn get_by_puuid(pool: &SqlitePool, puuid: &str, limit: i32) -> Result<Vec<Self>> {
-        let matches = sqlx::query_as::<_, Self>(
+impl GameSample {
+    /// Newest first, remakes excluded. `filter.role` applies to the stored role.
+    pub async fn fetch(pool: &SqlitePool, puuid: &str, filter: &StatsFilter) -> Result<Vec<Self>> {
+        let (created_after, created_before) = filter.creation_range();
+
+        let samples = sqlx::query_as::<_, Self>(
             r#"
-            SELECT DISTINCT m.* FROM matches m
-            JOIN participant_stats ps ON m.match_id = ps.match_id
-            WHERE ps.puuid = ?
+            SELECT
+                ps.match_id, ps.champion_id, ps.role, m.queue_id, ps.win,
+                ps.kills, ps.deaths, ps.assists, ps.cs, ps.damage_dealt, ps.vision_score, ps.gold_earned,
+                (SELECT SUM(t.gold_earned) FROM participant_stats t
+                    WHERE t.match_id = ps.match_id AND t.team_id = ps.team_id) AS team_gold,
+                (SELECT SUM(t.kills) FROM participant_stats t
+                    WHERE t.match_id = ps.match_id AND t.team_id = ps.team_id) AS team_kills,
+                (SELECT SUM(t.damage_dealt) FROM participant_stats t
+                    WHERE t.match_id = ps.match_id AND t.team_id = ps.team_id) AS team_damage,
+                m.game_duration, m.game_creation
+            FROM participant_stats ps
+            JOIN matches m ON m.match_id = ps.match_id
+            WHERE ps.puuid = ? AND ps.early_surrender = 0
+                AND (? IS NULL OR m.queue_id = ?)
+                AND (? IS NULL OR ps.role = ?)
+                AND m.game_creation >= ? AND m.game_creation < ?
+                AND (? IS NULL OR m.game_version LIKE ? ESCAPE '\')
             ORDER BY m.game_creation DESC
-            LIMIT ?
             "#,
         )
         .bind(puuid)
-        .bind(limit)
+        .bind(filter.queue_id())
+        .bind(filter.queue_id())
+        .bind(&filter.role)
+        .bind(&filter.role)
+        .bind(created_after)
+        .bind(created_before)
+        .bind(filter.patch_pattern())
+        .bind(filter.patch_pattern())
         .fetch_all(pool)
         .await?;
 
-        Ok(matches)
+        Ok(samples)
+    }
+
+    pub fn gold_share(&self) -> f64 {
+        self.gold_earned as f64 / self.team_gold.max(1) as f64
+    }
+
+    /// Share of the team's kills the player took part in
+    pub fn kill_participation(&self) -> f64 {
+        (self.kills + self.assists) as f64 / self.team_kills.max(1) as f64
+    }
+
+    pub fn damage_share(&self) -> f64 {
+        self.damage_dealt as f64 / self.team_damage.max(1) as f64
+    }
+
+    fn minutes(&self) -> f64 {
+        (self.game_duration as f64 / 60.0).max(1.0)
+    }
+}
+
+/// Running sums over a set of games
+#[derive(Debug, Clone, Copy, Default)]
+struct StatTotals {
+    games: i32,
+    wins: i32,
+    kills: i64,
+    deaths: i64,
+    assists: i64,
+    cs: i64,
+    damage_dealt: i64,
+    vision_score: i64,
+    seconds: i64,
+    gold_share: f64,
+}
+
+impl StatTotals {
+    fn add(&mut self, game: &GameSample) {
+        self.games += 1;
+        self.wins += game.win;
+        self.kills += game.kills as i64;
+        self.deaths += game.deaths as i64;
+        self.assists += game.assists as i64;
+        self.cs += game.cs as i64;
+        self.damage_dealt += game.damage_dealt as i64;
+        self.vision_score += game.vision_score as i64;
+        self.seconds += game.game_duration;
+        self.gold_share += game.gold_share();
+    }
+
+    fn per_game(&self, total: i64) -> f64 {
+        total as f64 / self.games.max(1) as f64
+    }
+
+    fn per_min(&self, total: i64) -> f64 {
+        total as f64 / (self.seconds as f64 / 60.0).max(1.0)
+    }
+
+    fn winrate(&self) -> f64 {
+        self.wins as f64 / self.games.max(1) as f64 * 100.0
+    }
+
+    fn kda(&self) -> f64 {
+        kda_ratio(self.per_game(self.kills), self.per_game(self.deaths), self.per_game(self.assists))
+    }
+
+    fn gold_share(&self) -> f64 {
+        self.gold_share / self.games.max(1) as f64
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoleStats {
+    pub role: String,
+    pub games: i32,
+    pub wins: i32,
+    pub winrate: f64,
+    pub kda: f64,
+    pub cs_per_min: f64,
+    pub damage_per_min: f64,
+    pub vision_per_min: f64,
+    pub gold_share: f64,
+    /// Differences with the player's average over every role, None when the role wasn't played
+    pub winrate_delta: Option<f64>,
+    pub kda_delta: Option<f64>,
+    pub cs_per_min_delta: Option<f64>,
+    pub damage_per_min_delta: Option<f64>,
+    pub vision_per_min_delta: Option<f64>,
+    pub gold_share_delta: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoleRecommendation {
+    pub role: String,
+    pub games: i32,
+    /// Winrate pulled towards the player's average, more so with few games
+    pub score: f64,
+    /// 0 to 1, grows with the number of games
+    pub confidence: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoleBreakdown {
+    /// Every role in `ROLES` order, including unplayed ones
+    pub roles: Vec<RoleStats>,
+    /// Played roles, best first
+    pub ranking: Vec<RoleRecommendation>,
+    pub best: Option<RoleRecommendation>,
+    /// Only set when a second role has enough games
+    pub worst: Option<RoleRecommendation>,
+}
+
+impl RoleBreakdown {
+    /// Games outside the five positions (ARAM, arena...) are left out. `filter.role` is ignored.
+    pub async fn for_puuid(pool: &SqlitePool, puuid: &str, filter: &StatsFilter) -> Result<Self> {
+        let filter = StatsFilter { role: None, ..filter.clone() };
+        let samples = GameSample::fetch(pool, puuid, &filter).await?;
+
+        let mut overall = StatTotals::default();
+        let mut per_role = [StatTotals::default(); ROLES.len()];
+        for game in &samples {
+            if let Some(index) = ROLES.iter().position(|role| *role == game.role) {
+                per_role[index].add(game);
+                overall.add(game);
+            }
+        }
+
+        let roles: Vec<RoleStats> = ROLES.iter().zip(per_role.iter())
+            .map(|(role, totals)| {
+                let delta = |value: f64, average: f64| (totals.games > 0).then_some(value - average);
+                RoleStats {
+                    role: role.to_string(),
+                    games: totals.games,
+                    wins: totals.wins,
+                    winrate: totals.winrate(),
+                    kda: totals.kda(),
+                    cs_per_min: totals.per_min(totals.cs),
+                    damage_per_min: totals.per_min(totals.damage_dealt),
+                    vision_per_min: totals.per_min(totals.vision_score),
+                    gold_share: totals.gold_share(),
+                    winrate_delta: delta(totals.winrate(), overall.winrate()),
+                    kda_delta: delta(totals.kda(), overall.kda()),
+                    cs_per_min_delta: delta(totals.per_min(totals.cs), overall.per_min(overall.cs)),
+                    damage_per_min_delta: delta(totals.per_min(totals.damage_dealt), overall.per_min(overall.damage_dealt)),
+                    vision_per_min_delta: delta(totals.per_min(totals.vision_score), overall.per_min(overall.vision_score)),
+                    gold_share_delta: delta(totals.gold_share(), overall.gold_share()),
+                }
+            })
+            .collect();
+
+        let average = overall.winrate();
+        let mut ranking: Vec<RoleRecommendation> = roles.iter()
+            .filter(|role| role.games > 0)
+            .map(|role| {
+                let games = role.games as f64;
+                RoleRecommendation {
+                    role: role.role.clone(),
+                    games: role.games,
+                    score: (role.winrate * games + average * ROLE_CONFIDENCE_GAMES) / (games + ROLE_CONFIDENCE_GAMES),
+                    confidence: games / (games + ROLE_CONFIDENCE_GAMES),
+                }
+            })
+            .collect();
+        ranking.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        let mut eligible = ranking.iter().filter(|r| r.games >= MIN_ROLE_GAMES);
+        let best = eligible.next().cloned();
+        let worst = eligible.next_back().cloned();
+
+        Ok(Self { roles, ranking, best, worst })
+    }
+}
+
+// Ranked snapshots, one row per queue each time the rank changed
+const TIERS: [&str; 10] = [
+    "IRON", "BRONZE", "SILVER", "GOLD", "PLATINUM", "EMERALD", "DIAMOND", "MASTER", "GRANDMASTER", "CHALLENGER",
+];
+const DIVISIONS: [&str; 4] = ["IV", "III", "II", "I"];
+/// Master and above share a single LP ladder
+const APEX_TIER_INDEX: usize = 7;
+
+/// Queue ID of the matches played in a league-v4 queue
+pub fn queue_id_for_queue_type(queue_type: &str) -> Option<i32> {
+    match queue_type {
+        "RANKED_SOLO_5x5" => Some(420),
+        "RANKED_FLEX_SR" => Some(440),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct DbRankedSnapshot {
+    pub puuid: String,
+    pub queue_type: String,
+    pub taken_at: i64,
+    pub tier: String,
+    pub division: String,
+    pub league_points: i32,
+    pub wins: i32,
+    pub losses: i32,
+}
+
+impl DbRankedSnapshot {
+    /// Read a league-v4 entry, `None` for entries without a rank
+    pub fn from_league_entry(puuid: &str, entry: &serde_json::Value, taken_at: i64) -> Option<Self> {
+        Some(Self {
+            puuid: puuid.to_string(),
+            queue_type: entry.get("queueType")?.as_str()?.to_string(),
+            taken_at,
+            tier: entry.get("tier")?.as_str()?.to_string(),
+            division: entry.get("rank")?.as_str()?.to_string(),
+            league_points: entry.get("leaguePoints")?.as_i64()? as i32,
+            wins: entry.get("wins")?.as_i64()? as i32,
+            losses: entry.get("losses")?.as_i64()? as i32,
+        })
+    }
+
+    pub fn games(&self) -> i32 {
+        self.wins + self.losses
+    }
+
+    /// Position on a single scale across tiers: 100 per division, Master+ continues from Diamond I
+    pub fn ladder_points(&self) -> i32 {
+        let tier = TIERS.iter().position(|t| *t == self.tier).unwrap_or(0);
+        if tier >= APEX_TIER_INDEX {
+            return (APEX_TIER_INDEX * 400) as i32 + self.league_points;
+        }
+
+        let division = DIVISIONS.iter().position(|d| *d == self.division).unwrap_or(0);
+        (tier * 400 + division * 100) as i32 + self.league_points
+    }
+
+    fn same_standing(&self, other: &Self) -> bool {
+        self.tier == other.tier
+            && self.division == other.division
+            && self.league_points == other.league_points
+            && self.wins == other.wins
+            && self.losses == other.losses
+    }
+
+    /// Store the snapshot unless the rank didn't change since the last one of the queue
+    pub async fn insert_if_changed(&self, pool: &SqlitePool) -> Result<bool> {
+        let latest = Self::latest(pool, &self.puuid, &self.queue_type).await?;
+        if latest.is_some_and(|latest| latest.same_standing(self)) {
+            return Ok(false);
+        }
+
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO ranked_snapshots (puuid, queue_type, taken_at, tier, division, league_points, wins, losses)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&self.puuid)
+        .bind(&self.queue_type)
+        .bind(self.taken_at)
+        .bind(&self.tier)
+        .bind(&self.division)
+        .bind(self.league_points)
+        .bind(self.wins)
+        .bind(self.losses)
+        .execute(pool)
+        .await?;
+
+        Ok(true)
+    }
+
+    /// Snapshot every queue of a league-v4 response, returns how many changed
+    pub async fn store_league_entries(pool: &SqlitePool, puuid: &str, entries: &[serde_json::Value]) -> Result<usize> {
+        let now = Utc::now().timestamp();
+        let mut stored = 0;
+
+        for snapshot in entries.iter().filter_map(|entry| Self::from_league_entry(puuid, entry, now)) {
+            if snapshot.insert_if_changed(pool).await? {
+                stored += 1;
+            }
+        }
+
+        Ok(stored)
+    }
+
+    pub async fn latest(pool: &SqlitePool, puuid: &str, queue_type: &str) -> Result<Option<Self>> {
+        let snapshot = sqlx::query_as::<_, Self>(
+            "SELECT * FROM ranked_snapshots WHERE puuid = ? AND queue_type = ? ORDER BY taken_at DESC LIMIT 1"
+        )
+        .bind(puuid)
+        .bind(queue_type)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(snapshot)
+    }
+
+    /// Oldest first
+    pub async fn history(pool: &SqlitePool, puuid: &str, queue_type: Option<&str>, since: Option<i64>) -> Result<Vec<Self>> {
+        let snapshots = sqlx::query_as::<_, Self>(
+            r#"
+            SELECT * FROM ranked_snapshots
+            WHERE puuid = ? AND (? IS NULL OR queue_type = ?) AND taken_at >= ?
+            ORDER BY taken_at ASC
+            "#,
+        )
+        .bind(puuid)
+        .bind(queue_type)
+        .bind(queue_type)
+        .bind(since.unwrap_or(0))
+        .fetch_all(pool)
+        .await?;
+
+        Ok(snapshots)
+    }
+}
+
+/// One point of the LP-over-time chart
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LpPoint {
+    pub taken_at: i64,
+    pub tier: String,
+    pub division: String,
+    pub league_points: i32,
+    pub ladder_points: i32,
+    pub wins: i32,
+    pub losses: i32,
+}
+
+impl LpPoint {
+    pub async fn series(pool: &SqlitePool, puuid: &str, queue_type: &str, since: Option<i64>) -> Result<Vec<Self>> {
+        let snapshots = DbRankedSnapshot::history(pool, puuid, Some(queue_type), since).await?;
+
+        Ok(snapshots.into_iter().map(|s| Self {
+            taken_at: s.taken_at,
+            ladder_points: s.ladder_points(),
+            tier: s.tier,
+            division: s.division,
+            league_points: s.league_points,
+            wins: s.wins,
+            losses: s.losses,
+        }).collect())
+    }
+}
+
+/// LP won or lost in a ranked match
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchLpChange {
+    pub match_id: String,
+    pub queue_type: String,
+    pub win: bool,
+    pub lp_change: i32,
+    pub tier: String,
+    pub division: String,
+    pub league_points: i32,
+}
+
+impl MatchLpChange {
+    /// Newest first. A match gets a change only when it is the single game played
+    /// between two consecutive snapshots, otherwise the LP can't be attributed.
+    pub async fn for_puuid(pool: &SqlitePool, puuid: &str, queue_type: &str, limit: usize) -> Result<Vec<Self>> {
+        let Some(queue_id) = queue_id_for_queue_type(queue_type) else {
+            return Ok(Vec::new());
+        };
+
+        let snapshots = DbRankedSnapshot::history(pool, puuid, Some(queue_type), None).await?;
+
+        // (match_id, game end in seconds, win)
+        let matches = sqlx::query_as::<_, (String, i64, i32)>(
+            r#"
+            SELECT m.match_id, m.game_creation / 1000 + m.game_duration, ps.win
+            FROM matches m
+            JOIN participant_stats ps ON m.match_id = ps.match_id
+            WHERE ps.puuid = ? AND m.queue_id = ?
+            ORDER BY m.game_creation ASC
+            "#,
+        )
+        .bind(puuid)
+        .bind(queue_id)
+        .fetch_all(pool)
+        .await?;
+
+        let mut changes = Vec::new();
+        for pair in snapshots.windows(2) {
+            let (before, after) = (&pair[0], &pair[1]);
+            if after.games() != before.games() + 1 {
+                continue;
+            }
+
+            let mut played = matches.iter()
+                .filter(|(_, ended_at, _)| *ended_at > before.taken_at && *ended_at <= after.taken_at);
+
+            if let (Some((match_id, _, win)), None) = (played.next(), played.next()) {
+                changes.push(Self {
+                    match_id: match_id.clone(),
+                    queue_type: queue_type.to_string(),
+                    win: *win == 1,
+                    lp_change: after.ladder_points() - before.ladder_points(),
+                    tier: after.tier.clone(),
+                    division: after.division.clone(),
+                    league_points: after.league_points,
+                });
+            }
+        }
+
+        changes.reverse();
+        changes.truncate(limit);
+
+        Ok(changes)
+    }
+}
+
+/// Highest rank reached in a queue during a season
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeasonPeak {
+    /// As reported by the game version of the matches, e.g. 15 for "15.20.712.4853"
+    pub season: i32,
+    pub queue_type: String,
+    pub tier: String,
+    pub division: String,
+    pub league_points: i32,
+    pub ladder_points: i32,
+    pub reached_at: i64,
+}
+
+impl SeasonPeak {
+    /// Newest season first. A snapshot belongs to the season of the last match played before it,
+    /// or of the first cached match when it is older than all of them.
+    pub async fn for_puuid(pool: &SqlitePool, puuid: &str) -> Result<Vec<Self>> {
+        let snapshots = DbRankedSnapshot::history(pool, puuid, None, None).await?;
+
+        // (game end in seconds, game version)
+        let matches = sqlx::query_as::<_, (i64, String)>(
+            r#"
+            SELECT m.game_creation / 1000 + m.game_duration, m.game_version
+            FROM matches m
+            JOIN participant_stats ps ON m.match_id = ps.match_id
+            WHERE ps.puuid = ?
+            ORDER BY m.game_creation ASC
+            "#,
+        )
+        .bind(puuid)
+        .fetch_all(pool)
+        .await?;
+
+        let seasons: Vec<(i64, i32)> = matches.into_iter()
+            .filter_map(|(ended_at, version)| Some((ended_at, version.split('.').next()?.parse().ok()?)))
+            .collect();
+
+        let mut peaks: Vec<Self> = Vec::new();
+        for snapshot in snapshots {
+            let season = seasons.iter().rev()
+                .find(|(ended_at, _)| *ended_at <= snapshot.taken_at)
+                .or(seasons.first())
+                .map(|(_, season)| *season);
+            let Some(season) = season else {
+                continue;
+            };
+
+            let ladder_points = snapshot.ladder_points();
+            let existing = peaks.iter_mut()
+                .find(|peak| peak.season == season && peak.queue_type == snapshot.queue_type);
+
+            match existing {
+                Some(peak) if peak.ladder_points >= ladder_points => {}
+                Some(peak) => *peak = Self::from_snapshot(season, snapshot, ladder_points),
+                None => peaks.push(Self::from_snapshot(season, snapshot, ladder_points)),
+            }
+        }
+
+        peaks.sort_by(|a, b| b.season.cmp(&a.season).then_with(|| a.queue_type.cmp(&b.queue_type)));
+
+        Ok(peaks)
+    }
+
+    fn from_snapshot(season: i32, snapshot: DbRankedSnapshot, ladder_points: i32) -> Self {
+        Self {
+            season,
+            queue_type: snapshot.queue_type,
+            tier: snapshot.tier,
+            division: snapshot.division,
+            league_points: snapshot.league_points,
+            ladder_points,
+            reached_at: snapshot.taken_at,
+        }
+    }
+}
+
+// Match Cache Metadata
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MatchCacheMetadata {
+    pub puuid: String,
+    pub last_match_id: Option<String>,
+    pub last_fetched: i64,
+    pub total_cached: i32,
+}
+
+impl MatchCacheMetadata {
+    pub async fn upsert(pool: &SqlitePool, puuid: &str, last_match_id: Option<&str>, total: i32) -> Result<()> {
+        let now = Utc::now().timestamp();
+
+        sqlx::query(
+            r#"
+            INSERT INTO match_cache_metadata (puuid, last_match_id, last_fetched, total_cached)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(puuid) DO UPDATE SET
+                last_match_id = excluded.last_match_id,
+                last_fetched = excluded.last_fetched,
+                total_cached = excluded.total_cached
+            "#,
+        )
+        .bind(puuid)
+        .bind(last_match_id)
+        .bind(now)
+        .bind(total)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get(pool: &SqlitePool, puuid: &str) -> Result<Option<Self>> {
+        let metadata = sqlx::query_as::<_, Self>(
+            "SELECT * FROM match_cache_metadata WHERE puuid = ?"
+        )
+        .bind(puuid)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(metadata)
+    }
+}
+
+impl DbMatch {
+    pub async fn get_by_match_id(pool: &SqlitePool, match_id: &str) -> Result<Option<Self>> {
+        let match_data = sqlx::query_as::<_, Self>(
+            "SELECT * FROM matches WHERE match_id = ?"
+        )
+        .bind(match_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(match_data)
+    }
+
+    pub async fn get_by_puuid(pool: &SqlitePool, puuid: &str, limit: i32) -> Result<Vec<Self>> {
+        let matches = sqlx::query_as::<_, Self>(
+            r#"
+            SELECT DISTINCT m.* FROM matches m
+            JOIN participant_stats ps ON m.match_id = ps.match_id
+            WHERE ps.puuid = ?
+            ORDER BY m.game_creation DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(puuid)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(matches)
+    }
+}
+
+// Match-v5 timeline, cached next to `matches.data`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DbMatchTimeline {
+    pub match_id: String,
+    pub data: String, // JSON
+    pub created_at: i64,
+}
+
+impl DbMatchTimeline {
+    pub async fn insert(&self, pool: &SqlitePool) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO match_timelines (match_id, data, created_at)
+            VALUES (?, ?, ?)
+            "#,
+        )
+        .bind(&self.match_id)
+        .bind(&self.data)
+        .bind(self.created_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_by_match_id(pool: &SqlitePool, match_id: &str) -> Result<Option<Self>> {
+        let timeline = sqlx::query_as::<_, Self>(
+            "SELECT * FROM match_timelines WHERE match_id = ?"
+        )
+        .bind(match_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(timeline)
+    }
+}
+
+// Champ select draft timeline
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DbDraftTimeline {
+    /// Upper case like match-v5 IDs, e.g. "EUW1". Empty for drafts saved before it was stored.
+    pub platform_id: String,
+    pub game_id: i64,
+    pub data: String, // JSON
+    pub created_at: i64,
+}
+
+impl DbDraftTimeline {
+    pub async fn insert(&self, pool: &SqlitePool) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO draft_timelines (platform_id, game_id, data, created_at)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(&self.platform_id)
+        .bind(self.game_id)
+        .bind(&self.data)
+        .bind(self.created_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Find the draft of a match by its match-v5 ID (e.g. `EUW1_1234567890`).
+    /// Drafts saved without a platform only match on the game ID.
+    pub async fn get_by_match_id(pool: &SqlitePool, match_id: &str) -> Result<Option<Self>> {
+        let Some((platform_id, game_id)) = match_id.split_once('_') else {
+            return Ok(None);
+        };
+        let Ok(game_id) = game_id.parse::<i64>() else {
+            return Ok(None);
+        };
+
+        let draft = sqlx::query_as::<_, Self>(
+            r#"
+            SELECT * FROM draft_timelines
+            WHERE game_id = ? AND platform_id IN (?, '')
+            ORDER BY platform_id DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(game_id)
+        .bind(platform_id.to_uppercase())
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(draft)
+    }
+}
+
+// Key/value settings
+pub struct DbSetting;
+
+impl DbSetting {
+    pub async fn get(pool: &SqlitePool, key: &str) -> Result<Option<String>> {
+        let value = sqlx::query_as::<_, (String,)>(
+            "SELECT value FROM settings WHERE key = ?"
+        )
+        .bind(key)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(value.map(|(value,)| value))
+    }
+
+    pub async fn set(pool: &SqlitePool, key: &str, value: &str) -> Result<()> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO settings (key, value, updated_at) VALUES (?, ?, ?)"
+        )
+        .bind(key)
+        .bind(value)
+        .bind(Utc::now().timestamp())
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, key: &str) -> Result<()> {
+        sqlx::query("DELETE FROM settings WHERE key = ?")
+            .bind(key)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+// Auto-accept decisions
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DbReadyCheckDecision {
+    pub id: Option<i64>,
+    pub queue_id: Option<i32>,
+    pub action: String, // "accept", "decline", "ignore"
+    /// Why the pop wasn't accepted, or why answering it failed
+    pub reason: Option<String>,
+    pub delay_ms: i64,
+    pub decided_at: i64,
+}
+
+impl DbReadyCheckDecision {
+    pub async fn insert(&self, pool: &SqlitePool) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO ready_check_log (queue_id, action, reason, delay_ms, decided_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(self.queue_id)
+        .bind(&self.action)
+        .bind(&self.reason)
+        .bind(self.delay_ms)
+        .bind(self.decided_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_recent(pool: &SqlitePool, limit: i32) -> Result<Vec<Self>> {
+        let decisions = sqlx::query_as::<_, Self>(
+            "SELECT * FROM ready_check_log ORDER BY decided_at DESC LIMIT ?"
+        )
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(decisions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::riot_api::mock_server::MOCK_PUUID;
+
+    const MATCH_FIXTURE: &str = include_str!("../../tests/fixtures/riot/match.json");
+
+    async fn open_db() -> (Database, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(Some(dir.path().join("galpha.db"))).await.unwrap();
+        (db, dir)
+    }
+
+    async fn participant_count(pool: &SqlitePool) -> i64 {
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM participant_stats")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        count
+    }
+
+    #[tokio::test]
+    async fn stores_every_participant() {
+        let (db, _dir) = open_db().await;
+        let details: MatchDetails = serde_json::from_str(MATCH_FIXTURE).unwrap();
+
+        DbMatch::store(db.pool(), &details).await.unwrap();
+        DbMatch::store(db.pool(), &details).await.unwrap();
+        assert_eq!(participant_count(db.pool()).await, 10);
+
+        let own = DbParticipantStat::get_by_puuid(db.pool(), MOCK_PUUID, 10).await.unwrap();
+        assert_eq!(own.len(), 1);
+        assert_eq!(own[0].champion_name, "Ahri");
+        assert_eq!(own[0].role, "MIDDLE");
+        assert_eq!(own[0].cs, 239);
+        assert_eq!(own[0].damage_dealt, 27811);
+
+        let stats = PlayerStats::calculate(db.pool(), MOCK_PUUID, None).await.unwrap();
+        assert_eq!(stats.total_games, 1);
+        assert_eq!(stats.wins, 1);
+        assert_eq!(stats.avg_kills, 9.0);
+
+        let matches = DbMatch::get_by_puuid(db.pool(), MOCK_PUUID, 10).await.unwrap();
+        assert_eq!(matches.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rebuilds_participants_from_match_json() {
+        let (db, _dir) = open_db().await;
+        let details: MatchDetails = serde_json::from_str(MATCH_FIXTURE).unwrap();
+
+        DbMatch::from_details(&details).insert(db.pool()).await.unwrap();
+        assert_eq!(participant_count(db.pool()).await, 0);
+
+        let mut tx = db.pool().begin().await.unwrap();
+        let rebuilt = DbParticipantStat::rebuild_from_matches(&mut tx).await.unwrap();
+        tx.commit().await.unwrap();
+        assert_eq!(rebuilt, 1);
+        assert_eq!(participant_count(db.pool()).await, 10);
+    }
+
+    fn snapshot(taken_at: i64, tier: &str, division: &str, league_points: i32, wins: i32, losses: i32) -> DbRankedSnapshot {
+        DbRankedSnapshot {
+            puuid: MOCK_PUUID.to_string(),
+            queue_type: "RANKED_SOLO_5x5".to_string(),
+            taken_at,
+            tier: tier.to_string(),
+            division: division.to_string(),
+            league_points,
+            wins,
+            losses,
+        }
+    }
+
+    #[tokio::test]
+    async fn snapshots_each_queue_only_when_it_changes() {
+        let (db, _dir) = open_db().await;
+        let entries: Vec<serde_json::Value> =
+            serde_json::from_str(include_str!("../../tests/fixtures/riot/league-entries.json")).unwrap();
+
+        assert_eq!(DbRankedSnapshot::store_league_entries(db.pool(), MOCK_PUUID, &entries).await.unwrap(), 2);
+        assert_eq!(DbRankedSnapshot::store_league_entries(db.pool(), MOCK_PUUID, &entries).await.unwrap(), 0);
+
+        let solo = DbRankedSnapshot::latest(db.pool(), MOCK_PUUID, "RANKED_SOLO_5x5").await.unwrap().unwrap();
+        assert_eq!((solo.tier.as_str(), solo.division.as_str(), solo.league_points), ("EMERALD", "II", 47));
+        assert_eq!(solo.ladder_points(), 2247);
+
+        let flex = DbRankedSnapshot::latest(db.pool(), MOCK_PUUID, "RANKED_FLEX_SR").await.unwrap().unwrap();
+        assert_eq!(flex.tier, "PLATINUM");
+
+        assert_eq!(snapshot(0, "MASTER", "I", 120, 0, 0).ladder_points(), 2920);
+        assert_eq!(snapshot(0, "IRON", "IV", 0, 0, 0).ladder_points(), 0);
+    }
+
+    #[tokio::test]
+    async fn attributes_lp_to_matches_and_finds_peaks() {
+        let (db, _dir) = open_db().await;
+        let details: MatchDetails = serde_json::from_str(MATCH_FIXTURE).unwrap();
+        DbMatch::store(db.pool(), &details).await.unwrap();
+
+        // The fixture game ends at 1760657434
+        for s in [
+            snapshot(1760650000, "EMERALD", "III", 88, 60, 54),
+            snapshot(1760657500, "EMERALD", "II", 7, 61, 54),
+            snapshot(1760700000, "EMERALD", "II", 47, 63, 54),
+            snapshot(1760800000, "EMERALD", "II", 30, 63, 55),
+        ] {
+            assert!(s.insert_if_changed(db.pool()).await.unwrap());
+        }
+
+        let changes = MatchLpChange::for_puuid(db.pool(), MOCK_PUUID, "RANKED_SOLO_5x5", 10).await.unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].match_id, "EUW1_7212345678");
+        assert!(changes[0].win);
+        assert_eq!(changes[0].lp_change, 19);
+
+        let series = LpPoint::series(db.pool(), MOCK_PUUID, "RANKED_SOLO_5x5", Some(1760657500)).await.unwrap();
+        assert_eq!(series.iter().map(|p| p.ladder_points).collect::<Vec<_>>(), vec![2207, 2247, 2230]);
+
+        let peaks = SeasonPeak::for_puuid(db.pool(), MOCK_PUUID).await.unwrap();
+        assert_eq!(peaks.len(), 1);
+        assert_eq!(peaks[0].season, 15);
+        assert_eq!(peaks[0].league_points, 47);
+        assert_eq!(peaks[0].reached_at, 1760700000);
+
+        // Lower, but the first rank of the next season
+        let mut next_season = details.clone();
+        next_season.metadata.match_id = "EUW1_7312345678".to_string();
+        next_season.info.game_creation += 7 * 86_400_000;
+        next_season.info.game_version = "16.1.700.1234".to_string();
+        DbMatch::store(db.pool(), &next_season).await.unwrap();
+        assert!(snapshot(1761400000, "EMERALD", "IV", 0, 70, 60).insert_if_changed(db.pool()).await.unwrap());
+
+        let peaks = SeasonPeak::for_puuid(db.pool(), MOCK_PUUID).await.unwrap();
+        let seasons: Vec<(i32, i32)> = peaks.iter().map(|p| (p.season, p.ladder_points)).collect();
+        assert_eq!(seasons, vec![(16, 2000), (15, 2247)]);
+    }
+
+    #[tokio::test]
+    async fn aggregates_stats_per_champion() {
+        let (db, _dir) = open_db().await;
+        let details: MatchDetails = serde_json::from_str(MATCH_FIXTURE).unwrap();
+        DbMatch::store(db.pool(), &details).await.unwrap();
+
+        let stats = ChampionStats::query(db.pool(), MOCK_PUUID, &ChampionStatsQuery::default()).await.unwrap();
+        assert_eq!(stats.len(), 1);
+
+        let ahri = &stats[0];
+        assert_eq!((ahri.champion_name.as_str(), ahri.games, ahri.wins), ("Ahri", 1, 1));
+        assert_eq!(ahri.role, None);
+        assert_eq!(ahri.winrate, 100.0);
+        assert!((ahri.kda - 17.0 / 3.0).abs() < 1e-9);
+        assert!((ahri.cs_per_min - 239.0 / (1834.0 / 60.0)).abs() < 1e-9);
+        assert!((ahri.vision_per_min - 19.0 / (1834.0 / 60.0)).abs() < 1e-9);
+        assert!((ahri.gold_share - 13904.0 / 55012.0).abs() < 1e-9);
+        assert_eq!(ahri.last_played, 1760655600000);
+
+        let by_role = ChampionStatsQuery { by_role: true, by_queue: true, ..Default::default() };
+        let stats = ChampionStats::query(db.pool(), MOCK_PUUID, &by_role).await.unwrap();
+        assert_eq!(stats[0].role.as_deref(), Some("MIDDLE"));
+        assert_eq!(stats[0].queue_id, Some(420));
+
+        let filtered = |filter: StatsFilter| {
+            let query = ChampionStatsQuery { filter, ..Default::default() };
+            let pool = db.pool().clone();
+            async move { ChampionStats::query(&pool, MOCK_PUUID, &query).await.unwrap().len() }
+        };
+        assert_eq!(filtered(StatsFilter { patch: Some("15.20".into()), ..Default::default() }).await, 1);
+        assert_eq!(filtered(StatsFilter { patch: Some("15.2".into()), ..Default::default() }).await, 0);
+        // LIKE wildcards in the patch are matched literally
+        assert_eq!(filtered(StatsFilter { patch: Some("15%".into()), ..Default::default() }).await, 0);
+        assert_eq!(filtered(StatsFilter { patch: Some("1_.20".into()), ..Default::default() }).await, 0);
+        assert_eq!(filtered(StatsFilter { queue: Some(QueueType::RankedFlex), ..Default::default() }).await, 0);
+        assert_eq!(filtered(StatsFilter { role: Some("TOP".into()), ..Default::default() }).await, 0);
+        assert_eq!(filtered(StatsFilter { start_time: Some(1760655601), ..Default::default() }).await, 0);
+        assert_eq!(filtered(StatsFilter { end_time: Some(1760655601), ..Default::default() }).await, 1);
+    }
+
+    #[tokio::test]
+    async fn keeps_drafts_of_each_platform_apart() {
+        let (db, _dir) = open_db().await;
+        let draft = |platform_id: &str, data: &str| DbDraftTimeline {
+            platform_id: platform_id.to_string(),
+            game_id: 7212345678,
+            data: data.to_string(),
+            created_at: 1760000000,
+        };
+        draft("EUW1", r#"{"platform":"euw"}"#).insert(db.pool()).await.unwrap();
+        draft("NA1", r#"{"platform":"na"}"#).insert(db.pool()).await.unwrap();
+
+        let euw = DbDraftTimeline::get_by_match_id(db.pool(), "EUW1_7212345678").await.unwrap().unwrap();
+        assert_eq!(euw.data, r#"{"platform":"euw"}"#);
+        let na = DbDraftTimeline::get_by_match_id(db.pool(), "NA1_7212345678").await.unwrap().unwrap();
+        assert_eq!(na.data, r#"{"platform":"na"}"#);
+        assert!(DbDraftTimeline::get_by_match_id(db.pool(), "KR_7212345678").await.unwrap().is_none());
+
+        // Saved before the platform was stored
+        draft("", r#"{"platform":null}"#).insert(db.pool()).await.unwrap();
+        let legacy = DbDraftTimeline::get_by_match_id(db.pool(), "KR_7212345678").await.unwrap().unwrap();
+        assert_eq!(legacy.data, r#"{"platform":null}"#);
+        let euw = DbDraftTimeline::get_by_match_id(db.pool(), "EUW1_7212345678").await.unwrap().unwrap();
+        assert_eq!(euw.platform_id, "EUW1");
+    }
+
+    #[tokio::test]
+    async fn breaks_down_roles_and_recommends_one() {
+        let (db, _dir) = open_db().await;
+        let fixture: MatchDetails = serde_json::from_str(MATCH_FIXTURE).unwrap();
+
+        let games = [("MIDDLE", true), ("MIDDLE", true), ("MIDDLE", true), ("MIDDLE", false),
+            ("TOP", false), ("TOP", false), ("TOP", false), ("JUNGLE", true)];
+        for (i, (position, win)) in games.into_iter().enumerate() {
+            let mut details = fixture.clone();
+            details.metadata.match_id = format!("EUW1_72123456{:02}", i);
+            details.info.game_creation += i as i64 * 3_600_000;
+            details.info.participants[2].team_position = position.to_string();
+            details.info.participants[2].win = win;
+            DbMatch::store(db.pool(), &details).await.unwrap();
+        }
+
+        let breakdown = RoleBreakdown::for_puuid(db.pool(), MOCK_PUUID, &StatsFilter::default()).await.unwrap();
+
+        let roles: Vec<(&str, i32)> = breakdown.roles.iter().map(|r| (r.role.as_str(), r.games)).collect();
+        assert_eq!(roles, vec![("TOP", 3), ("JUNGLE", 1), ("MIDDLE", 4), ("BOTTOM", 0), ("UTILITY", 0)]);
+
+        let middle = &breakdown.roles[2];
+        assert_eq!(middle.winrate, 75.0);
+        assert_eq!(middle.winrate_delta, Some(25.0));
+        assert!(middle.kda_delta.unwrap().abs() < 1e-9);
+
+        let bottom = &breakdown.roles[3];
+        assert_eq!(bottom.winrate, 0.0);
+        assert_eq!(bottom.winrate_delta, None);
+        assert_eq!(bottom.gold_share_delta, None);
+
+        let ranking: Vec<&str> = breakdown.ranking.iter().map(|r| r.role.as_str()).collect();
+        assert_eq!(ranking, vec!["MIDDLE", "JUNGLE", "TOP"]);
+        assert_eq!(breakdown.best.as_ref().unwrap().role, "MIDDLE");
+        assert!((breakdown.best.as_ref().unwrap().confidence - 4.0 / 14.0).abs() < 1e-9);
+        assert_eq!(breakdown.worst.as_ref().unwrap().role, "TOP");
+
+        let stored = DbParticipantStat::get_by_puuid(db.pool(), MOCK_PUUID, 1).await.unwrap();
+        assert_eq!(stored[0].individual_position, "MIDDLE");
+    }
+
+    #[tokio::test]
+    async fn normalizes_player_stats_by_duration_without_remakes() {
+        let (db, _dir) = open_db().await;
+        let fixture: MatchDetails = serde_json::from_str(MATCH_FIXTURE).unwrap();
+        DbMatch::store(db.pool(), &fixture).await.unwrap();
+
+        let mut long_game = fixture.clone();
+        long_game.metadata.match_id = "EUW1_7212345601".into();
+        long_game.info.game_creation += 3_600_000;
+        long_game.info.game_duration *= 2;
+        DbMatch::store(db.pool(), &long_game).await.unwrap();
+
+        let mut remake = fixture.clone();
+        remake.metadata.match_id = "EUW1_7212345602".into();
+        remake.info.game_creation += 7_200_000;
+        remake.info.game_duration = 210;
+        for participant in &mut remake.info.participants {
+            participant.game_ended_in_early_surrender = true;
+            participant.win = false;
+        }
+        DbMatch::store(db.pool(), &remake).await.unwrap();
+
+        let stats = PlayerStats::calculate(db.pool(), MOCK_PUUID, None).await.unwrap();
+        assert_eq!(stats.total_games, 2);
+        assert_eq!(stats.losses, 0);
+        assert_eq!(stats.avg_cs, 239.0);
+
+        // Same totals over twice the time halve the rate
+        let short = 239.0 / (1834.0 / 60.0);
+        let long = short / 2.0;
+        assert!((stats.cs_per_min.mean - (short + long) / 2.0).abs() < 1e-9);
+        assert!((stats.cs_per_min.variance - (short - long).powi(2) / 2.0).abs() < 1e-9);
+        assert!((stats.gold_per_min.mean - 13904.0 / (1834.0 / 60.0) * 0.75).abs() < 1e-9);
+
+        let me = &fixture.info.participants[2];
+        let team = fixture.info.participants.iter().filter(|p| p.team_id == me.team_id);
+        let team_kills: i32 = team.clone().map(|p| p.kills).sum();
+        let team_damage: i32 = team.map(|p| p.total_damage_dealt_to_champions).sum();
+        assert!((stats.kill_participation.mean - 17.0 / team_kills as f64).abs() < 1e-9);
+        assert_eq!(stats.kill_participation.variance, 0.0);
+        assert!((stats.damage_share.mean - 27811.0 / team_damage as f64).abs() < 1e-9);
+
+        let flex = PlayerStats::calculate(db.pool(), MOCK_PUUID, Some(QueueType::RankedFlex)).await.unwrap();
+        assert_eq!(flex.total_games, 0);
+        assert_eq!(flex.cs_per_min.mean, 0.0);
+    }
+
+    #[tokio::test]
+    async fn tracks_accounts_in_groups() {
+        let (db, _dir) = open_db().await;
+        let details: MatchDetails = serde_json::from_str(MATCH_FIXTURE).unwrap();
+        DbMatch::store(db.pool(), &details).await.unwrap();
+
+        let smurf = details.info.participants[3].puuid.clone();
+        for (puuid, game_name) in [(MOCK_PUUID, "Galnir"), (smurf.as_str(), "Smurf")] {
+            DbSummoner {
+                puuid: puuid.to_string(),
+                game_name: game_name.to_string(),
+                tag_line: "EUW".to_string(),
+                summoner_id: None,
+                account_id: None,
+                summoner_level: None,
+                profile_icon_id: None,
+                last_updated: 0,
+            }
+            .insert_or_update(db.pool()).await.unwrap();
+        }
+
+        TrackedAccount::add(db.pool(), MOCK_PUUID, "euw1", Some("main")).await.unwrap();
+        TrackedAccount::add(db.pool(), &smurf, "euw1", None).await.unwrap();
+        TrackedAccount::set_groups(db.pool(), MOCK_PUUID, &["team roster".into(), "my smurfs".into()]).await.unwrap();
+        TrackedAccount::set_groups(db.pool(), &smurf, &["my smurfs".into(), " ".into()]).await.unwrap();
+
+        let accounts = TrackedAccount::list(db.pool()).await.unwrap();
+        assert_eq!(accounts.len(), 2);
+        assert!(accounts[0].is_primary);
+        assert_eq!(accounts[0].label.as_deref(), Some("main"));
+        assert_eq!(accounts[0].groups, vec!["my smurfs", "team roster"]);
+        assert!(!accounts[1].is_primary);
+        assert_eq!(accounts[1].game_name, "Smurf");
+        assert_eq!(accounts[1].groups, vec!["my smurfs"]);
+
+        assert!(TrackedAccount::set_primary(db.pool(), &smurf).await.unwrap());
+        assert!(!TrackedAccount::set_primary(db.pool(), "unknown").await.unwrap());
+        assert_eq!(TrackedAccount::primary(db.pool()).await.unwrap().unwrap().puuid, smurf);
+
+        let stats = GroupStats::calculate(db.pool(), "my smurfs", None).await.unwrap();
+        assert_eq!(stats.accounts.len(), 2);
+        assert_eq!(stats.combined.total_games, 2);
+        assert_eq!(stats.combined.wins, stats.accounts[0].stats.wins + stats.accounts[1].stats.wins);
+        assert_eq!(stats.accounts[0].stats.avg_kills, 9.0);
+
+        // The primary flag moves to the remaining account
+        TrackedAccount::remove(db.pool(), &smurf).await.unwrap();
+        assert_eq!(TrackedAccount::primary(db.pool()).await.unwrap().unwrap().puuid, MOCK_PUUID);
+        assert_eq!(TrackedAccount::group_members(db.pool(), "my smurfs").await.unwrap(), vec![MOCK_PUUID]);
     }
 }
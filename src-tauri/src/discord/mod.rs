@@ -21,7 +21,7 @@ pub struct DiscordUser {
     pub email: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Serialize, Deserialize)]
 struct TokenResponse {
     access_token: String,
     token_type: String,
@@ -30,6 +30,37 @@ struct TokenResponse {
     scope: String,
 }
 
+/// OAuth tokens, kept encrypted with the other secrets
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DiscordToken {
+    pub access_token: String,
+    pub refresh_token: String,
+    /// Epoch seconds
+    pub expires_at: i64,
+    pub scope: String,
+}
+
+/// Never print the tokens
+impl std::fmt::Debug for DiscordToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DiscordToken")
+            .field("expires_at", &self.expires_at)
+            .field("scope", &self.scope)
+            .finish_non_exhaustive()
+    }
+}
+
+impl From<TokenResponse> for DiscordToken {
+    fn from(response: TokenResponse) -> Self {
+        Self {
+            access_token: response.access_token,
+            refresh_token: response.refresh_token,
+            expires_at: chrono::Utc::now().timestamp() + response.expires_in as i64,
+            scope: response.scope,
+        }
+    }
+}
+
 pub struct DiscordOAuth {
     client_id: String,
     client_secret: Option<String>,
@@ -136,7 +167,7 @@ impl DiscordOAuth {
     }
 
     /// Start OAuth flow
-    pub async fn authenticate(&self) -> Result<(DiscordUser, String)> {
+    pub async fn authenticate(&self) -> Result<(DiscordUser, DiscordToken)> {
         // Generate PKCE
         let (code_verifier, code_challenge) = Self::generate_pkce();
         let state = uuid::Uuid::new_v4().to_string();
@@ -212,6 +243,6 @@ impl DiscordOAuth {
         // Get user info
         let user = self.get_user_info(&token_response.access_token).await?;
 
-        Ok((user, token_response.access_token))
+        Ok((user, token_response.into()))
     }
 }
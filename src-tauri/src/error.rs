@@ -0,0 +1,108 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+/// Error returned by every Tauri command.
+/// Serialized as `{ code, message, details }` so the frontend can branch on `code`.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum GalphaError {
+    #[error("League of Legends is not running")]
+    LolNotRunning,
+    #[error("League client is not ready: {0}")]
+    LcuNotReady(String),
+    #[error("Riot API rate limit exceeded")]
+    RiotRateLimited { retry_after: Option<u64> },
+    #[error("Riot API key is invalid or expired")]
+    RiotForbidden,
+    #[error("Riot API client not initialized. Please set your API key first.")]
+    RiotNotConfigured,
+    #[error("Riot API request failed with status {status}: {body}")]
+    RiotApi { status: u16, body: String },
+    #[error("Unknown platform \"{0}\"")]
+    UnknownPlatform(String),
+    #[error("Not found: {0}")]
+    NotFound(String),
+    #[error("Static data not loaded yet")]
+    StaticDataNotLoaded,
+    #[error("Database not initialized")]
+    DbNotInitialized,
+    #[error("Database error: {0}")]
+    Database(String),
+    #[error("Database was written by a newer version of Galpha (schema {found}, this version supports {supported})")]
+    DbVersionTooNew { found: i64, supported: i64 },
+    #[error("Invalid setting {field}: {reason}")]
+    InvalidConfig { field: String, reason: String },
+    #[error("A recording is already in progress")]
+    RecorderBusy,
+    #[error("{0}")]
+    Unsupported(String),
+    #[error("{0}")]
+    Internal(String),
+}
+
+pub type CommandResult<T> = Result<T, GalphaError>;
+
+impl GalphaError {
+    /// Stable identifier for the frontend
+    pub fn code(&self) -> &'static str {
+        match self {
+            GalphaError::LolNotRunning => "LOL_NOT_RUNNING",
+            GalphaError::LcuNotReady(_) => "LCU_NOT_READY",
+            GalphaError::RiotRateLimited { .. } => "RIOT_RATE_LIMITED",
+            GalphaError::RiotForbidden => "RIOT_FORBIDDEN",
+            GalphaError::RiotNotConfigured => "RIOT_NOT_CONFIGURED",
+            GalphaError::RiotApi { .. } => "RIOT_API_ERROR",
+            GalphaError::UnknownPlatform(_) => "UNKNOWN_PLATFORM",
+            GalphaError::NotFound(_) => "NOT_FOUND",
+            GalphaError::StaticDataNotLoaded => "STATIC_DATA_NOT_LOADED",
+            GalphaError::DbNotInitialized => "DB_NOT_INITIALIZED",
+            GalphaError::Database(_) => "DATABASE_ERROR",
+            GalphaError::DbVersionTooNew { .. } => "DB_VERSION_TOO_NEW",
+            GalphaError::InvalidConfig { .. } => "INVALID_CONFIG",
+            GalphaError::RecorderBusy => "RECORDER_BUSY",
+            GalphaError::Unsupported(_) => "UNSUPPORTED",
+            GalphaError::Internal(_) => "INTERNAL",
+        }
+    }
+
+    fn details(&self) -> serde_json::Value {
+        match self {
+            GalphaError::RiotRateLimited { retry_after } => serde_json::json!({ "retryAfter": retry_after }),
+            GalphaError::RiotApi { status, .. } => serde_json::json!({ "status": status }),
+            GalphaError::DbVersionTooNew { found, supported } => serde_json::json!({ "found": found, "supported": supported }),
+            GalphaError::InvalidConfig { field, .. } => serde_json::json!({ "field": field }),
+            _ => serde_json::Value::Null,
+        }
+    }
+}
+
+impl Serialize for GalphaError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("GalphaError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("details", &self.details())?;
+        state.end()
+    }
+}
+
+/// Keeps typed errors raised deeper in the stack (e.g. by the Riot client),
+/// everything else becomes `Internal` with its full context chain
+impl From<anyhow::Error> for GalphaError {
+    fn from(error: anyhow::Error) -> Self {
+        if let Some(galpha_error) = error.downcast_ref::<GalphaError>() {
+            return galpha_error.clone();
+        }
+
+        if let Some(sqlx_error) = error.downcast_ref::<sqlx::Error>() {
+            return GalphaError::Database(sqlx_error.to_string());
+        }
+
+        GalphaError::Internal(format!("{:#}", error))
+    }
+}
+
+impl From<sqlx::Error> for GalphaError {
+    fn from(error: sqlx::Error) -> Self {
+        GalphaError::Database(error.to_string())
+    }
+}
@@ -0,0 +1,281 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use anyhow::Result;
+use chrono::Utc;
+use tracing::{info, debug, warn};
+use super::connector::LcuConnector;
+use super::events::{LcuEventType, CHAMP_SELECT_SESSION_URI};
+
+/// `/lol-champ-select/v1/session`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChampSelectSession {
+    #[serde(default)]
+    pub game_id: u64,
+    /// Turns of actions, each turn may hold several simultaneous actions
+    #[serde(default)]
+    pub actions: Vec<Vec<ChampSelectAction>>,
+    #[serde(default)]
+    pub bans: ChampSelectBans,
+    #[serde(default)]
+    pub my_team: Vec<ChampSelectPlayer>,
+    #[serde(default)]
+    pub their_team: Vec<ChampSelectPlayer>,
+    pub local_player_cell_id: i64,
+    pub timer: ChampSelectTimer,
+    #[serde(default)]
+    pub trades: Vec<ChampSelectTrade>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChampSelectAction {
+    pub id: i64,
+    pub actor_cell_id: i64,
+    pub champion_id: i32,
+    pub completed: bool,
+    pub is_ally_action: bool,
+    pub is_in_progress: bool,
+    #[serde(rename = "type")]
+    pub action_type: String, // "pick", "ban", "ten_bans_reveal"
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChampSelectBans {
+    #[serde(default)]
+    pub my_team_bans: Vec<i32>,
+    #[serde(default)]
+    pub their_team_bans: Vec<i32>,
+    #[serde(default)]
+    pub num_bans: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChampSelectPlayer {
+    pub cell_id: i64,
+    pub champion_id: i32,
+    pub champion_pick_intent: i32,
+    #[serde(default)]
+    pub assigned_position: String,
+    #[serde(default)]
+    pub puuid: String,
+    #[serde(default)]
+    pub summoner_id: u64,
+    pub team: i32,
+    #[serde(default)]
+    pub spell1_id: u64,
+    #[serde(default)]
+    pub spell2_id: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChampSelectTimer {
+    pub adjusted_time_left_in_phase: i64,
+    #[serde(default)]
+    pub internal_now_in_epoch_ms: i64,
+    #[serde(default)]
+    pub is_infinite: bool,
+    pub phase: String, // "PLANNING", "BAN_PICK", "FINALIZATION", "GAME_STARTING"
+    #[serde(default)]
+    pub total_time_in_phase: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChampSelectTrade {
+    pub id: i64,
+    pub cell_id: i64,
+    pub state: String, // "AVAILABLE", "BUSY", "INVALID", "RECEIVED", "SENT"
+}
+
+impl ChampSelectSession {
+    fn player(&self, cell_id: i64) -> Option<&ChampSelectPlayer> {
+        self.my_team.iter()
+            .chain(self.their_team.iter())
+            .find(|p| p.cell_id == cell_id)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DraftActionKind {
+    Pick,
+    Ban,
+}
+
+/// A locked-in pick or ban
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DraftEvent {
+    pub kind: DraftActionKind,
+    pub cell_id: i64,
+    /// 0 when a ban was skipped
+    pub champion_id: i32,
+    pub is_ally: bool,
+    pub assigned_position: Option<String>,
+    pub timer_phase: String,
+    /// ms since epoch
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DraftTimeline {
+    pub game_id: u64,
+    pub local_player_cell_id: i64,
+    pub events: Vec<DraftEvent>,
+    pub started_at: i64,
+    pub ended_at: i64,
+}
+
+/// Turns successive champ select sessions into a timeline of picks and bans
+pub struct DraftTracker {
+    game_id: u64,
+    local_player_cell_id: i64,
+    seen_actions: HashSet<i64>,
+    events: Vec<DraftEvent>,
+    started_at: i64,
+}
+
+impl DraftTracker {
+    pub fn new() -> Self {
+        Self {
+            game_id: 0,
+            local_player_cell_id: -1,
+            seen_actions: HashSet::new(),
+            events: Vec::new(),
+            started_at: Utc::now().timestamp_millis(),
+        }
+    }
+
+    /// Record every action completed since the previous session, returning the new events
+    pub fn update(&mut self, session: &ChampSelectSession) -> Vec<DraftEvent> {
+        if session.game_id != 0 {
+            self.game_id = session.game_id;
+        }
+        self.local_player_cell_id = session.local_player_cell_id;
+
+        let timestamp = if session.timer.internal_now_in_epoch_ms > 0 {
+            session.timer.internal_now_in_epoch_ms
+        } else {
+            Utc::now().timestamp_millis()
+        };
+
+        let mut new_events = Vec::new();
+
+        for action in session.actions.iter().flatten() {
+            if !action.completed || self.seen_actions.contains(&action.id) {
+                continue;
+            }
+
+            let kind = match action.action_type.as_str() {
+                "pick" => DraftActionKind::Pick,
+                "ban" => DraftActionKind::Ban,
+                _ => continue,
+            };
+
+            self.seen_actions.insert(action.id);
+
+            let assigned_position = session.player(action.actor_cell_id)
+                .map(|p| p.assigned_position.clone())
+                .filter(|p| !p.is_empty());
+
+            new_events.push(DraftEvent {
+                kind,
+                cell_id: action.actor_cell_id,
+                champion_id: action.champion_id,
+                is_ally: action.is_ally_action,
+                assigned_position,
+                timer_phase: session.timer.phase.clone(),
+                timestamp,
+            });
+        }
+
+        self.events.extend(new_events.iter().cloned());
+        new_events
+    }
+
+    pub fn finish(self) -> DraftTimeline {
+        DraftTimeline {
+            game_id: self.game_id,
+            local_player_cell_id: self.local_player_cell_id,
+            events: self.events,
+            started_at: self.started_at,
+            ended_at: Utc::now().timestamp_millis(),
+        }
+    }
+}
+
+impl Default for DraftTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Follow the current champ select until the session ends (lock-in or dodge)
+pub async fn track_draft(connector: &LcuConnector) -> Result<DraftTimeline> {
+    let mut tracker = DraftTracker::new();
+    let mut stream = connector.subscribe(&[CHAMP_SELECT_SESSION_URI]).await?;
+
+    if let Some(session) = connector.get_champ_select_session().await? {
+        tracker.update(&session);
+    }
+
+    while let Some(event) = stream.next_event().await {
+        if event.event_type == LcuEventType::Delete {
+            break;
+        }
+
+        match event.parse::<ChampSelectSession>() {
+            Ok(session) => {
+                for draft_event in tracker.update(&session) {
+                    debug!("Draft {:?}: champion {} by cell {}", draft_event.kind, draft_event.champion_id, draft_event.cell_id);
+                }
+            }
+            Err(e) => warn!("Failed to parse champ select session: {}", e),
+        }
+    }
+
+    let timeline = tracker.finish();
+    info!("Champ select ended with {} draft actions (game {})", timeline.events.len(), timeline.game_id);
+
+    Ok(timeline)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_session() -> ChampSelectSession {
+        let json = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/lcu/champ-select-session.json"));
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn records_each_completed_action_once() {
+        let mut tracker = DraftTracker::new();
+        let mut session = fixture_session();
+
+        let events = tracker.update(&session);
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].kind, DraftActionKind::Ban);
+        assert_eq!(events[1].champion_id, 0);
+        assert_eq!(events[2].kind, DraftActionKind::Pick);
+        assert_eq!(events[2].assigned_position.as_deref(), Some("middle"));
+
+        // Same session again: nothing new
+        assert!(tracker.update(&session).is_empty());
+
+        session.actions[2][0].completed = true;
+        let events = tracker.update(&session);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].champion_id, 238);
+        assert!(!events[0].is_ally);
+
+        let timeline = tracker.finish();
+        assert_eq!(timeline.game_id, 7212345678);
+        assert_eq!(timeline.events.len(), 4);
+    }
+}
@@ -1,14 +1,32 @@
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use anyhow::{Result, Context};
-use tracing::{info, debug};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use futures_util::{SinkExt, StreamExt};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async_tls_with_config, Connector};
+use tracing::{info, debug, warn};
+use super::events::{topic_for_uri, LcuEvent, LcuEventStream, WAMP_SUBSCRIBE};
+use super::detector::LolDetector;
+use crate::error::GalphaError;
+use super::champ_select::ChampSelectSession;
+use super::gameflow::GameflowPhase;
+use super::ready_check::ReadyCheck;
+
+/// Buffered events per subscription before the websocket reader waits on the consumer
+const EVENT_CHANNEL_CAPACITY: usize = 64;
 
 #[derive(Debug, Clone)]
 pub struct LcuConnector {
     client: Client,
     base_url: String,
     auth_token: String,
+    source: CredentialSource,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -31,60 +49,81 @@ pub struct SummonerInfo {
     pub profile_icon_id: u32,
 }
 
+/// How the LCU port and auth token were found
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "method", content = "path", rename_all = "camelCase")]
+pub enum CredentialSource {
+    /// `--app-port` / `--remoting-auth-token` of the running LeagueClientUx process
+    ProcessArgs,
+    /// A lockfile on disk
+    Lockfile(PathBuf),
+    /// Base URL and password given by the caller
+    Explicit,
+}
+
 impl LcuConnector {
-    /// Create a new LCU connector by reading lockfile
-    pub async fn new() -> Result<Self> {
-        let (port, password) = Self::read_lockfile()?;
+    /// Create a new LCU connector, looking at the client process first and
+    /// falling back to the given lockfile locations (see `LcuConfig::lockfile_paths`)
+    pub async fn with_lockfile_paths(lockfile_paths: &[PathBuf]) -> Result<Self> {
+        let (port, password, source) = match LolDetector::find_client_credentials() {
+            Some(credentials) => (credentials.port, credentials.password, CredentialSource::ProcessArgs),
+            None => {
+                let (port, password, path) = Self::read_lockfile(lockfile_paths)?;
+                (port, password, CredentialSource::Lockfile(path))
+            }
+        };
+
+        Self::from_credentials(&format!("https://127.0.0.1:{}", port), &password, source)
+    }
+
+    /// Create a connector from one specific lockfile, skipping process discovery
+    pub async fn from_lockfile(path: &Path) -> Result<Self> {
+        let (port, password, path) = Self::read_lockfile(&[path.to_path_buf()])?;
+
+        Self::from_credentials(&format!("https://127.0.0.1:{}", port), &password, CredentialSource::Lockfile(path))
+    }
+
+    /// Create a connector for an explicit base URL (e.g. `https://127.0.0.1:54321`)
+    pub fn with_base_url(base_url: &str, password: &str) -> Result<Self> {
+        Self::from_credentials(base_url, password, CredentialSource::Explicit)
+    }
 
+    fn from_credentials(base_url: &str, password: &str, source: CredentialSource) -> Result<Self> {
         // Create a client that accepts self-signed certificates (LCU uses self-signed)
         let client = Client::builder()
             .danger_accept_invalid_certs(true)
             .build()?;
 
-        let base_url = format!("https://127.0.0.1:{}", port);
+        let base_url = base_url.trim_end_matches('/').to_string();
         let auth_token = format!("riot:{}", password);
 
-        info!("Connected to LCU on port {}", port);
+        info!("Connected to LCU at {} (via {:?})", base_url, source);
 
         Ok(Self {
             client,
             base_url,
             auth_token,
+            source,
         })
     }
 
-    /// Read the lockfile to get connection info
-    fn read_lockfile() -> Result<(u16, String)> {
-        #[cfg(target_os = "windows")]
-        let lockfile_path = {
-            let path = std::env::var("LOCALAPPDATA")
-                .context("Could not find LOCALAPPDATA")?;
-            format!("{}\\Riot Games\\League of Legends\\lockfile", path)
-        };
-
-        #[cfg(target_os = "macos")]
-        let lockfile_path = "/Applications/League of Legends.app/Contents/LoL/lockfile".to_string();
+    /// How the connection info was discovered
+    pub fn credential_source(&self) -> &CredentialSource {
+        &self.source
+    }
 
-        #[cfg(target_os = "linux")]
-        let lockfile_path = {
-            let home = std::env::var("HOME")
-                .context("Could not find HOME directory")?;
-            format!("{}/.wine/drive_c/Riot Games/League of Legends/lockfile", home)
-        };
+    /// Read the first existing lockfile to get connection info
+    fn read_lockfile(paths: &[PathBuf]) -> Result<(u16, String, PathBuf)> {
+        let lockfile_path = paths.iter()
+            .find(|path| path.is_file())
+            .ok_or(GalphaError::LolNotRunning)?;
 
-        let content = fs::read_to_string(&lockfile_path)
+        let content = fs::read_to_string(lockfile_path)
             .context("Failed to read lockfile. Is League of Legends running?")?;
 
-        let parts: Vec<&str> = content.split(':').collect();
-        if parts.len() < 5 {
-            anyhow::bail!("Invalid lockfile format");
-        }
+        let (port, password) = parse_lockfile(&content)?;
 
-        let port = parts[2].parse::<u16>()
-            .context("Failed to parse port from lockfile")?;
-        let password = parts[3].to_string();
-
-        Ok((port, password))
+        Ok((port, password, lockfile_path.clone()))
     }
 
     /// Get current summoner information
@@ -110,7 +149,9 @@ impl LcuConnector {
 
             // Check if it's a decryption error (user not fully logged in)
             if body.contains("Exception decrypting") {
-                anyhow::bail!("Summoner not available yet. Please ensure you are fully logged into League of Legends.");
+                return Err(GalphaError::LcuNotReady(
+                    "Summoner not available yet. Please ensure you are fully logged into League of Legends.".to_string(),
+                ).into());
             }
 
             anyhow::bail!("LCU request failed with status {}: {}", status, body);
@@ -185,6 +226,383 @@ impl LcuConnector {
             Ok(None)
         }
     }
+
+    /// Get the current gameflow phase
+    pub async fn get_gameflow_phase(&self) -> Result<GameflowPhase> {
+        let url = format!("{}/lol-gameflow/v1/gameflow-phase", self.base_url);
+
+        debug!("Fetching gameflow phase from: {}", url);
+
+        let password = self.auth_token.split(':').nth(1)
+            .ok_or_else(|| anyhow::anyhow!("Invalid auth token format"))?;
+
+        let response = self.client
+            .get(&url)
+            .basic_auth("riot", Some(password))
+            .send()
+            .await
+            .context("Failed to send request to LCU")?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("LCU request failed with status {}", response.status());
+        }
+
+        let phase: GameflowPhase = response.json().await
+            .context("Failed to parse gameflow phase")?;
+
+        Ok(phase)
+    }
+
+    /// Platform of the logged-in account, upper case like match-v5 IDs (e.g. "EUW1")
+    pub async fn get_platform_id(&self) -> Result<String> {
+        let url = format!("{}/lol-platform-config/v1/namespaces/LoginDataPacket/platformId", self.base_url);
+
+        debug!("Fetching platform ID from: {}", url);
+
+        let password = self.auth_token.split(':').nth(1)
+            .ok_or_else(|| anyhow::anyhow!("Invalid auth token format"))?;
+
+        let response = self.client
+            .get(&url)
+            .basic_auth("riot", Some(password))
+            .send()
+            .await
+            .context("Failed to send request to LCU")?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("LCU request failed with status {}", response.status());
+        }
+
+        let platform_id: String = response.json().await
+            .context("Failed to parse platform ID")?;
+
+        Ok(platform_id.to_uppercase())
+    }
+
+    /// Get the current champion select session, if any
+    pub async fn get_champ_select_session(&self) -> Result<Option<ChampSelectSession>> {
+        let url = format!("{}/lol-champ-select/v1/session", self.base_url);
+
+        debug!("Fetching champ select session from: {}", url);
+
+        let password = self.auth_token.split(':').nth(1)
+            .ok_or_else(|| anyhow::anyhow!("Invalid auth token format"))?;
+
+        let response = self.client
+            .get(&url)
+            .basic_auth("riot", Some(password))
+            .send()
+            .await
+            .context("Failed to send request to LCU")?;
+
+        if !response.status().is_success() {
+            // Not in champ select
+            return Ok(None);
+        }
+
+        let session: ChampSelectSession = response.json().await
+            .context("Failed to parse champ select session")?;
+
+        Ok(Some(session))
+    }
+
+    /// Get the local player's recent games (`begin..end`, newest first)
+    pub async fn get_match_history(&self, begin: usize, end: usize) -> Result<LcuMatchHistory> {
+        let url = format!(
+            "{}/lol-match-history/v1/products/lol/current-summoner/matches?begIndex={}&endIndex={}",
+            self.base_url, begin, end
+        );
+
+        debug!("Fetching match history from: {}", url);
+
+        let password = self.auth_token.split(':').nth(1)
+            .ok_or_else(|| anyhow::anyhow!("Invalid auth token format"))?;
+
+        let response = self.client
+            .get(&url)
+            .basic_auth("riot", Some(password))
+            .send()
+            .await
+            .context("Failed to send request to LCU")?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("LCU request failed with status {}", response.status());
+        }
+
+        let history: LcuMatchHistory = response.json().await
+            .context("Failed to parse match history")?;
+
+        Ok(history)
+    }
+
+    /// Get the pending ready check, if any
+    pub async fn get_ready_check(&self) -> Result<Option<ReadyCheck>> {
+        let url = format!("{}/lol-matchmaking/v1/ready-check", self.base_url);
+
+        debug!("Fetching ready check from: {}", url);
+
+        let password = self.auth_token.split(':').nth(1)
+            .ok_or_else(|| anyhow::anyhow!("Invalid auth token format"))?;
+
+        let response = self.client
+            .get(&url)
+            .basic_auth("riot", Some(password))
+            .send()
+            .await
+            .context("Failed to send request to LCU")?;
+
+        if !response.status().is_success() {
+            // Not in queue
+            return Ok(None);
+        }
+
+        let ready_check: ReadyCheck = response.json().await
+            .context("Failed to parse ready check")?;
+
+        Ok(Some(ready_check))
+    }
+
+    /// Get the queue ID of the current lobby
+    pub async fn get_lobby_queue_id(&self) -> Result<Option<i32>> {
+        let url = format!("{}/lol-lobby/v2/lobby", self.base_url);
+
+        let password = self.auth_token.split(':').nth(1)
+            .ok_or_else(|| anyhow::anyhow!("Invalid auth token format"))?;
+
+        let response = self.client
+            .get(&url)
+            .basic_auth("riot", Some(password))
+            .send()
+            .await
+            .context("Failed to send request to LCU")?;
+
+        if !response.status().is_success() {
+            return Ok(None);
+        }
+
+        let lobby: serde_json::Value = response.json().await
+            .context("Failed to parse lobby")?;
+
+        Ok(lobby.pointer("/gameConfig/queueId")
+            .and_then(|v| v.as_i64())
+            .map(|id| id as i32))
+    }
+
+    /// Accept the pending ready check
+    pub async fn accept_ready_check(&self) -> Result<()> {
+        self.post("/lol-matchmaking/v1/ready-check/accept").await
+    }
+
+    /// Decline the pending ready check
+    pub async fn decline_ready_check(&self) -> Result<()> {
+        self.post("/lol-matchmaking/v1/ready-check/decline").await
+    }
+
+    /// Send an empty POST to an LCU endpoint
+    async fn post(&self, path: &str) -> Result<()> {
+        let url = format!("{}{}", self.base_url, path);
+
+        debug!("POST {}", url);
+
+        let password = self.auth_token.split(':').nth(1)
+            .ok_or_else(|| anyhow::anyhow!("Invalid auth token format"))?;
+
+        let response = self.client
+            .post(&url)
+            .basic_auth("riot", Some(password))
+            .send()
+            .await
+            .context("Failed to send request to LCU")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("LCU request failed with status {}: {}", status, body);
+        }
+
+        Ok(())
+    }
+}
+
+impl LcuConnector {
+    /// Open the client's WAMP websocket and subscribe to `OnJsonApiEvent` updates
+    /// for the given endpoints (e.g. `/lol-gameflow/v1/gameflow-phase`)
+    pub async fn subscribe(&self, uris: &[&str]) -> Result<LcuEventStream> {
+        let password = self.auth_token.split(':').nth(1)
+            .ok_or_else(|| anyhow::anyhow!("Invalid auth token format"))?;
+
+        let ws_url = format!("{}/", self.base_url.replacen("https://", "wss://", 1));
+
+        let mut request = ws_url.as_str().into_client_request()
+            .context("Failed to build LCU websocket request")?;
+        request.headers_mut().insert(
+            "Authorization",
+            format!("Basic {}", STANDARD.encode(format!("riot:{}", password))).parse()?,
+        );
+
+        let (mut socket, _) = connect_async_tls_with_config(
+            request,
+            None,
+            false,
+            Some(Connector::Rustls(Arc::new(insecure_tls_config()?))),
+        )
+        .await
+        .context("Failed to open LCU websocket")?;
+
+        for uri in uris {
+            let topic = topic_for_uri(uri);
+            debug!("Subscribing to LCU event: {}", topic);
+
+            let frame = serde_json::json!([WAMP_SUBSCRIBE, topic]).to_string();
+            socket.send(Message::Text(frame)).await
+                .context("Failed to send LCU subscription")?;
+        }
+
+        info!("Subscribed to {} LCU event(s) at {}", uris.len(), ws_url);
+
+        let (tx, rx) = mpsc::channel(EVENT_CHANNEL_CAPACITY);
+
+        tokio::spawn(async move {
+            loop {
+                let message = tokio::select! {
+                    message = socket.next() => message,
+                    // Stream dropped: close right away rather than on the next event
+                    _ = tx.closed() => break,
+                };
+                let Some(message) = message else {
+                    break;
+                };
+
+                let text = match message {
+                    Ok(Message::Text(text)) => text,
+                    Ok(Message::Close(_)) => break,
+                    Ok(_) => continue,
+                    Err(e) => {
+                        warn!("LCU websocket error: {}", e);
+                        break;
+                    }
+                };
+
+                if let Some(event) = LcuEvent::from_wamp(&text) {
+                    // Receiver dropped: nobody is listening anymore
+                    if tx.send(event).await.is_err() {
+                        break;
+                    }
+                }
+            }
+
+            let _ = socket.close(None).await;
+            debug!("LCU websocket subscription closed");
+        });
+
+        Ok(LcuEventStream::new(rx))
+    }
+}
+
+/// Parse `name:pid:port:password:protocol` lockfile contents
+fn parse_lockfile(content: &str) -> Result<(u16, String)> {
+    let parts: Vec<&str> = content.trim().split(':').collect();
+    if parts.len() < 5 {
+        anyhow::bail!("Invalid lockfile format");
+    }
+
+    let port = parts[2].parse::<u16>()
+        .context("Failed to parse port from lockfile")?;
+    let password = parts[3].to_string();
+
+    Ok((port, password))
+}
+
+/// Lockfile locations to try when the client process can't be inspected
+pub fn default_lockfile_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+
+    #[cfg(target_os = "windows")]
+    {
+        paths.push(PathBuf::from("C:\\Riot Games\\League of Legends\\lockfile"));
+        if let Ok(local_app_data) = std::env::var("LOCALAPPDATA") {
+            paths.push(PathBuf::from(local_app_data).join("Riot Games\\League of Legends\\lockfile"));
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    paths.push(PathBuf::from("/Applications/League of Legends.app/Contents/LoL/lockfile"));
+
+    #[cfg(target_os = "linux")]
+    if let Some(home) = dirs::home_dir() {
+        let riot_dir = "drive_c/Riot Games/League of Legends/lockfile";
+
+        // Respect a custom wine prefix first
+        if let Ok(prefix) = std::env::var("WINEPREFIX") {
+            paths.push(PathBuf::from(prefix).join(riot_dir));
+        }
+
+        paths.push(home.join(".wine").join(riot_dir));
+        // Lutris
+        paths.push(home.join("Games/league-of-legends").join(riot_dir));
+        // Bottles (native and flatpak)
+        for bottles_root in [
+            home.join(".local/share/bottles/bottles"),
+            home.join(".var/app/com.usebottles.bottles/data/bottles/bottles"),
+        ] {
+            if let Ok(entries) = fs::read_dir(&bottles_root) {
+                paths.extend(entries.flatten().map(|entry| entry.path().join(riot_dir)));
+            }
+        }
+    }
+
+    paths
+}
+
+/// TLS config that accepts the LCU's self-signed certificate
+fn insecure_tls_config() -> Result<rustls::ClientConfig> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+
+    let config = rustls::ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(AcceptAnyCert(provider)))
+        .with_no_client_auth();
+
+    Ok(config)
+}
+
+#[derive(Debug)]
+struct AcceptAnyCert(Arc<rustls::crypto::CryptoProvider>);
+
+impl rustls::client::danger::ServerCertVerifier for AcceptAnyCert {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::pki_types::CertificateDer<'_>,
+        _intermediates: &[rustls::pki_types::CertificateDer<'_>],
+        _server_name: &rustls::pki_types::ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: rustls::pki_types::UnixTime,
+    ) -> std::result::Result<rustls::client::danger::ServerCertVerified, rustls::Error> {
+        Ok(rustls::client::danger::ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &rustls::pki_types::CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> std::result::Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &rustls::pki_types::CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> std::result::Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -215,3 +633,155 @@ pub struct GameParticipant {
     #[serde(rename = "teamId")]
     pub team_id: i32,
 }
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LcuMatchHistory {
+    pub games: LcuGameList,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LcuGameList {
+    #[serde(rename = "gameCount", default)]
+    pub game_count: i32,
+    pub games: Vec<LcuGameSummary>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LcuGameSummary {
+    #[serde(rename = "gameId")]
+    pub game_id: u64,
+    #[serde(rename = "platformId")]
+    pub platform_id: String,
+    #[serde(rename = "gameCreation")]
+    pub game_creation: i64,
+    #[serde(rename = "gameDuration")]
+    pub game_duration: i64,
+    #[serde(rename = "gameMode")]
+    pub game_mode: String,
+    #[serde(rename = "queueId")]
+    pub queue_id: i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::events::{LcuEventType, GAMEFLOW_PHASE_URI};
+    use super::super::mock_server::MockLcu;
+
+    async fn connect(mock: &MockLcu) -> LcuConnector {
+        LcuConnector::from_lockfile(&mock.lockfile_path()).await.unwrap()
+    }
+
+    #[test]
+    fn parses_lockfile_contents() {
+        let (port, password) = parse_lockfile("LeagueClient:4242:54321:s3cr3t:https\n").unwrap();
+        assert_eq!(port, 54321);
+        assert_eq!(password, "s3cr3t");
+
+        assert!(parse_lockfile("LeagueClient:4242").is_err());
+    }
+
+    #[tokio::test]
+    async fn reads_injected_lockfile() {
+        let mock = MockLcu::start().await.unwrap();
+        let connector = connect(&mock).await;
+
+        assert_eq!(connector.credential_source(), &CredentialSource::Lockfile(mock.lockfile_path()));
+    }
+
+    #[tokio::test]
+    async fn missing_lockfile_is_an_error() {
+        let mock = MockLcu::start().await.unwrap();
+        std::fs::remove_file(mock.lockfile_path()).unwrap();
+
+        assert!(LcuConnector::from_lockfile(&mock.lockfile_path()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn fetches_current_summoner() {
+        let mock = MockLcu::start().await.unwrap();
+        let summoner = connect(&mock).await.get_current_summoner().await.unwrap();
+
+        assert_eq!(summoner.game_name.as_deref(), Some("Galnir"));
+        assert_eq!(summoner.summoner_level, 287);
+        assert_eq!(mock.requests(), vec!["GET /lol-summoner/v1/current-summoner"]);
+    }
+
+    #[tokio::test]
+    async fn rejects_wrong_password() {
+        let mock = MockLcu::start().await.unwrap();
+        let connector = LcuConnector::with_base_url(&format!("https://127.0.0.1:{}", mock.port), "wrong").unwrap();
+
+        assert!(connector.get_current_summoner().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn fetches_active_game_only_in_game() {
+        let mock = MockLcu::start().await.unwrap();
+        let connector = connect(&mock).await;
+
+        let game = connector.get_active_game().await.unwrap().unwrap();
+        assert_eq!(game.game_data.team_one[0].champion_id, 103);
+
+        mock.set_response("GET", "/lol-gameflow/v1/session", 200, r#"{"phase":"Lobby","gameData":{"teamOne":[],"teamTwo":[]}}"#);
+        assert!(connector.get_active_game().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn fetches_champ_select_and_match_history() {
+        let mock = MockLcu::start().await.unwrap();
+        let connector = connect(&mock).await;
+
+        let session = connector.get_champ_select_session().await.unwrap().unwrap();
+        assert_eq!(session.game_id, 7212345678);
+        assert_eq!(session.my_team[0].assigned_position, "middle");
+        assert_eq!(session.timer.phase, "BAN_PICK");
+        assert_eq!(connector.get_platform_id().await.unwrap(), "EUW1");
+
+        let history = connector.get_match_history(0, 20).await.unwrap();
+        assert_eq!(history.games.games.len(), 2);
+        assert_eq!(history.games.games[1].queue_id, 450);
+
+        mock.set_response("GET", "/lol-champ-select/v1/session", 404, "{}");
+        assert!(connector.get_champ_select_session().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn streams_subscribed_events() {
+        let mock = MockLcu::start().await.unwrap();
+        let connector = connect(&mock).await;
+
+        let mut stream = connector.subscribe(&[GAMEFLOW_PHASE_URI]).await.unwrap();
+        mock.wait_for_subscription(GAMEFLOW_PHASE_URI).await.unwrap();
+
+        // Not subscribed: must not reach the stream
+        mock.push_event("/lol-lobby/v2/lobby", "Update", serde_json::json!({}));
+        mock.push_event(GAMEFLOW_PHASE_URI, "Update", serde_json::json!("ChampSelect"));
+
+        let event = stream.next_event().await.unwrap();
+        assert_eq!(event.uri, GAMEFLOW_PHASE_URI);
+        assert_eq!(event.event_type, LcuEventType::Update);
+        assert_eq!(event.parse::<GameflowPhase>().unwrap(), GameflowPhase::ChampSelect);
+    }
+
+    #[tokio::test]
+    async fn closes_the_websocket_when_the_stream_is_dropped() {
+        let mock = MockLcu::start().await.unwrap();
+        let connector = connect(&mock).await;
+
+        let stream = connector.subscribe(&[GAMEFLOW_PHASE_URI]).await.unwrap();
+        mock.wait_for_subscription(GAMEFLOW_PHASE_URI).await.unwrap();
+
+        // No event is pushed, the reader must notice the drop on its own
+        drop(stream);
+        mock.wait_for_close(GAMEFLOW_PHASE_URI).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn accepts_ready_check() {
+        let mock = MockLcu::start().await.unwrap();
+        connect(&mock).await.accept_ready_check().await.unwrap();
+
+        assert_eq!(mock.requests(), vec!["POST /lol-matchmaking/v1/ready-check/accept"]);
+    }
+}
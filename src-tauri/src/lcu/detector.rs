@@ -1,9 +1,39 @@
-use sysinfo::{ProcessesToUpdate, System};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::time::sleep;
 use tracing::{info, debug};
 
+/// LCU connection info taken from the `LeagueClientUx` command line
+#[derive(Debug, Clone)]
+pub struct ClientCredentials {
+    pub port: u16,
+    pub password: String,
+}
+
+impl ClientCredentials {
+    /// Parse `--app-port` and `--remoting-auth-token` from process arguments
+    pub fn from_args<S: AsRef<str>>(args: &[S]) -> Option<Self> {
+        let mut port = None;
+        let mut password = None;
+
+        for arg in args {
+            let arg = arg.as_ref().trim_matches('"');
+
+            if let Some(value) = arg.strip_prefix("--app-port=") {
+                port = value.parse::<u16>().ok();
+            } else if let Some(value) = arg.strip_prefix("--remoting-auth-token=") {
+                password = Some(value.to_string());
+            }
+        }
+
+        Some(Self {
+            port: port?,
+            password: password?,
+        })
+    }
+}
+
 #[derive(Clone)]
 pub struct LolDetector {
     system: Arc<Mutex<System>>,
@@ -49,6 +79,35 @@ impl LolDetector {
         false
     }
 
+    /// Read LCU credentials from the running `LeagueClientUx` process, if any
+    pub fn find_client_credentials() -> Option<ClientCredentials> {
+        let mut sys = System::new();
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::new().with_cmd(UpdateKind::OnlyIfNotSet),
+        );
+
+        for process in sys.processes().values() {
+            let process_name = process.name().to_string_lossy().to_lowercase();
+            if !process_name.starts_with("leagueclientux") {
+                continue;
+            }
+
+            let args: Vec<String> = process.cmd()
+                .iter()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect();
+
+            if let Some(credentials) = ClientCredentials::from_args(&args) {
+                debug!("Found LCU credentials on process {} (port {})", process.pid(), credentials.port);
+                return Some(credentials);
+            }
+        }
+
+        None
+    }
+
     /// Start monitoring for League of Legends process
     pub async fn start_monitoring<F>(&self, callback: F)
     where
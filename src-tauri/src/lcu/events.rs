@@ -0,0 +1,82 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use futures_util::Stream;
+use tokio::sync::mpsc;
+
+/// Gameflow phase endpoint (plain string payload)
+pub const GAMEFLOW_PHASE_URI: &str = "/lol-gameflow/v1/gameflow-phase";
+/// Champion select session endpoint
+pub const CHAMP_SELECT_SESSION_URI: &str = "/lol-champ-select/v1/session";
+
+// WAMP 1.0 message type codes used by the LCU websocket
+pub(crate) const WAMP_SUBSCRIBE: u8 = 5;
+pub(crate) const WAMP_EVENT: u8 = 8;
+
+/// Kind of change reported by an LCU event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LcuEventType {
+    Create,
+    Update,
+    Delete,
+}
+
+/// A single `OnJsonApiEvent` pushed by the League client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LcuEvent {
+    pub uri: String,
+    #[serde(rename = "eventType")]
+    pub event_type: LcuEventType,
+    pub data: serde_json::Value,
+}
+
+impl LcuEvent {
+    /// Deserialize the event payload into a typed model
+    pub fn parse<T: DeserializeOwned>(&self) -> anyhow::Result<T> {
+        Ok(serde_json::from_value(self.data.clone())?)
+    }
+
+    /// Parse a raw WAMP frame, returning `None` for anything that is not an API event
+    pub(crate) fn from_wamp(text: &str) -> Option<Self> {
+        let frame: serde_json::Value = serde_json::from_str(text).ok()?;
+        let parts = frame.as_array()?;
+
+        if parts.len() < 3 || parts[0].as_u64() != Some(WAMP_EVENT as u64) {
+            return None;
+        }
+
+        serde_json::from_value(parts[2].clone()).ok()
+    }
+}
+
+/// Build the WAMP topic name for an LCU endpoint
+/// (e.g. `/lol-gameflow/v1/gameflow-phase` -> `OnJsonApiEvent_lol-gameflow_v1_gameflow-phase`)
+pub(crate) fn topic_for_uri(uri: &str) -> String {
+    format!("OnJsonApiEvent{}", uri.trim_end_matches('/').replace('/', "_"))
+}
+
+/// Async stream of events from an LCU websocket subscription.
+/// The underlying connection is closed once the stream is dropped.
+pub struct LcuEventStream {
+    receiver: mpsc::Receiver<LcuEvent>,
+}
+
+impl LcuEventStream {
+    pub(crate) fn new(receiver: mpsc::Receiver<LcuEvent>) -> Self {
+        Self { receiver }
+    }
+
+    /// Wait for the next event, or `None` once the client disconnects
+    pub async fn next_event(&mut self) -> Option<LcuEvent> {
+        self.receiver.recv().await
+    }
+}
+
+impl Stream for LcuEventStream {
+    type Item = LcuEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
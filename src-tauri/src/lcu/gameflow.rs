@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use chrono::Utc;
+use tokio::sync::{broadcast, watch};
+use tokio::time::sleep;
+use tracing::{info, debug, warn};
+use super::connector::LcuConnector;
+use crate::config::Config;
+use super::events::{LcuEventType, GAMEFLOW_PHASE_URI};
+
+/// How long to wait before reconnecting when the client is not reachable
+const RECONNECT_DELAY_SECS: u64 = 5;
+const TRANSITION_CHANNEL_CAPACITY: usize = 32;
+
+/// Phases reported by `/lol-gameflow/v1/gameflow-phase`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameflowPhase {
+    None,
+    Lobby,
+    Matchmaking,
+    ReadyCheck,
+    ChampSelect,
+    GameStart,
+    InProgress,
+    WaitingForStats,
+    EndOfGame,
+    Reconnect,
+    /// Phases we don't track explicitly (PreEndOfGame, TerminatedInError, ...)
+    #[serde(other)]
+    Unknown,
+}
+
+/// Emitted every time the gameflow phase changes
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameflowTransition {
+    pub previous: GameflowPhase,
+    pub current: GameflowPhase,
+    /// When the previous phase was entered (ms since epoch)
+    pub previous_entered_at: i64,
+    /// When the transition happened (ms since epoch)
+    pub changed_at: i64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PhaseState {
+    phase: GameflowPhase,
+    entered_at: i64,
+}
+
+/// Tracks the client's gameflow phase over the LCU websocket and
+/// broadcasts transitions to the rest of the app
+#[derive(Clone)]
+pub struct GameflowMonitor {
+    state: Arc<Mutex<PhaseState>>,
+    sender: broadcast::Sender<GameflowTransition>,
+}
+
+impl GameflowMonitor {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(TRANSITION_CHANNEL_CAPACITY);
+
+        Self {
+            state: Arc::new(Mutex::new(PhaseState {
+                phase: GameflowPhase::None,
+                entered_at: Utc::now().timestamp_millis(),
+            })),
+            sender,
+        }
+    }
+
+    /// Current phase (None while the client is closed)
+    pub fn current_phase(&self) -> GameflowPhase {
+        self.state.lock().unwrap().phase
+    }
+
+    /// Receive every future transition
+    pub fn subscribe(&self) -> broadcast::Receiver<GameflowTransition> {
+        self.sender.subscribe()
+    }
+
+    /// Start tracking the gameflow phase in the background, finding the client
+    /// with the lockfile paths of the current config
+    pub fn start<F>(&self, config: watch::Receiver<Config>, callback: F)
+    where
+        F: Fn(&GameflowTransition) + Send + Sync + 'static,
+    {
+        let monitor = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let lockfile_paths = config.borrow().lcu.lockfile_paths();
+                monitor.run_session(&lockfile_paths, &callback).await;
+
+                // Client closed or unreachable
+                monitor.transition(GameflowPhase::None, &callback);
+                sleep(Duration::from_secs(RECONNECT_DELAY_SECS)).await;
+            }
+        });
+    }
+
+    /// Follow phase changes for a single client session, until it disconnects
+    async fn run_session<F>(&self, lockfile_paths: &[PathBuf], callback: &F)
+    where
+        F: Fn(&GameflowTransition),
+    {
+        let connector = match LcuConnector::with_lockfile_paths(lockfile_paths).await {
+            Ok(connector) => connector,
+            Err(e) => {
+                debug!("Gameflow monitor waiting for LCU: {}", e);
+                return;
+            }
+        };
+
+        // Subscribe first so no transition is missed between the snapshot and the stream
+        let mut stream = match connector.subscribe(&[GAMEFLOW_PHASE_URI]).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                warn!("Failed to subscribe to gameflow events: {}", e);
+                return;
+            }
+        };
+
+        match connector.get_gameflow_phase().await {
+            Ok(phase) => self.transition(phase, callback),
+            Err(e) => debug!("Failed to read initial gameflow phase: {}", e),
+        }
+
+        while let Some(event) = stream.next_event().await {
+            let phase = if event.event_type == LcuEventType::Delete {
+                GameflowPhase::None
+            } else {
+                match event.parse::<GameflowPhase>() {
+                    Ok(phase) => phase,
+                    Err(e) => {
+                        warn!("Unexpected gameflow phase payload {}: {}", event.data, e);
+                        continue;
+                    }
+                }
+            };
+
+            self.transition(phase, callback);
+        }
+
+        info!("Gameflow monitor lost connection to LCU");
+    }
+
+    pub(crate) fn transition<F>(&self, phase: GameflowPhase, callback: &F)
+    where
+        F: Fn(&GameflowTransition),
+    {
+        let transition = {
+            let mut state = self.state.lock().unwrap();
+            if state.phase == phase {
+                return;
+            }
+
+            let now = Utc::now().timestamp_millis();
+            let transition = GameflowTransition {
+                previous: state.phase,
+                current: phase,
+                previous_entered_at: state.entered_at,
+                changed_at: now,
+            };
+
+            *state = PhaseState { phase, entered_at: now };
+            transition
+        };
+
+        info!("Gameflow phase changed: {:?} -> {:?}", transition.previous, transition.current);
+
+        callback(&transition);
+        // No receivers is fine, services subscribe lazily
+        let _ = self.sender.send(transition);
+    }
+}
+
+impl Default for GameflowMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
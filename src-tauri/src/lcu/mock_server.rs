@@ -0,0 +1,234 @@
+//! In-process stand-in for the League client: HTTPS + WAMP websocket on a
+//! self-signed certificate, with a fake lockfile pointing at it.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use futures_util::{SinkExt, StreamExt};
+use tempfile::TempDir;
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::broadcast;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+use crate::mock_http::{read_request, tls_acceptor, write_response};
+use super::events::{topic_for_uri, WAMP_EVENT, WAMP_SUBSCRIBE};
+
+const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/lcu");
+
+struct MockState {
+    password: String,
+    /// "METHOD /path" -> (status, body)
+    routes: Mutex<HashMap<String, (u16, String)>>,
+    /// Every request received, as "METHOD /path?query"
+    requests: Mutex<Vec<String>>,
+    /// Topics of the open websockets
+    subscriptions: Mutex<HashSet<String>>,
+    events: broadcast::Sender<(String, String)>,
+}
+
+pub struct MockLcu {
+    pub port: u16,
+    pub password: String,
+    lockfile_dir: TempDir,
+    state: Arc<MockState>,
+}
+
+impl MockLcu {
+    /// Start a mock client serving the canned fixtures
+    pub async fn start() -> Result<Self> {
+        let password = "mock-remoting-token".to_string();
+        let (events, _) = broadcast::channel(16);
+
+        let state = Arc::new(MockState {
+            password: password.clone(),
+            routes: Mutex::new(HashMap::new()),
+            requests: Mutex::new(Vec::new()),
+            subscriptions: Mutex::new(HashSet::new()),
+            events,
+        });
+
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let port = listener.local_addr()?.port();
+        let acceptor = tls_acceptor()?;
+
+        let server_state = state.clone();
+        tokio::spawn(async move {
+            while let Ok((tcp, _)) = listener.accept().await {
+                let acceptor = acceptor.clone();
+                let state = server_state.clone();
+
+                tokio::spawn(async move {
+                    if let Ok(stream) = acceptor.accept(tcp).await {
+                        let _ = handle_connection(stream, state).await;
+                    }
+                });
+            }
+        });
+
+        let mock = Self {
+            port,
+            password,
+            lockfile_dir: tempfile::tempdir()?,
+            state,
+        };
+
+        mock.write_lockfile()?;
+        mock.set_fixture("GET", "/lol-summoner/v1/current-summoner", "current-summoner.json")?;
+        mock.set_fixture("GET", "/lol-gameflow/v1/session", "gameflow-session.json")?;
+        mock.set_fixture("GET", "/lol-champ-select/v1/session", "champ-select-session.json")?;
+        mock.set_fixture("GET", "/lol-match-history/v1/products/lol/current-summoner/matches", "match-history.json")?;
+        mock.set_response("GET", "/lol-gameflow/v1/gameflow-phase", 200, "\"InProgress\"");
+        mock.set_response("GET", "/lol-platform-config/v1/namespaces/LoginDataPacket/platformId", 200, "\"euw1\"");
+
+        Ok(mock)
+    }
+
+    pub fn lockfile_path(&self) -> PathBuf {
+        self.lockfile_dir.path().join("lockfile")
+    }
+
+    /// (Re)write the lockfile, e.g. after deleting it in a test
+    pub fn write_lockfile(&self) -> Result<()> {
+        let content = format!("LeagueClient:4242:{}:{}:https", self.port, self.password);
+        std::fs::write(self.lockfile_path(), content)?;
+        Ok(())
+    }
+
+    pub fn set_response(&self, method: &str, path: &str, status: u16, body: &str) {
+        self.state.routes.lock().unwrap()
+            .insert(format!("{} {}", method, path), (status, body.to_string()));
+    }
+
+    pub fn set_fixture(&self, method: &str, path: &str, fixture: &str) -> Result<()> {
+        let body = std::fs::read_to_string(Path::new(FIXTURES_DIR).join(fixture))
+            .context(format!("Missing fixture {}", fixture))?;
+        self.set_response(method, path, 200, &body);
+        Ok(())
+    }
+
+    pub fn requests(&self) -> Vec<String> {
+        self.state.requests.lock().unwrap().clone()
+    }
+
+    /// Wait until a websocket client subscribed to the endpoint
+    pub async fn wait_for_subscription(&self, uri: &str) -> Result<()> {
+        let topic = topic_for_uri(uri);
+
+        for _ in 0..100 {
+            if self.state.subscriptions.lock().unwrap().contains(&topic) {
+                return Ok(());
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+
+        anyhow::bail!("No subscription to {}", topic)
+    }
+
+    /// Wait until every websocket subscribed to the endpoint was closed
+    pub async fn wait_for_close(&self, uri: &str) -> Result<()> {
+        let topic = topic_for_uri(uri);
+
+        for _ in 0..100 {
+            if !self.state.subscriptions.lock().unwrap().contains(&topic) {
+                return Ok(());
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+
+        anyhow::bail!("Still subscribed to {}", topic)
+    }
+
+    /// Push an `OnJsonApiEvent` to every client subscribed to the endpoint
+    pub fn push_event(&self, uri: &str, event_type: &str, data: serde_json::Value) {
+        let topic = topic_for_uri(uri);
+        let frame = serde_json::json!([
+            WAMP_EVENT,
+            topic,
+            { "data": data, "eventType": event_type, "uri": uri }
+        ]);
+
+        let _ = self.state.events.send((topic, frame.to_string()));
+    }
+}
+
+async fn handle_connection<S>(mut stream: S, state: Arc<MockState>) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let request = read_request(&mut stream).await?;
+    let headers = &request.headers;
+
+    state.requests.lock().unwrap().push(format!("{} {}", request.method, request.target));
+
+    let expected_auth = format!("Basic {}", STANDARD.encode(format!("riot:{}", state.password)));
+    if headers.get("authorization") != Some(&expected_auth) {
+        return write_response(&mut stream, 401, &[], "{\"message\":\"Unauthorized\"}").await;
+    }
+
+    if headers.get("upgrade").is_some_and(|v| v.eq_ignore_ascii_case("websocket")) {
+        let key = headers.get("sec-websocket-key").cloned().unwrap_or_default();
+        let handshake = format!(
+            "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+            derive_accept_key(key.as_bytes())
+        );
+        stream.write_all(handshake.as_bytes()).await?;
+
+        let socket = WebSocketStream::from_raw_socket(stream, Role::Server, None).await;
+        return serve_websocket(socket, state).await;
+    }
+
+    let route = state.routes.lock().unwrap().get(&format!("{} {}", request.method, request.path())).cloned();
+
+    match route {
+        Some((status, body)) => write_response(&mut stream, status, &[], &body).await,
+        None if request.method == "POST" => write_response(&mut stream, 204, &[], "").await,
+        None => write_response(&mut stream, 404, &[], "{\"message\":\"Not found\"}").await,
+    }
+}
+
+async fn serve_websocket<S>(mut socket: WebSocketStream<S>, state: Arc<MockState>) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut events = state.events.subscribe();
+    let mut topics = HashSet::new();
+
+    loop {
+        tokio::select! {
+            message = socket.next() => {
+                let Some(Ok(Message::Text(text))) = message else {
+                    break;
+                };
+
+                let frame: serde_json::Value = serde_json::from_str(&text)?;
+                if frame[0].as_u64() == Some(WAMP_SUBSCRIBE as u64) {
+                    if let Some(topic) = frame[1].as_str() {
+                        topics.insert(topic.to_string());
+                        state.subscriptions.lock().unwrap().insert(topic.to_string());
+                    }
+                }
+            }
+            event = events.recv() => {
+                let Ok((topic, frame)) = event else {
+                    break;
+                };
+
+                if topics.contains(&topic) {
+                    socket.send(Message::Text(frame)).await?;
+                }
+            }
+        }
+    }
+
+    let mut subscriptions = state.subscriptions.lock().unwrap();
+    for topic in &topics {
+        subscriptions.remove(topic);
+    }
+
+    Ok(())
+}
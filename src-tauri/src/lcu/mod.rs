@@ -1,5 +1,16 @@
 pub mod detector;
 pub mod connector;
+pub mod events;
+pub mod gameflow;
+pub mod champ_select;
+pub mod ready_check;
 
-pub use detector::LolDetector;
-pub use connector::{LcuConnector, ActiveGameInfo, GameData, GameParticipant};
+#[cfg(test)]
+pub(crate) mod mock_server;
+
+pub use detector::{LolDetector, ClientCredentials};
+pub use connector::{LcuConnector, CredentialSource, ActiveGameInfo, GameData, GameParticipant, default_lockfile_paths};
+pub use events::{LcuEvent, LcuEventStream, LcuEventType};
+pub use gameflow::{GameflowMonitor, GameflowPhase, GameflowTransition};
+pub use champ_select::{ChampSelectSession, DraftTimeline, DraftTracker, track_draft};
+pub use ready_check::{ReadyCheckDecision, ReadyCheckRules};
@@ -0,0 +1,286 @@
+use serde::{Deserialize, Serialize};
+use rand::Rng;
+use crate::error::{CommandResult, GalphaError};
+
+/// A queue pop expires this many seconds after it appears
+pub const READY_CHECK_TIMEOUT_SECS: u64 = 12;
+/// Longest accept delay, leaving time for the accept to reach the client before the pop expires
+pub const MAX_ACCEPT_DELAY_SECS: u64 = READY_CHECK_TIMEOUT_SECS - 2;
+
+/// `/lol-matchmaking/v1/ready-check`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadyCheck {
+    pub state: String,           // "InProgress", "EveryoneReady", "StrangerNotReady", "Invalid"
+    pub player_response: String, // "None", "Accepted", "Declined"
+    #[serde(default)]
+    pub timer: f64,
+}
+
+impl ReadyCheck {
+    /// True while the local player still has to answer
+    pub fn is_pending(&self) -> bool {
+        self.state == "InProgress" && self.player_response == "None"
+    }
+}
+
+/// Which queue pops should be accepted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AcceptMode {
+    Always,
+    /// Only accept queues listed in `queue_ids`
+    QueuesOnly,
+}
+
+/// Local hours during which a ready check is never accepted.
+/// `start_hour > end_hour` wraps around midnight (e.g. 23 -> 7).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuietHours {
+    pub start_hour: u32,
+    pub end_hour: u32,
+}
+
+impl QuietHours {
+    pub fn contains(&self, hour: u32) -> bool {
+        if self.start_hour <= self.end_hour {
+            hour >= self.start_hour && hour < self.end_hour
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadyCheckRules {
+    pub enabled: bool,
+    pub mode: AcceptMode,
+    #[serde(default)]
+    pub queue_ids: Vec<i32>,
+    /// Random delay before accepting, in seconds, capped at `MAX_ACCEPT_DELAY_SECS`
+    #[serde(default)]
+    pub min_delay_secs: u64,
+    #[serde(default)]
+    pub max_delay_secs: u64,
+    #[serde(default)]
+    pub quiet_hours: Vec<QuietHours>,
+    /// Decline pops that don't match the rules instead of leaving them to the player
+    #[serde(default)]
+    pub decline_unmatched: bool,
+}
+
+impl Default for ReadyCheckRules {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: AcceptMode::Always,
+            queue_ids: Vec::new(),
+            min_delay_secs: 0,
+            max_delay_secs: 0,
+            quiet_hours: Vec::new(),
+            decline_unmatched: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum ReadyCheckDecision {
+    Accept { delay_ms: u64 },
+    Decline { reason: String },
+    Ignore { reason: String },
+}
+
+impl ReadyCheckDecision {
+    pub fn action(&self) -> &'static str {
+        match self {
+            ReadyCheckDecision::Accept { .. } => "accept",
+            ReadyCheckDecision::Decline { .. } => "decline",
+            ReadyCheckDecision::Ignore { .. } => "ignore",
+        }
+    }
+
+    pub fn reason(&self) -> Option<&str> {
+        match self {
+            ReadyCheckDecision::Accept { .. } => None,
+            ReadyCheckDecision::Decline { reason } | ReadyCheckDecision::Ignore { reason } => Some(reason),
+        }
+    }
+}
+
+impl ReadyCheckRules {
+    /// Reject rules that would silently not apply as set
+    pub fn validate(&self) -> CommandResult<()> {
+        for (field, delay_secs) in [("min_delay_secs", self.min_delay_secs), ("max_delay_secs", self.max_delay_secs)] {
+            if delay_secs > MAX_ACCEPT_DELAY_SECS {
+                return Err(invalid(
+                    field,
+                    format!("must be at most {} seconds, before the ready check expires", MAX_ACCEPT_DELAY_SECS),
+                ));
+            }
+        }
+
+        if self.min_delay_secs > self.max_delay_secs {
+            return Err(invalid("min_delay_secs", "must not be above max_delay_secs"));
+        }
+
+        for (i, hours) in self.quiet_hours.iter().enumerate() {
+            for (name, hour) in [("start_hour", hours.start_hour), ("end_hour", hours.end_hour)] {
+                if hour >= 24 {
+                    return Err(invalid(&format!("quiet_hours[{}].{}", i, name), "must be between 0 and 23"));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Decide what to do with a queue pop, given its queue and the current local hour
+    pub fn decide(&self, queue_id: Option<i32>, local_hour: u32) -> ReadyCheckDecision {
+        if !self.enabled {
+            return ReadyCheckDecision::Ignore { reason: "auto-accept disabled".to_string() };
+        }
+
+        let rejection = if self.quiet_hours.iter().any(|h| h.contains(local_hour)) {
+            Some(format!("quiet hours ({}h)", local_hour))
+        } else if self.mode == AcceptMode::QueuesOnly
+            && !queue_id.is_some_and(|id| self.queue_ids.contains(&id))
+        {
+            Some(format!("queue {:?} not allowed", queue_id))
+        } else {
+            None
+        };
+
+        if let Some(reason) = rejection {
+            return if self.decline_unmatched {
+                ReadyCheckDecision::Decline { reason }
+            } else {
+                ReadyCheckDecision::Ignore { reason }
+            };
+        }
+
+        let min_delay_secs = self.min_delay_secs.min(MAX_ACCEPT_DELAY_SECS);
+        let max_delay_secs = self.max_delay_secs.min(MAX_ACCEPT_DELAY_SECS);
+
+        let delay_ms = if max_delay_secs > min_delay_secs {
+            rand::thread_rng().gen_range(min_delay_secs * 1000..=max_delay_secs * 1000)
+        } else {
+            min_delay_secs * 1000
+        };
+
+        ReadyCheckDecision::Accept { delay_ms }
+    }
+}
+
+fn invalid(field: &str, reason: impl Into<String>) -> GalphaError {
+    GalphaError::InvalidConfig { field: format!("ready_check.{}", field), reason: reason.into() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RANKED_SOLO: i32 = 420;
+    const ARAM: i32 = 450;
+
+    fn rules() -> ReadyCheckRules {
+        ReadyCheckRules { enabled: true, ..ReadyCheckRules::default() }
+    }
+
+    #[test]
+    fn quiet_hours_within_a_day() {
+        let hours = QuietHours { start_hour: 9, end_hour: 17 };
+
+        assert!(hours.contains(9));
+        assert!(hours.contains(16));
+        assert!(!hours.contains(17));
+        assert!(!hours.contains(3));
+    }
+
+    #[test]
+    fn quiet_hours_across_midnight() {
+        let hours = QuietHours { start_hour: 23, end_hour: 7 };
+
+        assert!(hours.contains(23));
+        assert!(hours.contains(0));
+        assert!(hours.contains(6));
+        assert!(!hours.contains(7));
+        assert!(!hours.contains(22));
+        assert!(!hours.contains(12));
+    }
+
+    #[test]
+    fn ignores_everything_when_disabled() {
+        let decision = ReadyCheckRules::default().decide(Some(RANKED_SOLO), 12);
+
+        assert_eq!(decision, ReadyCheckDecision::Ignore { reason: "auto-accept disabled".to_string() });
+    }
+
+    #[test]
+    fn accepts_only_listed_queues() {
+        let rules = ReadyCheckRules { mode: AcceptMode::QueuesOnly, queue_ids: vec![RANKED_SOLO], ..rules() };
+
+        assert_eq!(rules.decide(Some(RANKED_SOLO), 12), ReadyCheckDecision::Accept { delay_ms: 0 });
+        assert_eq!(rules.decide(Some(ARAM), 12).action(), "ignore");
+        assert_eq!(rules.decide(None, 12).action(), "ignore");
+    }
+
+    #[test]
+    fn declines_during_quiet_hours_when_asked() {
+        let quiet_hours = vec![QuietHours { start_hour: 23, end_hour: 7 }];
+        let ignoring = ReadyCheckRules { quiet_hours: quiet_hours.clone(), ..rules() };
+        let declining = ReadyCheckRules { quiet_hours, decline_unmatched: true, ..rules() };
+
+        assert_eq!(ignoring.decide(Some(RANKED_SOLO), 2).action(), "ignore");
+        assert_eq!(declining.decide(Some(RANKED_SOLO), 2), ReadyCheckDecision::Decline { reason: "quiet hours (2h)".to_string() });
+        assert_eq!(declining.decide(Some(RANKED_SOLO), 12).action(), "accept");
+    }
+
+    #[test]
+    fn delays_within_the_range() {
+        let rules = ReadyCheckRules { min_delay_secs: 2, max_delay_secs: 4, ..rules() };
+
+        for _ in 0..50 {
+            let ReadyCheckDecision::Accept { delay_ms } = rules.decide(None, 12) else { panic!("not accepted") };
+            assert!((2000..=4000).contains(&delay_ms));
+        }
+    }
+
+    #[test]
+    fn caps_the_delay_before_the_pop_expires() {
+        let rules = ReadyCheckRules { min_delay_secs: 30, max_delay_secs: 60, ..rules() };
+
+        assert_eq!(rules.decide(None, 12), ReadyCheckDecision::Accept { delay_ms: MAX_ACCEPT_DELAY_SECS * 1000 });
+    }
+
+    fn invalid_field(rules: ReadyCheckRules) -> String {
+        match rules.validate() {
+            Err(GalphaError::InvalidConfig { field, .. }) => field,
+            other => panic!("expected an invalid setting, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn validates_delays() {
+        assert!(ReadyCheckRules { min_delay_secs: 2, max_delay_secs: 5, ..rules() }.validate().is_ok());
+
+        let rules_with = |min_delay_secs, max_delay_secs| ReadyCheckRules { min_delay_secs, max_delay_secs, ..rules() };
+        assert_eq!(invalid_field(rules_with(2, 30)), "ready_check.max_delay_secs");
+        assert_eq!(invalid_field(rules_with(30, 5)), "ready_check.min_delay_secs");
+        assert_eq!(invalid_field(rules_with(6, 5)), "ready_check.min_delay_secs");
+    }
+
+    #[test]
+    fn validates_quiet_hours() {
+        let with_hours = |start_hour, end_hour| ReadyCheckRules {
+            quiet_hours: vec![QuietHours { start_hour: 23, end_hour: 7 }, QuietHours { start_hour, end_hour }],
+            ..rules()
+        };
+
+        assert!(with_hours(0, 23).validate().is_ok());
+        assert_eq!(invalid_field(with_hours(25, 7)), "ready_check.quiet_hours[1].start_hour");
+        assert_eq!(invalid_field(with_hours(22, 24)), "ready_check.quiet_hours[1].end_hour");
+    }
+}
@@ -1,31 +1,60 @@
+mod config;
+mod error;
 mod lcu;
+mod secrets;
 mod riot_api;
 mod database;
 mod discord;
+mod live_client;
+mod static_data;
+mod sync;
 
 #[cfg(not(target_os = "macos"))]
 mod recorder;
-
-use lcu::{LolDetector, LcuConnector, ActiveGameInfo};
-use riot_api::{RiotApiClient, MatchDetails};
-use database::{Database, DbSummoner, DbMatch, PlayerStats, RankedStatsCache, MatchCacheMetadata};
+#[cfg(test)]
+mod mock_http;
+
+use config::{redact_api_key, Config, ConfigStore, CONFIG_CHANGED_EVENT};
+use error::{CommandResult, GalphaError};
+use lcu::{LolDetector, LcuConnector, CredentialSource, ActiveGameInfo, GameflowMonitor, GameflowPhase, ReadyCheckDecision, ReadyCheckRules, track_draft};
+use riot_api::{RiotApiClient, MatchDetails, MatchIdQuery, Platform, QueueType};
+use database::{Database, DbSummoner, DbMatch, DbMatchTimeline, DbDraftTimeline, DbReadyCheckDecision, DbSetting, PlayerStats, GroupStats, TrackedAccount, ChampionStats, ChampionStatsQuery, RoleBreakdown, StatsFilter, MatchCacheMetadata, DbRankedSnapshot, LpPoint, MatchLpChange, SeasonPeak};
 use discord::{DiscordOAuth, DiscordUser};
+use secrets::Secrets;
+use live_client::{LiveClient, LiveEventPoller, AllGameData};
+use static_data::{Champion, DataDragon, Item, Rune, RuneTree, StaticData, SummonerSpell};
+use sync::{MatchSyncWorker, SyncProgress, SYNC_PROGRESS_EVENT};
 
 #[cfg(not(target_os = "macos"))]
 use recorder::{Recorder, RecordingQuality};
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use tauri::State;
-use chrono::Utc;
+use tokio::sync::{Mutex, RwLock};
+use tokio::sync::broadcast::error::RecvError;
+use tauri::{AppHandle, Emitter, State};
+use chrono::{Local, Timelike, Utc};
+use anyhow::Context;
 use tracing_subscriber;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
+
+const READY_CHECK_RULES_KEY: &str = "ready_check_rules";
+const SOLO_QUEUE_TYPE: &str = "RANKED_SOLO_5x5";
 
 // Application state - simplified to avoid Send issues
 pub struct AppState {
     detector: Arc<Mutex<LolDetector>>,
     db: Arc<Mutex<Option<Database>>>,
-    api_key: Arc<Mutex<Option<String>>>,
-    region: Arc<Mutex<String>>,
+    /// Shared by every command, replaced when the API key changes
+    riot_client: Arc<RwLock<Option<Arc<RiotApiClient>>>>,
+    /// config.toml, stored settings and environment variables, services subscribe to changes
+    config: ConfigStore,
+    /// Encrypted values of the database, e.g. the Discord token
+    secrets: Arc<Secrets>,
+    /// Missing when its HTTP client couldn't be built, static data then stays unloaded
+    data_dragon: Option<DataDragon>,
+    /// Names of the champions, items, runes and spells of the latest patch
+    static_data: Arc<RwLock<Option<Arc<StaticData>>>>,
+    gameflow: GameflowMonitor,
+    match_sync: MatchSyncWorker,
     #[cfg(not(target_os = "macos"))]
     recorder: Arc<Mutex<Recorder>>,
 }
@@ -37,39 +66,117 @@ fn init_logging() {
         .init();
 }
 
+/// Connect to the League client, reporting why it isn't reachable
+async fn connect_lcu(config: &ConfigStore) -> CommandResult<LcuConnector> {
+    LcuConnector::with_lockfile_paths(&config.get().lcu.lockfile_paths()).await.map_err(|e| match GalphaError::from(e) {
+        GalphaError::Internal(message) => GalphaError::LcuNotReady(message),
+        error => error,
+    })
+}
+
+/// The shared Riot API client
+async fn riot_client(state: &AppState) -> CommandResult<Arc<RiotApiClient>> {
+    state.riot_client.read().await
+        .clone()
+        .ok_or(GalphaError::RiotNotConfigured)
+}
+
+/// Static data of the latest patch, once loaded
+async fn static_data(state: &AppState) -> CommandResult<Arc<StaticData>> {
+    state.static_data.read().await
+        .clone()
+        .ok_or(GalphaError::StaticDataNotLoaded)
+}
+
+/// Platform for a call: the one requested, else the default region
+async fn resolve_region(state: &AppState, region: Option<String>) -> CommandResult<Platform> {
+    match region {
+        Some(region) => region.parse(),
+        None => Ok(state.config.get().riot_api.region),
+    }
+}
+
+/// Replace the Riot client in one step, keeping the current one (and its rate limits) if the key didn't change
+async fn set_riot_client(riot_client: &RwLock<Option<Arc<RiotApiClient>>>, api_key: String) -> CommandResult<()> {
+    let mut client_lock = riot_client.write().await;
+
+    if client_lock.as_ref().is_some_and(|client| client.uses_api_key(&api_key)) {
+        return Ok(());
+    }
+
+    let client = RiotApiClient::new(api_key)
+        .context("Failed to create Riot API client")?;
+    *client_lock = Some(Arc::new(client));
+    info!("Riot API client rebuilt for the new API key");
+
+    Ok(())
+}
+
+/// Open the configured database and apply the settings stored in it
+async fn open_database(state: &AppState) -> CommandResult<Database> {
+    let db = Database::new(state.config.get().database.path()).await
+        .context("Failed to initialize database")?;
+
+    state.config.reload(db.pool()).await
+        .context("Failed to load settings")?;
+
+    Ok(db)
+}
+
 // Tauri commands
 #[tauri::command]
-async fn check_lol_running(state: State<'_, AppState>) -> Result<bool, String> {
+async fn check_lol_running(state: State<'_, AppState>) -> CommandResult<bool> {
     let detector = state.detector.lock().await;
     Ok(detector.is_lol_running())
 }
 
+/// How the League client's port and password were found: process arguments or a lockfile
 #[tauri::command]
-async fn get_current_summoner(_state: State<'_, AppState>) -> Result<serde_json::Value, String> {
-    let connector = LcuConnector::new().await
-        .map_err(|e| format!("Failed to connect to LCU: {}", e))?;
+async fn get_lcu_connection(state: State<'_, AppState>) -> CommandResult<CredentialSource> {
+    let connector = connect_lcu(&state.config).await?;
+
+    Ok(connector.credential_source().clone())
+}
+
+#[tauri::command]
+async fn get_current_summoner(state: State<'_, AppState>) -> CommandResult<serde_json::Value> {
+    let connector = connect_lcu(&state.config).await?;
 
     let summoner = connector.get_current_summoner().await
-        .map_err(|e| format!("Failed to get summoner: {}", e))?;
+        .context("Failed to get summoner")?;
 
     Ok(serde_json::to_value(summoner).unwrap())
 }
 
 #[tauri::command]
-async fn get_active_game(_state: State<'_, AppState>) -> Result<Option<ActiveGameInfo>, String> {
-    let connector = LcuConnector::new().await
-        .map_err(|e| format!("Failed to connect to LCU: {}", e))?;
+async fn get_active_game(state: State<'_, AppState>) -> CommandResult<Option<ActiveGameInfo>> {
+    let connector = connect_lcu(&state.config).await?;
 
     let active_game = connector.get_active_game().await
-        .map_err(|e| format!("Failed to get active game: {}", e))?;
+        .context("Failed to get active game")?;
 
     Ok(active_game)
 }
 
 #[tauri::command]
-async fn initialize_database(state: State<'_, AppState>) -> Result<(), String> {
-    let db = Database::new(None).await
-        .map_err(|e| format!("Failed to initialize database: {}", e))?;
+async fn get_gameflow_phase(state: State<'_, AppState>) -> CommandResult<GameflowPhase> {
+    Ok(state.gameflow.current_phase())
+}
+
+#[tauri::command]
+async fn get_live_game_data() -> CommandResult<AllGameData> {
+    let client = LiveClient::new()
+        .context("Failed to create live client")?;
+
+    let data = client.get_all_game_data().await
+        .context("Failed to get live game data")?;
+
+    Ok(data)
+}
+
+#[tauri::command]
+async fn initialize_database(state: State<'_, AppState>) -> CommandResult<()> {
+    let db = open_database(&state).await?;
 
     let mut db_lock = state.db.lock().await;
     *db_lock = Some(db);
@@ -85,10 +192,10 @@ async fn save_summoner(
     tag_line: String,
     summoner_level: Option<i32>,
     profile_icon_id: Option<i32>,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     let db_lock = state.db.lock().await;
     let db = db_lock.as_ref()
-        .ok_or("Database not initialized")?;
+        .ok_or(GalphaError::DbNotInitialized)?;
 
     let summoner = DbSummoner {
         puuid,
@@ -102,7 +209,7 @@ async fn save_summoner(
     };
 
     summoner.insert_or_update(db.pool()).await
-        .map_err(|e| format!("Failed to save summoner: {}", e))?;
+        .context("Failed to save summoner")?;
 
     Ok(())
 }
@@ -111,28 +218,65 @@ async fn save_summoner(
 async fn get_player_stats(
     state: State<'_, AppState>,
     puuid: String,
-) -> Result<serde_json::Value, String> {
+    queue: Option<QueueType>,
+) -> CommandResult<serde_json::Value> {
     let db_lock = state.db.lock().await;
     let db = db_lock.as_ref()
-        .ok_or("Database not initialized")?;
+        .ok_or(GalphaError::DbNotInitialized)?;
 
-    let stats = PlayerStats::calculate(db.pool(), &puuid).await
-        .map_err(|e| format!("Failed to calculate stats: {}", e))?;
+    let stats = PlayerStats::calculate(db.pool(), &puuid, queue).await
+        .context("Failed to calculate stats")?;
 
     Ok(serde_json::to_value(stats).unwrap())
 }
 
+/// Stats per champion (and role or queue when asked), most played first
+#[tauri::command]
+async fn get_champion_stats(
+    state: State<'_, AppState>,
+    puuid: String,
+    query: Option<ChampionStatsQuery>,
+) -> CommandResult<Vec<ChampionStats>> {
+    let db_lock = state.db.lock().await;
+    let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
+
+    let stats = ChampionStats::query(db.pool(), &puuid, &query.unwrap_or_default()).await
+        .context("Failed to get champion stats")?;
+
+    Ok(stats)
+}
+
+/// Stats per position with the best and worst roles
+#[tauri::command]
+async fn get_role_stats(
+    state: State<'_, AppState>,
+    puuid: String,
+    filter: Option<StatsFilter>,
+) -> CommandResult<RoleBreakdown> {
+    let db_lock = state.db.lock().await;
+    let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
+
+    let breakdown = RoleBreakdown::for_puuid(db.pool(), &puuid, &filter.unwrap_or_default()).await
+        .context("Failed to get role stats")?;
+
+    Ok(breakdown)
+}
+
+/// `api_key` defaults to the configured one, which the webview only gets redacted
 #[tauri::command]
 async fn initialize_riot_client(
     state: State<'_, AppState>,
-    api_key: String,
+    api_key: Option<String>,
     region: String,
-) -> Result<(), String> {
-    let mut key_lock = state.api_key.lock().await;
-    *key_lock = Some(api_key);
+) -> CommandResult<()> {
+    let region: Platform = region.parse()?;
+    let api_key = api_key
+        .or_else(|| state.config.get().riot_api.api_key)
+        .ok_or(GalphaError::RiotNotConfigured)?;
+    set_riot_client(&state.riot_client, api_key).await?;
 
-    let mut region_lock = state.region.lock().await;
-    *region_lock = region;
+    // Not saved, the settings page does that
+    state.config.update_session(serde_json::json!({ "riot_api": { "region": region } }))?;
 
     Ok(())
 }
@@ -142,20 +286,18 @@ async fn fetch_match_history(
     state: State<'_, AppState>,
     puuid: String,
     count: usize,
-) -> Result<Vec<String>, String> {
-    // Create client on demand to avoid Send issues
-    let key_lock = state.api_key.lock().await;
-    let api_key = key_lock.as_ref()
-        .ok_or("Riot API client not initialized")?.clone();
-    drop(key_lock);
-
-    let region_lock = state.region.lock().await;
-    let region = region_lock.clone();
-    drop(region_lock);
-
-    let client = RiotApiClient::new(api_key, region);
-    let match_ids = client.get_match_ids(&puuid, 0, count).await
-        .map_err(|e| format!("Failed to fetch match IDs: {}", e))?;
+    region: Option<String>,
+    query: Option<MatchIdQuery>,
+) -> CommandResult<Vec<String>> {
+    let client = riot_client(&state).await?;
+    let region = resolve_region(&state, region).await?;
+
+    // More than 100 IDs spans several pages
+    let match_ids = client.match_id_pages(region, &puuid, query.unwrap_or_default())
+        .limit(count)
+        .collect_all()
+        .await
+        .context("Failed to fetch match IDs")?;
 
     Ok(match_ids)
 }
@@ -164,13 +306,13 @@ async fn fetch_match_history(
 async fn get_recent_matches(
     state: State<'_, AppState>,
     limit: i32,
-) -> Result<Vec<serde_json::Value>, String> {
+) -> CommandResult<Vec<serde_json::Value>> {
     let db_lock = state.db.lock().await;
     let db = db_lock.as_ref()
-        .ok_or("Database not initialized")?;
+        .ok_or(GalphaError::DbNotInitialized)?;
 
     let matches = DbMatch::get_all(db.pool(), limit).await
-        .map_err(|e| format!("Failed to get matches: {}", e))?;
+        .context("Failed to get matches")?;
 
     let json_matches: Vec<serde_json::Value> = matches
         .into_iter()
@@ -185,21 +327,12 @@ async fn get_account_by_riot_id(
     state: State<'_, AppState>,
     game_name: String,
     tag_line: String,
-) -> Result<serde_json::Value, String> {
-    // Get API key and region
-    let key_lock = state.api_key.lock().await;
-    let api_key = key_lock.as_ref()
-        .ok_or("Riot API client not initialized. Please set your API key first.")?.clone();
-    drop(key_lock);
-
-    let region_lock = state.region.lock().await;
-    let region = region_lock.clone();
-    drop(region_lock);
-
-    // Create client and fetch account
-    let client = RiotApiClient::new(api_key, region);
-    let account = client.get_account_by_riot_id(&game_name, &tag_line).await
-        .map_err(|e| format!("Failed to fetch account: {}", e))?;
+    region: Option<String>,
+) -> CommandResult<serde_json::Value> {
+    let client = riot_client(&state).await?;
+    let region = resolve_region(&state, region).await?;
+    let account = client.get_account_by_riot_id(region, &game_name, &tag_line).await
+        .context("Failed to fetch account")?;
 
     Ok(serde_json::to_value(account).unwrap())
 }
@@ -208,21 +341,12 @@ async fn get_account_by_riot_id(
 async fn get_summoner_by_puuid(
     state: State<'_, AppState>,
     puuid: String,
-) -> Result<serde_json::Value, String> {
-    // Get API key and region
-    let key_lock = state.api_key.lock().await;
-    let api_key = key_lock.as_ref()
-        .ok_or("Riot API client not initialized")?.clone();
-    drop(key_lock);
-
-    let region_lock = state.region.lock().await;
-    let region = region_lock.clone();
-    drop(region_lock);
-
-    // Create client and fetch summoner
-    let client = RiotApiClient::new(api_key, region);
-    let summoner = client.get_summoner_by_puuid(&puuid).await
-        .map_err(|e| format!("Failed to fetch summoner: {}", e))?;
+    region: Option<String>,
+) -> CommandResult<serde_json::Value> {
+    let client = riot_client(&state).await?;
+    let region = resolve_region(&state, region).await?;
+    let summoner = client.get_summoner_by_puuid(region, &puuid).await
+        .context("Failed to fetch summoner")?;
 
     Ok(serde_json::to_value(summoner).unwrap())
 }
@@ -231,21 +355,12 @@ async fn get_summoner_by_puuid(
 async fn get_ranked_stats(
     state: State<'_, AppState>,
     summoner_id: String,
-) -> Result<serde_json::Value, String> {
-    // Get API key and region
-    let key_lock = state.api_key.lock().await;
-    let api_key = key_lock.as_ref()
-        .ok_or("Riot API client not initialized")?.clone();
-    drop(key_lock);
-
-    let region_lock = state.region.lock().await;
-    let region = region_lock.clone();
-    drop(region_lock);
-
-    // Create client and fetch ranked stats
-    let client = RiotApiClient::new(api_key, region);
-    let ranked_stats = client.get_ranked_stats(&summoner_id).await
-        .map_err(|e| format!("Failed to fetch ranked stats: {}", e))?;
+    region: Option<String>,
+) -> CommandResult<serde_json::Value> {
+    let client = riot_client(&state).await?;
+    let region = resolve_region(&state, region).await?;
+    let ranked_stats = client.get_ranked_stats(region, &summoner_id).await
+        .context("Failed to fetch ranked stats")?;
 
     Ok(serde_json::to_value(ranked_stats).unwrap())
 }
@@ -254,42 +369,81 @@ async fn get_ranked_stats(
 async fn get_ranked_stats_by_puuid(
     state: State<'_, AppState>,
     puuid: String,
-) -> Result<serde_json::Value, String> {
-    // Get API key and region
-    let key_lock = state.api_key.lock().await;
-    let api_key = key_lock.as_ref()
-        .ok_or("Riot API client not initialized")?.clone();
-    drop(key_lock);
-
-    let region_lock = state.region.lock().await;
-    let region = region_lock.clone();
-    drop(region_lock);
-
-    // Create client and fetch ranked stats by PUUID
-    let client = RiotApiClient::new(api_key, region);
-    let ranked_stats = client.get_ranked_stats_by_puuid(&puuid).await
-        .map_err(|e| format!("Failed to fetch ranked stats by PUUID: {}", e))?;
+    region: Option<String>,
+) -> CommandResult<serde_json::Value> {
+    let client = riot_client(&state).await?;
+    let region = resolve_region(&state, region).await?;
+    let ranked_stats = client.get_ranked_stats_by_puuid(region, &puuid).await
+        .context("Failed to fetch ranked stats by PUUID")?;
+
+    // Every fetch is a point of the LP history
+    let db_lock = state.db.lock().await;
+    if let Some(db) = db_lock.as_ref() {
+        if let Err(e) = DbRankedSnapshot::store_league_entries(db.pool(), &puuid, &ranked_stats).await {
+            warn!("Failed to store ranked snapshot: {}", e);
+        }
+    }
 
     Ok(serde_json::to_value(ranked_stats).unwrap())
 }
 
+/// LP over time of a queue (Solo/Duo by default), oldest first
 #[tauri::command]
-async fn get_match_details(
+async fn get_lp_history(
     state: State<'_, AppState>,
-    match_id: String,
-) -> Result<serde_json::Value, String> {
-    let key_lock = state.api_key.lock().await;
-    let api_key = key_lock.as_ref()
-        .ok_or("Riot API client not initialized")?.clone();
-    drop(key_lock);
+    puuid: String,
+    queue_type: Option<String>,
+    since: Option<i64>,
+) -> CommandResult<Vec<LpPoint>> {
+    let db_lock = state.db.lock().await;
+    let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
 
-    let region_lock = state.region.lock().await;
-    let region = region_lock.clone();
-    drop(region_lock);
+    let queue_type = queue_type.unwrap_or_else(|| SOLO_QUEUE_TYPE.to_string());
+    let series = LpPoint::series(db.pool(), &puuid, &queue_type, since).await
+        .context("Failed to get LP history")?;
 
-    let client = RiotApiClient::new(api_key, region);
-    let match_details = client.get_match_details(&match_id).await
-        .map_err(|e| format!("Failed to fetch match details: {}", e))?;
+    Ok(series)
+}
+
+/// LP won or lost in each recent ranked match, newest first
+#[tauri::command]
+async fn get_lp_changes(
+    state: State<'_, AppState>,
+    puuid: String,
+    queue_type: Option<String>,
+    limit: Option<usize>,
+) -> CommandResult<Vec<MatchLpChange>> {
+    let db_lock = state.db.lock().await;
+    let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
+
+    let queue_type = queue_type.unwrap_or_else(|| SOLO_QUEUE_TYPE.to_string());
+    let changes = MatchLpChange::for_puuid(db.pool(), &puuid, &queue_type, limit.unwrap_or(20)).await
+        .context("Failed to get LP changes")?;
+
+    Ok(changes)
+}
+
+#[tauri::command]
+async fn get_peak_ranks(state: State<'_, AppState>, puuid: String) -> CommandResult<Vec<SeasonPeak>> {
+    let db_lock = state.db.lock().await;
+    let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
+
+    let peaks = SeasonPeak::for_puuid(db.pool(), &puuid).await
+        .context("Failed to get peak ranks")?;
+
+    Ok(peaks)
+}
+
+#[tauri::command]
+async fn get_match_details(
+    state: State<'_, AppState>,
+    match_id: String,
+    region: Option<String>,
+) -> CommandResult<serde_json::Value> {
+    let client = riot_client(&state).await?;
+    let region = resolve_region(&state, region).await?;
+    let match_details = client.get_match_details(region, &match_id).await
+        .context("Failed to fetch match details")?;
 
     Ok(serde_json::to_value(match_details).unwrap())
 }
@@ -299,9 +453,10 @@ async fn get_match_details(
 async fn fetch_match_details_cached(
     state: State<'_, AppState>,
     match_id: String,
-) -> Result<serde_json::Value, String> {
+    region: Option<String>,
+) -> CommandResult<serde_json::Value> {
     let db_lock = state.db.lock().await;
-    let db = db_lock.as_ref().ok_or("Database not initialized")?;
+    let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
 
     // Check cache first
     if let Ok(Some(cached_match)) = DbMatch::get_by_match_id(db.pool(), &match_id).await {
@@ -312,52 +467,79 @@ async fn fetch_match_details_cached(
     info!("Cache MISS for match {}", match_id);
 
     // Not in cache, fetch from API
-    let key_lock = state.api_key.lock().await;
-    let api_key = key_lock.as_ref().ok_or("Riot API client not initialized")?.clone();
-    drop(key_lock);
-
-    let region_lock = state.region.lock().await;
-    let region = region_lock.clone();
-    drop(region_lock);
-
-    let client = RiotApiClient::new(api_key, region);
-    let match_details = client.get_match_details(&match_id).await
-        .map_err(|e| format!("Failed to fetch match details: {}", e))?;
+    let client = riot_client(&state).await?;
+    let region = resolve_region(&state, region).await?;
+    let match_details = client.get_match_details(region, &match_id).await
+        .context("Failed to fetch match details")?;
 
     // Store in cache
-    let db_match = DbMatch {
-        match_id: match_details.metadata.match_id.clone(),
-        game_creation: match_details.info.game_creation,
-        game_duration: match_details.info.game_duration,
-        game_mode: match_details.info.game_mode.clone(),
-        game_type: match_details.info.game_type.clone(),
-        queue_id: match_details.info.queue_id,
-        map_id: match_details.info.map_id,
-        platform_id: match_details.info.platform_id.clone(),
-        game_version: match_details.info.game_version.clone(),
-        data: serde_json::to_string(&match_details).unwrap(),
-        created_at: Utc::now().timestamp(),
-    };
-
-    if let Err(e) = db_match.insert(db.pool()).await {
+    if let Err(e) = DbMatch::store(db.pool(), &match_details).await {
         warn!("Failed to cache match {}: {}", match_id, e);
     }
 
     Ok(serde_json::to_value(match_details).unwrap())
 }
 
+#[tauri::command]
+async fn get_match_timeline(
+    state: State<'_, AppState>,
+    match_id: String,
+    region: Option<String>,
+) -> CommandResult<serde_json::Value> {
+    let db_lock = state.db.lock().await;
+    let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
+
+    if let Ok(Some(cached)) = DbMatchTimeline::get_by_match_id(db.pool(), &match_id).await {
+        debug!("Cache HIT for timeline {}", match_id);
+        return Ok(serde_json::from_str(&cached.data).unwrap());
+    }
+
+    let client = riot_client(&state).await?;
+    let region = resolve_region(&state, region).await?;
+    // Kept as received, the typed model drops the events it has no variant for
+    let timeline = client.get_match_timeline_json(region, &match_id).await
+        .context("Failed to fetch match timeline")?;
+
+    if let Err(e) = cache_timeline(db.pool(), &client, region, &match_id, &timeline).await {
+        warn!("Failed to cache timeline {}: {:#}", match_id, e);
+    }
+
+    Ok(timeline)
+}
+
+/// match_timelines references matches, so the match is stored first
+async fn cache_timeline(
+    pool: &sqlx::SqlitePool,
+    client: &RiotApiClient,
+    region: Platform,
+    match_id: &str,
+    timeline: &serde_json::Value,
+) -> anyhow::Result<()> {
+    if !DbMatch::exists(pool, match_id).await? {
+        let details = client.get_match_details(region, match_id).await?;
+        DbMatch::store(pool, &details).await?;
+    }
+
+    let db_timeline = DbMatchTimeline {
+        match_id: match_id.to_string(),
+        data: timeline.to_string(),
+        created_at: Utc::now().timestamp(),
+    };
+    db_timeline.insert(pool).await
+}
+
 // NEW: Get cached matches for a player
 #[tauri::command]
 async fn get_cached_matches(
     state: State<'_, AppState>,
     puuid: String,
     limit: i32,
-) -> Result<Vec<serde_json::Value>, String> {
+) -> CommandResult<Vec<serde_json::Value>> {
     let db_lock = state.db.lock().await;
-    let db = db_lock.as_ref().ok_or("Database not initialized")?;
+    let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
 
     let matches = DbMatch::get_by_puuid(db.pool(), &puuid, limit).await
-        .map_err(|e| format!("Failed to get cached matches: {}", e))?;
+        .context("Failed to get cached matches")?;
 
     let json_matches: Vec<serde_json::Value> = matches
         .into_iter()
@@ -367,78 +549,414 @@ async fn get_cached_matches(
     Ok(json_matches)
 }
 
+/// Keep the match history of a PUUID in sync in the background
+#[tauri::command]
+async fn start_match_sync(
+    state: State<'_, AppState>,
+    puuid: String,
+    region: Option<String>,
+) -> CommandResult<()> {
+    let region = resolve_region(&state, region).await?;
+    let db_lock = state.db.lock().await;
+    let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
+
+    // The sync cursor references the summoner row
+    if DbSummoner::get_by_puuid(db.pool(), &puuid).await?.is_none() {
+        return Err(GalphaError::NotFound(format!("Summoner {} is not saved", puuid)));
+    }
+
+    TrackedAccount::add(db.pool(), &puuid, region.id(), None).await
+        .context("Failed to track account")?;
+    state.match_sync.request_sync();
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn stop_match_sync(state: State<'_, AppState>, puuid: String) -> CommandResult<()> {
+    let db_lock = state.db.lock().await;
+    let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
+
+    // The account stays tracked, with its label, groups and primary flag
+    if !TrackedAccount::set_sync_enabled(db.pool(), &puuid, false).await.context("Failed to stop match sync")? {
+        return Err(GalphaError::NotFound(format!("Account {} is not tracked", puuid)));
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn list_accounts(state: State<'_, AppState>) -> CommandResult<Vec<TrackedAccount>> {
+    let db_lock = state.db.lock().await;
+    let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
+
+    let accounts = TrackedAccount::list(db.pool()).await
+        .context("Failed to list accounts")?;
+
+    Ok(accounts)
+}
+
+/// Look up a Riot ID, save its summoner and track it
+#[tauri::command]
+async fn add_account(
+    state: State<'_, AppState>,
+    game_name: String,
+    tag_line: String,
+    region: Option<String>,
+    label: Option<String>,
+) -> CommandResult<TrackedAccount> {
+    let client = riot_client(&state).await?;
+    let region = resolve_region(&state, region).await?;
+
+    let account = client.get_account_by_riot_id(region, &game_name, &tag_line).await
+        .context("Failed to fetch account")?;
+    let summoner = client.get_summoner_by_puuid(region, &account.puuid).await
+        .context("Failed to fetch summoner")?;
+
+    let db_lock = state.db.lock().await;
+    let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
+
+    DbSummoner {
+        puuid: account.puuid.clone(),
+        game_name: account.game_name,
+        tag_line: account.tag_line,
+        summoner_id: None,
+        account_id: None,
+        summoner_level: Some(summoner.summoner_level),
+        profile_icon_id: Some(summoner.profile_icon_id),
+        last_updated: Utc::now().timestamp(),
+    }
+    .insert_or_update(db.pool()).await
+    .context("Failed to save summoner")?;
+
+    TrackedAccount::add(db.pool(), &account.puuid, region.id(), label.as_deref()).await
+        .context("Failed to track account")?;
+    state.match_sync.request_sync();
+
+    let tracked = TrackedAccount::get(db.pool(), &account.puuid).await
+        .context("Failed to get account")?
+        .ok_or_else(|| GalphaError::NotFound(format!("Account {} is not tracked", account.puuid)))?;
+
+    Ok(tracked)
+}
+
+#[tauri::command]
+async fn remove_account(state: State<'_, AppState>, puuid: String) -> CommandResult<()> {
+    let db_lock = state.db.lock().await;
+    let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
+
+    TrackedAccount::remove(db.pool(), &puuid).await
+        .context("Failed to remove account")?;
+
+    Ok(())
+}
+
+/// Make a tracked account the primary one
+#[tauri::command]
+async fn switch_account(state: State<'_, AppState>, puuid: String) -> CommandResult<TrackedAccount> {
+    let db_lock = state.db.lock().await;
+    let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
+
+    if !TrackedAccount::set_primary(db.pool(), &puuid).await.context("Failed to switch account")? {
+        return Err(GalphaError::NotFound(format!("Account {} is not tracked", puuid)));
+    }
+
+    let account = TrackedAccount::get(db.pool(), &puuid).await
+        .context("Failed to get account")?
+        .ok_or_else(|| GalphaError::NotFound(format!("Account {} is not tracked", puuid)))?;
+
+    Ok(account)
+}
+
+/// Replace the groups of an account, e.g. ["my smurfs", "team roster"]
+#[tauri::command]
+async fn set_account_groups(
+    state: State<'_, AppState>,
+    puuid: String,
+    groups: Vec<String>,
+) -> CommandResult<()> {
+    let db_lock = state.db.lock().await;
+    let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
+
+    if TrackedAccount::get(db.pool(), &puuid).await?.is_none() {
+        return Err(GalphaError::NotFound(format!("Account {} is not tracked", puuid)));
+    }
+
+    TrackedAccount::set_groups(db.pool(), &puuid, &groups).await
+        .context("Failed to set account groups")?;
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_group_stats(
+    state: State<'_, AppState>,
+    group: String,
+    queue: Option<QueueType>,
+) -> CommandResult<GroupStats> {
+    let db_lock = state.db.lock().await;
+    let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
+
+    let stats = GroupStats::calculate(db.pool(), &group, queue).await
+        .context("Failed to calculate group stats")?;
+
+    Ok(stats)
+}
+
+/// Download the latest patch if there is a new one, returns the version in use
+#[tauri::command]
+async fn refresh_static_data(state: State<'_, AppState>) -> CommandResult<String> {
+    let data_dragon = state.data_dragon.as_ref().ok_or(GalphaError::StaticDataNotLoaded)?;
+    let data = data_dragon.load().await
+        .context("Failed to load Data Dragon")?;
+    let version = data.version.clone();
+    *state.static_data.write().await = Some(Arc::new(data));
+
+    Ok(version)
+}
+
+#[tauri::command]
+async fn get_static_data_version(state: State<'_, AppState>) -> CommandResult<String> {
+    Ok(static_data(&state).await?.version.clone())
+}
+
+#[tauri::command]
+async fn get_champion(state: State<'_, AppState>, champion_id: i32) -> CommandResult<Option<Champion>> {
+    Ok(static_data(&state).await?.champion(champion_id).cloned())
+}
+
+/// By `championName` of a match or display name
+#[tauri::command]
+async fn get_champion_by_name(state: State<'_, AppState>, name: String) -> CommandResult<Option<Champion>> {
+    Ok(static_data(&state).await?.champion_by_name(&name).cloned())
+}
+
+#[tauri::command]
+async fn get_item(state: State<'_, AppState>, item_id: i32) -> CommandResult<Option<Item>> {
+    Ok(static_data(&state).await?.item(item_id).cloned())
+}
+
+#[tauri::command]
+async fn get_rune(state: State<'_, AppState>, rune_id: i32) -> CommandResult<Option<Rune>> {
+    Ok(static_data(&state).await?.rune(rune_id).cloned())
+}
+
 #[tauri::command]
-async fn discord_login(client_id: String) -> Result<DiscordUser, String> {
+async fn get_rune_tree(state: State<'_, AppState>, tree_id: i32) -> CommandResult<Option<RuneTree>> {
+    Ok(static_data(&state).await?.rune_tree(tree_id).cloned())
+}
+
+#[tauri::command]
+async fn get_summoner_spell(state: State<'_, AppState>, spell_id: i32) -> CommandResult<Option<SummonerSpell>> {
+    Ok(static_data(&state).await?.summoner_spell(spell_id).cloned())
+}
+
+#[tauri::command]
+async fn sync_matches_now(state: State<'_, AppState>) -> CommandResult<()> {
+    state.match_sync.request_sync();
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_sync_status(
+    state: State<'_, AppState>,
+    puuid: String,
+) -> CommandResult<Option<MatchCacheMetadata>> {
+    let db_lock = state.db.lock().await;
+    let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
+
+    let metadata = MatchCacheMetadata::get(db.pool(), &puuid).await
+        .context("Failed to get sync status")?;
+
+    Ok(metadata)
+}
+
+#[tauri::command]
+async fn get_match_draft(
+    state: State<'_, AppState>,
+    match_id: String,
+) -> CommandResult<Option<serde_json::Value>> {
+    let db_lock = state.db.lock().await;
+    let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
+
+    let draft = DbDraftTimeline::get_by_match_id(db.pool(), &match_id).await
+        .context("Failed to get draft")?;
+
+    let draft = draft.map(|d| serde_json::from_str(&d.data))
+        .transpose()
+        .context("Failed to parse draft")?;
+
+    Ok(draft)
+}
+
+#[tauri::command]
+async fn get_ready_check_rules(state: State<'_, AppState>) -> CommandResult<ReadyCheckRules> {
+    Ok(load_ready_check_rules(&state.db).await)
+}
+
+#[tauri::command]
+async fn set_ready_check_rules(
+    state: State<'_, AppState>,
+    rules: ReadyCheckRules,
+) -> CommandResult<()> {
+    rules.validate()?;
+
+    let db_lock = state.db.lock().await;
+    let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
+
+    let value = serde_json::to_string(&rules).unwrap();
+    DbSetting::set(db.pool(), READY_CHECK_RULES_KEY, &value).await
+        .context("Failed to save ready check rules")?;
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_ready_check_log(
+    state: State<'_, AppState>,
+    limit: i32,
+) -> CommandResult<Vec<DbReadyCheckDecision>> {
+    let db_lock = state.db.lock().await;
+    let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
+
+    let decisions = DbReadyCheckDecision::get_recent(db.pool(), limit).await
+        .context("Failed to get ready check log")?;
+
+    Ok(decisions)
+}
+
+#[tauri::command]
+async fn discord_login(state: State<'_, AppState>, client_id: String) -> CommandResult<DiscordUser> {
     let oauth = DiscordOAuth::new(client_id, None);
 
-    let (user, _access_token) = oauth.authenticate().await
-        .map_err(|e| format!("Discord authentication failed: {}", e))?;
+    let (user, token) = oauth.authenticate().await
+        .context("Discord authentication failed")?;
 
-    // TODO: Store access_token securely for future API calls
+    ensure_database(&state).await?;
+    let db_lock = state.db.lock().await;
+    let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
+
+    let token = serde_json::to_string(&token).context("Failed to serialize Discord token")?;
+    state.secrets.set(db.pool(), secrets::DISCORD_TOKEN, &token).await
+        .context("Failed to save Discord token")?;
 
     Ok(user)
 }
 
-// Get API key from database
+/// Re-encrypt the stored secrets with a new master key, returns how many there were
 #[tauri::command]
-async fn get_api_key(state: State<'_, AppState>) -> Result<Option<String>, String> {
-    // Ensure database is initialized
-    {
-        let mut db_lock = state.db.lock().await;
-        if db_lock.is_none() {
-            let database = Database::new(None).await
-                .map_err(|e| format!("Failed to initialize database: {}", e))?;
-            *db_lock = Some(database);
-            info!("Database initialized for API key retrieval");
-        }
+async fn rotate_secrets_key(state: State<'_, AppState>) -> CommandResult<usize> {
+    let db_lock = state.db.lock().await;
+    let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
+
+    let rotated = state.secrets.rotate(db.pool()).await
+        .context("Failed to rotate the master key")?;
+
+    Ok(rotated)
+}
+
+/// Open the database on first use, the API key is stored in it
+async fn ensure_database(state: &AppState) -> CommandResult<()> {
+    let mut db_lock = state.db.lock().await;
+    if db_lock.is_none() {
+        *db_lock = Some(open_database(state).await?);
+        info!("Database initialized for settings access");
     }
 
-    let db_lock = state.db.lock().await;
-    let db = db_lock.as_ref().ok_or("Database not initialized")?;
+    Ok(())
+}
 
-    let result = sqlx::query_as::<_, (String,)>(
-        "SELECT value FROM settings WHERE key = 'riot_api_key'"
-    )
-    .fetch_optional(db.pool())
-    .await
-    .map_err(|e| format!("Failed to get API key: {}", e))?;
+// Get API key from config.toml, the database or the environment, redacted
+#[tauri::command]
+async fn get_api_key(state: State<'_, AppState>) -> CommandResult<Option<String>> {
+    ensure_database(&state).await?;
 
-    Ok(result.map(|(value,)| value))
+    Ok(state.config.get().riot_api.api_key.as_deref().map(redact_api_key))
 }
 
 // Save API key to database
 #[tauri::command]
-async fn set_api_key(state: State<'_, AppState>, api_key: String) -> Result<(), String> {
-    // Ensure database is initialized
+async fn set_api_key(state: State<'_, AppState>, api_key: String) -> CommandResult<()> {
+    ensure_database(&state).await?;
+
     {
-        let mut db_lock = state.db.lock().await;
-        if db_lock.is_none() {
-            let database = Database::new(None).await
-                .map_err(|e| format!("Failed to initialize database: {}", e))?;
-            *db_lock = Some(database);
-            info!("Database initialized for API key storage");
+        let db_lock = state.db.lock().await;
+        let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
+
+        state.config.update(db.pool(), serde_json::json!({ "riot_api": { "api_key": api_key } })).await?;
+    }
+
+    // Use the new key for every following request
+    set_riot_client(&state.riot_client, api_key).await?;
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_config(state: State<'_, AppState>) -> CommandResult<Config> {
+    Ok(state.config.get().redacted())
+}
+
+/// Save a partial config, e.g. `{ "app": { "refresh_interval": 60 } }`.
+/// `database.path` applies on the next start.
+#[tauri::command]
+async fn update_config(state: State<'_, AppState>, patch: serde_json::Value) -> CommandResult<Config> {
+    let config = {
+        let db_lock = state.db.lock().await;
+        let db = db_lock.as_ref().ok_or(GalphaError::DbNotInitialized)?;
+
+        state.config.update(db.pool(), patch).await?
+    };
+
+    if let Some(api_key) = config.riot_api.api_key.clone() {
+        set_riot_client(&state.riot_client, api_key).await?;
+    }
+
+    Ok(config.redacted())
+}
+
+/// Load Data Dragon at startup, from the cache when offline
+async fn load_static_data(data_dragon: DataDragon, static_data: Arc<RwLock<Option<Arc<StaticData>>>>) {
+    match data_dragon.load().await {
+        Ok(data) => {
+            info!("Static data of patch {} loaded", data.version);
+            *static_data.write().await = Some(Arc::new(data));
         }
+        Err(e) => warn!("Failed to load static data: {:#}", e),
     }
+}
 
-    let db_lock = state.db.lock().await;
-    let db = db_lock.as_ref().ok_or("Database not initialized")?;
+/// Rebuild the Riot client when the API key changes and forward every change to the frontend
+async fn run_config_watcher(
+    mut config: tokio::sync::watch::Receiver<Config>,
+    riot_client: Arc<RwLock<Option<Arc<RiotApiClient>>>>,
+    handle: AppHandle,
+) {
+    let mut api_key = config.borrow_and_update().riot_api.api_key.clone();
 
-    let now = Utc::now().timestamp();
+    while config.changed().await.is_ok() {
+        let current = config.borrow_and_update().clone();
 
-    sqlx::query(
-        "INSERT OR REPLACE INTO settings (key, value, updated_at) VALUES ('riot_api_key', ?, ?)"
-    )
-    .bind(&api_key)
-    .bind(now)
-    .execute(db.pool())
-    .await
-    .map_err(|e| format!("Failed to save API key: {}", e))?;
+        if current.riot_api.api_key != api_key {
+            api_key = current.riot_api.api_key.clone();
 
-    // Update the in-memory API key
-    let mut api_key_lock = state.api_key.lock().await;
-    *api_key_lock = Some(api_key);
+            match api_key.clone() {
+                Some(api_key) => {
+                    if let Err(e) = set_riot_client(&riot_client, api_key).await {
+                        warn!("Failed to apply the new API key: {}", e);
+                    }
+                }
+                None => {
+                    *riot_client.write().await = None;
+                    info!("Riot API client removed with the API key");
+                }
+            }
+        }
 
-    Ok(())
+        if let Err(e) = handle.emit(CONFIG_CHANGED_EVENT, current.redacted()) {
+            warn!("Failed to emit config change: {}", e);
+        }
+    }
 }
 
 // Recording commands
@@ -448,7 +966,7 @@ async fn start_recording(
     state: State<'_, AppState>,
     output_dir: String,
     quality: String,
-) -> Result<String, String> {
+) -> CommandResult<String> {
     let recorder = state.recorder.lock().await;
 
     let quality_enum = match quality.as_str() {
@@ -461,9 +979,11 @@ async fn start_recording(
 
     let output_path = std::path::PathBuf::from(output_dir);
 
-    recorder.start_recording(output_path, quality_enum)
+    let session_id = recorder.start_recording(output_path, quality_enum)
         .await
-        .map_err(|e| format!("Failed to start recording: {}", e))
+        .context("Failed to start recording")?;
+
+    Ok(session_id)
 }
 
 #[cfg(target_os = "macos")]
@@ -472,41 +992,45 @@ async fn start_recording(
     _state: State<'_, AppState>,
     _output_dir: String,
     _quality: String,
-) -> Result<String, String> {
-    Err("L'enregistrement vidéo n'est pas encore supporté sur macOS. Cette fonctionnalité est disponible sur Windows uniquement.".to_string())
+) -> CommandResult<String> {
+    Err(GalphaError::Unsupported(
+        "Video recording is not supported on macOS yet. It is currently available on Windows only.".to_string(),
+    ))
 }
 
 #[cfg(not(target_os = "macos"))]
 #[tauri::command]
-async fn stop_recording(state: State<'_, AppState>) -> Result<String, String> {
+async fn stop_recording(state: State<'_, AppState>) -> CommandResult<String> {
     let recorder = state.recorder.lock().await;
 
-    recorder.stop_recording()
+    let output_path = recorder.stop_recording()
         .await
-        .map_err(|e| format!("Failed to stop recording: {}", e))
+        .context("Failed to stop recording")?;
+
+    Ok(output_path)
 }
 
 #[cfg(target_os = "macos")]
 #[tauri::command]
-async fn stop_recording(_state: State<'_, AppState>) -> Result<String, String> {
-    Err("L'enregistrement vidéo n'est pas disponible sur macOS.".to_string())
+async fn stop_recording(_state: State<'_, AppState>) -> CommandResult<String> {
+    Err(GalphaError::Unsupported("Video recording is not available on macOS.".to_string()))
 }
 
 #[cfg(not(target_os = "macos"))]
 #[tauri::command]
-async fn is_recording(state: State<'_, AppState>) -> Result<bool, String> {
+async fn is_recording(state: State<'_, AppState>) -> CommandResult<bool> {
     let recorder = state.recorder.lock().await;
     Ok(recorder.is_recording())
 }
 
 #[cfg(target_os = "macos")]
 #[tauri::command]
-async fn is_recording(_state: State<'_, AppState>) -> Result<bool, String> {
+async fn is_recording(_state: State<'_, AppState>) -> CommandResult<bool> {
     Ok(false)
 }
 
 #[tauri::command]
-async fn list_recordings(directory: String) -> Result<Vec<serde_json::Value>, String> {
+async fn list_recordings(directory: String) -> CommandResult<Vec<serde_json::Value>> {
     use std::fs;
     use std::path::Path;
 
@@ -550,7 +1074,7 @@ async fn list_recordings(directory: String) -> Result<Vec<serde_json::Value>, St
                 }
             }
         }
-        Err(e) => return Err(format!("Failed to read directory: {}", e)),
+        Err(e) => return Err(GalphaError::Internal(format!("Failed to read directory: {}", e))),
     }
 
     // Sort by creation date (newest first)
@@ -563,14 +1087,225 @@ async fn list_recordings(directory: String) -> Result<Vec<serde_json::Value>, St
     Ok(recordings)
 }
 
+/// Record the champ select draft of every game and store it once the game starts
+async fn run_draft_tracking(gameflow: GameflowMonitor, db: Arc<Mutex<Option<Database>>>, config: ConfigStore) {
+    let mut transitions = gameflow.subscribe();
+
+    loop {
+        match transitions.recv().await {
+            Ok(transition) if transition.current == GameflowPhase::ChampSelect => {}
+            Ok(_) | Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => break,
+        }
+
+        let connector = match LcuConnector::with_lockfile_paths(&config.get().lcu.lockfile_paths()).await {
+            Ok(connector) => connector,
+            Err(e) => {
+                warn!("Failed to connect to LCU for draft tracking: {}", e);
+                continue;
+            }
+        };
+
+        // Game IDs are only unique within a platform
+        let platform_id = match connector.get_platform_id().await {
+            Ok(platform_id) => platform_id,
+            Err(e) => {
+                warn!("Failed to get the platform for draft tracking: {}", e);
+                continue;
+            }
+        };
+
+        let timeline = match track_draft(&connector).await {
+            Ok(timeline) => timeline,
+            Err(e) => {
+                warn!("Draft tracking failed: {}", e);
+                continue;
+            }
+        };
+
+        // Wait for the phase that follows champ select: a dodge goes back to the lobby
+        let next_phase = loop {
+            match transitions.recv().await {
+                Ok(transition) if transition.previous == GameflowPhase::ChampSelect => break Some(transition.current),
+                Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break None,
+            }
+        };
+
+        if !matches!(next_phase, Some(GameflowPhase::GameStart | GameflowPhase::InProgress)) || timeline.game_id == 0 {
+            info!("Champ select did not lead to a game, discarding draft");
+            continue;
+        }
+
+        let db_lock = db.lock().await;
+        let Some(db) = db_lock.as_ref() else {
+            warn!("Database not initialized, draft for game {} not saved", timeline.game_id);
+            continue;
+        };
+
+        let draft = DbDraftTimeline {
+            platform_id,
+            game_id: timeline.game_id as i64,
+            data: serde_json::to_string(&timeline).unwrap(),
+            created_at: Utc::now().timestamp(),
+        };
+
+        if let Err(e) = draft.insert(db.pool()).await {
+            warn!("Failed to save draft for game {}: {}", timeline.game_id, e);
+        }
+    }
+}
+
+/// Rules saved in settings, or the (disabled) defaults
+async fn load_ready_check_rules(db: &Arc<Mutex<Option<Database>>>) -> ReadyCheckRules {
+    let db_lock = db.lock().await;
+    let Some(db) = db_lock.as_ref() else {
+        return ReadyCheckRules::default();
+    };
+
+    match DbSetting::get(db.pool(), READY_CHECK_RULES_KEY).await {
+        Ok(Some(value)) => serde_json::from_str(&value).unwrap_or_default(),
+        _ => ReadyCheckRules::default(),
+    }
+}
+
+/// Answer queue pops according to the saved rules, logging every decision
+async fn run_ready_check_handler(gameflow: GameflowMonitor, db: Arc<Mutex<Option<Database>>>, config: ConfigStore) {
+    let mut transitions = gameflow.subscribe();
+
+    loop {
+        match transitions.recv().await {
+            Ok(transition) if transition.current == GameflowPhase::ReadyCheck => {}
+            Ok(_) | Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => break,
+        }
+
+        let rules = load_ready_check_rules(&db).await;
+
+        let connector = match LcuConnector::with_lockfile_paths(&config.get().lcu.lockfile_paths()).await {
+            Ok(connector) => connector,
+            Err(e) => {
+                warn!("Failed to connect to LCU for ready check: {}", e);
+                continue;
+            }
+        };
+
+        let queue_id = connector.get_lobby_queue_id().await.unwrap_or(None);
+        let mut decision = rules.decide(queue_id, Local::now().hour());
+
+        if let ReadyCheckDecision::Accept { delay_ms } = decision {
+            tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+        }
+
+        // The player may have answered (or the pop expired) in the meantime
+        if !matches!(decision, ReadyCheckDecision::Ignore { .. }) {
+            let still_pending = matches!(connector.get_ready_check().await, Ok(Some(ref check)) if check.is_pending());
+
+            if !still_pending {
+                decision = ReadyCheckDecision::Ignore { reason: "ready check no longer pending".to_string() };
+            }
+        }
+
+        let result = match &decision {
+            ReadyCheckDecision::Accept { .. } => connector.accept_ready_check().await,
+            ReadyCheckDecision::Decline { .. } => connector.decline_ready_check().await,
+            ReadyCheckDecision::Ignore { .. } => Ok(()),
+        };
+
+        let reason = match result {
+            Ok(()) => decision.reason().map(String::from),
+            Err(e) => {
+                warn!("Failed to answer ready check: {}", e);
+                Some(format!("failed to {}: {}", decision.action(), e))
+            }
+        };
+
+        info!("Ready check for queue {:?}: {:?}", queue_id, decision);
+
+        let entry = DbReadyCheckDecision {
+            id: None,
+            queue_id,
+            action: decision.action().to_string(),
+            reason,
+            delay_ms: match decision {
+                ReadyCheckDecision::Accept { delay_ms } => delay_ms as i64,
+                _ => 0,
+            },
+            decided_at: Utc::now().timestamp(),
+        };
+
+        let db_lock = db.lock().await;
+        if let Some(db) = db_lock.as_ref() {
+            if let Err(e) = entry.insert(db.pool()).await {
+                warn!("Failed to log ready check decision: {}", e);
+            }
+        }
+    }
+}
+
+/// Poll the Live Client Data API during games and forward new events to the frontend
+async fn run_live_events(gameflow: GameflowMonitor, handle: AppHandle) {
+    let mut transitions = gameflow.subscribe();
+
+    loop {
+        match transitions.recv().await {
+            Ok(transition) if transition.current == GameflowPhase::InProgress => {}
+            Ok(_) | Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => break,
+        }
+
+        let mut poller = match LiveClient::new() {
+            Ok(client) => LiveEventPoller::new(client),
+            Err(e) => {
+                warn!("Failed to create live client: {}", e);
+                continue;
+            }
+        };
+
+        while gameflow.current_phase() == GameflowPhase::InProgress {
+            match poller.poll().await {
+                Ok(events) => {
+                    for event in events {
+                        if let Err(e) = handle.emit("live-game-event", &event) {
+                            warn!("Failed to emit live game event: {}", e);
+                        }
+                    }
+                }
+                // The API is not up yet during loading screens
+                Err(e) => debug!("Live client poll failed: {}", e),
+            }
+
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        }
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     init_logging();
 
     let detector = Arc::new(Mutex::new(LolDetector::new()));
     let db = Arc::new(Mutex::new(None));
-    let api_key = Arc::new(Mutex::new(None));
-    let region = Arc::new(Mutex::new("euw1".to_string()));
+    let secrets = Arc::new(Secrets::new(Secrets::default_key_file()));
+    let config = ConfigStore::load(secrets.clone());
+    // A key from config.toml or the environment works before the database is opened
+    let riot_client = config.get().riot_api.api_key
+        .and_then(|api_key| match RiotApiClient::new(api_key) {
+            Ok(client) => Some(Arc::new(client)),
+            Err(e) => {
+                warn!("Failed to create Riot API client: {}", e);
+                None
+            }
+        });
+    let riot_client = Arc::new(RwLock::new(riot_client));
+    let gameflow = GameflowMonitor::new();
+    let data_dragon = match DataDragon::new(DataDragon::default_dir()) {
+        Ok(data_dragon) => Some(data_dragon),
+        Err(e) => {
+            warn!("Failed to create Data Dragon client, static data won't be loaded: {}", e);
+            None
+        }
+    };
 
     #[cfg(not(target_os = "macos"))]
     let recorder = Arc::new(Mutex::new(Recorder::new()));
@@ -578,23 +1313,71 @@ pub fn run() {
     let app_state = AppState {
         detector,
         db,
-        api_key,
-        region,
+        riot_client,
+        config,
+        secrets,
+        data_dragon: data_dragon.clone(),
+        static_data: Arc::new(RwLock::new(None)),
+        gameflow: gameflow.clone(),
+        match_sync: MatchSyncWorker::new(),
         #[cfg(not(target_os = "macos"))]
         recorder,
     };
+    let draft_db = app_state.db.clone();
+    let ready_check_db = app_state.db.clone();
+    let sync_db = app_state.db.clone();
+    let sync_client = app_state.riot_client.clone();
+    let config_client = app_state.riot_client.clone();
+    let match_sync = app_state.match_sync.clone();
+    let config = app_state.config.clone();
+    let static_data = app_state.static_data.clone();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .manage(app_state)
+        .setup(move |app| {
+            let handle = app.handle().clone();
+
+            tauri::async_runtime::spawn(run_draft_tracking(gameflow.clone(), draft_db, config.clone()));
+            tauri::async_runtime::spawn(run_ready_check_handler(gameflow.clone(), ready_check_db, config.clone()));
+            tauri::async_runtime::spawn(run_live_events(gameflow.clone(), handle.clone()));
+            tauri::async_runtime::spawn(run_config_watcher(config.subscribe(), config_client, handle.clone()));
+            if let Some(data_dragon) = data_dragon {
+                tauri::async_runtime::spawn(load_static_data(data_dragon, static_data));
+            }
+
+            let sync_handle = handle.clone();
+            tauri::async_runtime::spawn(match_sync.run(sync_db, sync_client, gameflow.clone(), config.subscribe(), move |progress: SyncProgress| {
+                if let Err(e) = sync_handle.emit(SYNC_PROGRESS_EVENT, progress) {
+                    warn!("Failed to emit match sync progress: {}", e);
+                }
+            }));
+
+            // Forward every gameflow transition to the frontend
+            let gameflow_config = config.subscribe();
+            tauri::async_runtime::spawn(async move {
+                gameflow.start(gameflow_config, move |transition| {
+                    if let Err(e) = handle.emit("gameflow-phase-changed", transition) {
+                        warn!("Failed to emit gameflow transition: {}", e);
+                    }
+                });
+            });
+
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             check_lol_running,
+            get_lcu_connection,
             get_current_summoner,
             get_active_game,
+            get_gameflow_phase,
+            get_live_game_data,
             initialize_database,
             save_summoner,
             get_player_stats,
+            get_champion_stats,
+            get_role_stats,
             initialize_riot_client,
             fetch_match_history,
             get_recent_matches,
@@ -602,12 +1385,41 @@ pub fn run() {
             get_summoner_by_puuid,
             get_ranked_stats,
             get_ranked_stats_by_puuid,
+            get_lp_history,
+            get_lp_changes,
+            get_peak_ranks,
             get_match_details,
             fetch_match_details_cached,
+            get_match_timeline,
             get_cached_matches,
+            start_match_sync,
+            stop_match_sync,
+            sync_matches_now,
+            refresh_static_data,
+            get_static_data_version,
+            get_champion,
+            get_champion_by_name,
+            get_item,
+            get_rune,
+            get_rune_tree,
+            get_summoner_spell,
+            get_sync_status,
+            list_accounts,
+            add_account,
+            remove_account,
+            switch_account,
+            set_account_groups,
+            get_group_stats,
+            get_match_draft,
+            get_ready_check_rules,
+            set_ready_check_rules,
+            get_ready_check_log,
             discord_login,
             get_api_key,
             set_api_key,
+            get_config,
+            update_config,
+            rotate_secrets_key,
             start_recording,
             stop_recording,
             is_recording,
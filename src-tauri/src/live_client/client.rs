@@ -0,0 +1,107 @@
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use anyhow::{Result, Context};
+use tokio::time::Duration;
+use tracing::debug;
+use super::{ActivePlayer, AllGameData, EventData, LivePlayer};
+
+const DEFAULT_BASE_URL: &str = "https://127.0.0.1:2999";
+const REQUEST_TIMEOUT_MS: u64 = 2000;
+
+/// Client for the in-game Live Client Data API (only reachable while a game is running)
+#[derive(Debug, Clone)]
+pub struct LiveClient {
+    client: Client,
+    base_url: String,
+}
+
+impl LiveClient {
+    pub fn new() -> Result<Self> {
+        Self::with_base_url(DEFAULT_BASE_URL)
+    }
+
+    /// Point the client at another host, e.g. a local stub server
+    pub fn with_base_url(base_url: &str) -> Result<Self> {
+        // The game serves a self-signed certificate
+        let client = Client::builder()
+            .danger_accept_invalid_certs(true)
+            .timeout(Duration::from_millis(REQUEST_TIMEOUT_MS))
+            .build()?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    async fn get<T: DeserializeOwned>(&self, endpoint: &str) -> Result<T> {
+        let url = format!("{}/liveclientdata/{}", self.base_url, endpoint);
+
+        debug!("Fetching live client data from: {}", url);
+
+        let response = self.client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to reach the Live Client Data API. Is a game running?")?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Live Client Data request failed with status {}", response.status());
+        }
+
+        response.json().await
+            .context(format!("Failed to parse live client {}", endpoint))
+    }
+
+    pub async fn get_all_game_data(&self) -> Result<AllGameData> {
+        self.get("allgamedata").await
+    }
+
+    pub async fn get_active_player(&self) -> Result<ActivePlayer> {
+        self.get("activeplayer").await
+    }
+
+    pub async fn get_player_list(&self) -> Result<Vec<LivePlayer>> {
+        self.get("playerlist").await
+    }
+
+    pub async fn get_event_data(&self) -> Result<EventData> {
+        self.get("eventdata").await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::live_client::mock_server::MockLiveClient;
+
+    #[tokio::test]
+    async fn reads_every_endpoint() {
+        let mock = MockLiveClient::start().await.unwrap();
+        let client = LiveClient::with_base_url(&mock.base_url()).unwrap();
+
+        let all = client.get_all_game_data().await.unwrap();
+        assert_eq!(all.active_player.riot_id.as_deref(), Some("Galnir#EUW"));
+        assert_eq!(all.all_players.len(), 3);
+        assert_eq!(all.events.events.len(), 4);
+        assert_eq!(all.game_data.map_number, 11);
+
+        let active = client.get_active_player().await.unwrap();
+        assert_eq!(active.level, 9);
+        assert_eq!(active.champion_stats.max_health, 1287.0);
+        assert_eq!(active.champion_stats.resource_value, 412.3);
+
+        let players = client.get_player_list().await.unwrap();
+        let ahri = &players[0];
+        assert_eq!((ahri.champion_name.as_str(), ahri.team.as_str(), ahri.position.as_str()), ("Ahri", "ORDER", "MIDDLE"));
+        assert_eq!(ahri.scores.creep_score, 96);
+        assert_eq!(ahri.items[0].item_id, 6655);
+        assert!(players[2].is_dead);
+
+        assert_eq!(mock.requests(), vec![
+            "/liveclientdata/allgamedata",
+            "/liveclientdata/activeplayer",
+            "/liveclientdata/playerlist",
+        ]);
+    }
+}
@@ -0,0 +1,94 @@
+//! In-process stand-in for the game's Live Client Data API: HTTPS on a
+//! self-signed certificate, answering `/liveclientdata/*` from canned bodies.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use anyhow::{Context, Result};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+use crate::mock_http::{read_request, tls_acceptor, write_response};
+
+const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/live_client");
+
+#[derive(Default)]
+struct MockState {
+    /// "/liveclientdata/endpoint" -> (status, body)
+    routes: Mutex<HashMap<String, (u16, String)>>,
+    /// Every request received, as "/liveclientdata/endpoint"
+    requests: Mutex<Vec<String>>,
+}
+
+pub struct MockLiveClient {
+    pub port: u16,
+    state: Arc<MockState>,
+}
+
+impl MockLiveClient {
+    /// Start a mock game serving the canned fixtures
+    pub async fn start() -> Result<Self> {
+        let state = Arc::new(MockState::default());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let port = listener.local_addr()?.port();
+        let acceptor = tls_acceptor()?;
+
+        let server_state = state.clone();
+        tokio::spawn(async move {
+            while let Ok((tcp, _)) = listener.accept().await {
+                let acceptor = acceptor.clone();
+                let state = server_state.clone();
+
+                tokio::spawn(async move {
+                    if let Ok(stream) = acceptor.accept(tcp).await {
+                        let _ = handle_connection(stream, state).await;
+                    }
+                });
+            }
+        });
+
+        let mock = Self { port, state };
+        for endpoint in ["allgamedata", "activeplayer", "playerlist", "eventdata"] {
+            mock.set_fixture(endpoint, &format!("{}.json", endpoint))?;
+        }
+
+        Ok(mock)
+    }
+
+    /// Base URL to hand to `LiveClient::with_base_url`
+    pub fn base_url(&self) -> String {
+        format!("https://127.0.0.1:{}", self.port)
+    }
+
+    pub fn set_response(&self, endpoint: &str, status: u16, body: &str) {
+        self.state.routes.lock().unwrap()
+            .insert(format!("/liveclientdata/{}", endpoint), (status, body.to_string()));
+    }
+
+    pub fn set_fixture(&self, endpoint: &str, fixture: &str) -> Result<()> {
+        let body = std::fs::read_to_string(Path::new(FIXTURES_DIR).join(fixture))
+            .context(format!("Missing fixture {}", fixture))?;
+        self.set_response(endpoint, 200, &body);
+        Ok(())
+    }
+
+    pub fn requests(&self) -> Vec<String> {
+        self.state.requests.lock().unwrap().clone()
+    }
+}
+
+async fn handle_connection<S>(mut stream: S, state: Arc<MockState>) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let request = read_request(&mut stream).await?;
+
+    state.requests.lock().unwrap().push(request.target.clone());
+
+    let route = state.routes.lock().unwrap().get(request.path()).cloned();
+
+    match route {
+        Some((status, body)) => write_response(&mut stream, status, &[], &body).await,
+        None => write_response(&mut stream, 404, &[], "{\"errorCode\":\"RESOURCE_NOT_FOUND\"}").await,
+    }
+}
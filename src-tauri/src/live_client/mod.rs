@@ -0,0 +1,9 @@
+pub mod client;
+pub mod models;
+pub mod poller;
+#[cfg(test)]
+pub(crate) mod mock_server;
+
+pub use client::LiveClient;
+pub use models::*;
+pub use poller::LiveEventPoller;
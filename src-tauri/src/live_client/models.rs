@@ -0,0 +1,189 @@
+use serde::{Deserialize, Serialize};
+
+/// `/liveclientdata/allgamedata`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllGameData {
+    pub active_player: ActivePlayer,
+    pub all_players: Vec<LivePlayer>,
+    pub events: EventData,
+    pub game_data: LiveGameData,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveGameData {
+    pub game_mode: String,
+    pub game_time: f64,
+    pub map_name: String,
+    pub map_number: i32,
+    pub map_terrain: String,
+}
+
+/// `/liveclientdata/activeplayer`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivePlayer {
+    #[serde(default)]
+    pub summoner_name: String,
+    pub riot_id: Option<String>,
+    pub level: i32,
+    pub current_gold: f64,
+    pub champion_stats: ChampionStats,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChampionStats {
+    pub ability_power: f64,
+    pub armor: f64,
+    pub attack_damage: f64,
+    pub attack_speed: f64,
+    pub current_health: f64,
+    pub max_health: f64,
+    pub magic_resist: f64,
+    pub move_speed: f64,
+    #[serde(default)]
+    pub resource_value: f64,
+    #[serde(default)]
+    pub resource_max: f64,
+}
+
+/// Entry of `/liveclientdata/playerlist`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LivePlayer {
+    pub champion_name: String,
+    #[serde(default)]
+    pub summoner_name: String,
+    pub riot_id: Option<String>,
+    pub team: String, // "ORDER" (blue) or "CHAOS" (red)
+    #[serde(default)]
+    pub position: String,
+    pub level: i32,
+    pub is_bot: bool,
+    pub is_dead: bool,
+    pub respawn_timer: f64,
+    pub scores: LiveScores,
+    #[serde(default)]
+    pub items: Vec<LiveItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveScores {
+    pub kills: i32,
+    pub deaths: i32,
+    pub assists: i32,
+    pub creep_score: i32,
+    pub ward_score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveItem {
+    #[serde(rename = "itemID")]
+    pub item_id: i32,
+    pub display_name: String,
+    pub slot: i32,
+    pub count: i32,
+}
+
+/// `/liveclientdata/eventdata`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventData {
+    #[serde(rename = "Events", default)]
+    pub events: Vec<RawLiveEvent>,
+}
+
+/// Raw event as sent by the game, fields depend on `EventName`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RawLiveEvent {
+    #[serde(rename = "EventID")]
+    pub event_id: i64,
+    pub event_name: String,
+    pub event_time: f64,
+    pub killer_name: Option<String>,
+    pub victim_name: Option<String>,
+    #[serde(default)]
+    pub assisters: Vec<String>,
+    pub dragon_type: Option<String>,
+    pub stolen: Option<String>, // "True" / "False"
+    pub turret_killed: Option<String>,
+    pub inhib_killed: Option<String>,
+    pub kill_streak: Option<i32>,
+    pub result: Option<String>,
+}
+
+/// Typed in-game event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum LiveGameEvent {
+    #[serde(rename_all = "camelCase")]
+    GameStart { time: f64 },
+    #[serde(rename_all = "camelCase")]
+    ChampionKill { time: f64, killer: String, victim: String, assisters: Vec<String> },
+    #[serde(rename_all = "camelCase")]
+    Multikill { time: f64, killer: String, kill_streak: i32 },
+    #[serde(rename_all = "camelCase")]
+    DragonKill { time: f64, killer: String, dragon_type: String, stolen: bool },
+    #[serde(rename_all = "camelCase")]
+    HeraldKill { time: f64, killer: String, stolen: bool },
+    #[serde(rename_all = "camelCase")]
+    BaronKill { time: f64, killer: String, stolen: bool },
+    #[serde(rename_all = "camelCase")]
+    TurretKilled { time: f64, killer: String, turret: String },
+    #[serde(rename_all = "camelCase")]
+    InhibKilled { time: f64, killer: String, inhibitor: String },
+    #[serde(rename_all = "camelCase")]
+    GameEnd { time: f64, result: String },
+    #[serde(rename_all = "camelCase")]
+    Other { time: f64, name: String },
+}
+
+impl From<&RawLiveEvent> for LiveGameEvent {
+    fn from(raw: &RawLiveEvent) -> Self {
+        let time = raw.event_time;
+        let killer = raw.killer_name.clone().unwrap_or_default();
+        let stolen = raw.stolen.as_deref() == Some("True");
+
+        match raw.event_name.as_str() {
+            "GameStart" => LiveGameEvent::GameStart { time },
+            "ChampionKill" => LiveGameEvent::ChampionKill {
+                time,
+                killer,
+                victim: raw.victim_name.clone().unwrap_or_default(),
+                assisters: raw.assisters.clone(),
+            },
+            "Multikill" => LiveGameEvent::Multikill {
+                time,
+                killer,
+                kill_streak: raw.kill_streak.unwrap_or(0),
+            },
+            "DragonKill" => LiveGameEvent::DragonKill {
+                time,
+                killer,
+                dragon_type: raw.dragon_type.clone().unwrap_or_default(),
+                stolen,
+            },
+            "HeraldKill" => LiveGameEvent::HeraldKill { time, killer, stolen },
+            "BaronKill" => LiveGameEvent::BaronKill { time, killer, stolen },
+            "TurretKilled" => LiveGameEvent::TurretKilled {
+                time,
+                killer,
+                turret: raw.turret_killed.clone().unwrap_or_default(),
+            },
+            "InhibKilled" => LiveGameEvent::InhibKilled {
+                time,
+                killer,
+                inhibitor: raw.inhib_killed.clone().unwrap_or_default(),
+            },
+            "GameEnd" => LiveGameEvent::GameEnd {
+                time,
+                result: raw.result.clone().unwrap_or_default(),
+            },
+            name => LiveGameEvent::Other { time, name: name.to_string() },
+        }
+    }
+}
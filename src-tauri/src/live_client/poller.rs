@@ -0,0 +1,91 @@
+use anyhow::Result;
+use super::{LiveClient, LiveGameEvent};
+
+/// Polls `eventdata` and only reports events that weren't seen on a previous poll
+pub struct LiveEventPoller {
+    client: LiveClient,
+    last_event_id: Option<i64>,
+}
+
+impl LiveEventPoller {
+    pub fn new(client: LiveClient) -> Self {
+        Self {
+            client,
+            last_event_id: None,
+        }
+    }
+
+    /// Fetch the event list and return the events added since the last call
+    pub async fn poll(&mut self) -> Result<Vec<LiveGameEvent>> {
+        let data = self.client.get_event_data().await?;
+
+        // Event IDs restart from 0 in a new game
+        if let (Some(last), Some(max)) = (self.last_event_id, data.events.iter().map(|e| e.event_id).max()) {
+            if max < last {
+                self.last_event_id = None;
+            }
+        }
+
+        let new_events: Vec<LiveGameEvent> = data.events
+            .iter()
+            .filter(|e| self.last_event_id.is_none_or(|last| e.event_id > last))
+            .map(LiveGameEvent::from)
+            .collect();
+
+        if let Some(max) = data.events.iter().map(|e| e.event_id).max() {
+            self.last_event_id = Some(max);
+        }
+
+        Ok(new_events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::mock_server::MockLiveClient;
+
+    async fn poller(mock: &MockLiveClient) -> LiveEventPoller {
+        LiveEventPoller::new(LiveClient::with_base_url(&mock.base_url()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn reports_each_event_once() {
+        let mock = MockLiveClient::start().await.unwrap();
+        let mut poller = poller(&mock).await;
+
+        let events = poller.poll().await.unwrap();
+        assert_eq!(events.len(), 4);
+        assert!(matches!(&events[1], LiveGameEvent::Other { name, .. } if name == "MinionsSpawning"));
+        assert!(matches!(
+            &events[2],
+            LiveGameEvent::ChampionKill { killer, victim, assisters, .. }
+                if killer == "Galnir#EUW" && victim == "Tideborn#EUW" && assisters == &["Sunforge#EUW"]
+        ));
+        assert!(matches!(&events[3], LiveGameEvent::DragonKill { dragon_type, stolen: false, .. } if dragon_type == "Fire"));
+
+        assert!(poller.poll().await.unwrap().is_empty());
+        assert_eq!(mock.requests(), vec!["/liveclientdata/eventdata"; 2]);
+    }
+
+    #[tokio::test]
+    async fn starts_over_when_event_ids_restart() {
+        let mock = MockLiveClient::start().await.unwrap();
+        let mut poller = poller(&mock).await;
+        poller.poll().await.unwrap();
+
+        mock.set_response("eventdata", 200, r#"{"Events":[{"EventID":0,"EventName":"GameStart","EventTime":0.04}]}"#);
+
+        let events = poller.poll().await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], LiveGameEvent::GameStart { .. }));
+    }
+
+    #[tokio::test]
+    async fn fails_outside_a_game() {
+        let mock = MockLiveClient::start().await.unwrap();
+        mock.set_response("eventdata", 404, "{\"errorCode\":\"RESOURCE_NOT_FOUND\"}");
+
+        assert!(poller(&mock).await.poll().await.is_err());
+    }
+}
@@ -0,0 +1,87 @@
+//! HTTP/1.1 plumbing shared by the in-process mock servers: just enough to
+//! read one request head and answer it on a connection that is then closed.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use anyhow::Result;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio_rustls::TlsAcceptor;
+
+pub struct MockRequest {
+    pub method: String,
+    /// Path and query, e.g. "/lol/match/v5/matches?start=0"
+    pub target: String,
+    /// Names lowercased
+    pub headers: HashMap<String, String>,
+}
+
+impl MockRequest {
+    /// `target` without the query
+    pub fn path(&self) -> &str {
+        self.target.split('?').next().unwrap_or_default()
+    }
+}
+
+/// Read the request line and headers (up to the blank line)
+pub async fn read_request<S: AsyncRead + Unpin>(stream: &mut S) -> Result<MockRequest> {
+    let mut head = Vec::new();
+    let mut byte = [0u8; 1];
+
+    while !head.ends_with(b"\r\n\r\n") {
+        if stream.read(&mut byte).await? == 0 {
+            anyhow::bail!("Connection closed before end of headers");
+        }
+        head.push(byte[0]);
+    }
+
+    let head = String::from_utf8_lossy(&head);
+    let mut lines = head.lines();
+
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let target = request_line.next().unwrap_or_default().to_string();
+
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+
+    Ok(MockRequest { method, target, headers })
+}
+
+/// JSON response, `headers` added to the standard ones
+pub async fn write_response<S: AsyncWrite + Unpin>(
+    stream: &mut S,
+    status: u16,
+    headers: &[(String, String)],
+    body: &str,
+) -> Result<()> {
+    let extra_headers: String = headers.iter()
+        .map(|(name, value)| format!("{}: {}\r\n", name, value))
+        .collect();
+
+    let raw = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Type: application/json;charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n{}\r\n{}",
+        status,
+        body.len(),
+        extra_headers,
+        body
+    );
+    stream.write_all(raw.as_bytes()).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+/// TLS on a fresh self-signed certificate for 127.0.0.1, like the game and the client use
+pub fn tls_acceptor() -> Result<TlsAcceptor> {
+    let certified = rcgen::generate_simple_self_signed(vec!["127.0.0.1".to_string(), "localhost".to_string()])?;
+    let cert = certified.cert.der().clone();
+    let key = rustls::pki_types::PrivateKeyDer::Pkcs8(certified.key_pair.serialize_der().into());
+
+    let config = rustls::ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()?
+        .with_no_client_auth()
+        .with_single_cert(vec![cert], key)?;
+
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
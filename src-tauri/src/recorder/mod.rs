@@ -4,6 +4,7 @@ use std::process::{Child, Command, Stdio};
 use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::{debug, error, info, warn};
+use crate::error::GalphaError;
 
 pub struct RecordingSession {
     pub id: String,
@@ -76,7 +77,7 @@ impl Recorder {
             let session = self.current_session.lock().unwrap();
             if let Some(s) = &*session {
                 if s.status == RecordingStatus::Recording {
-                    return Err(anyhow::anyhow!("Recording already in progress"));
+                    return Err(GalphaError::RecorderBusy.into());
                 }
             }
         }
@@ -134,10 +135,9 @@ impl Recorder {
         #[cfg(target_os = "macos")]
         {
             // macOS recording is currently disabled due to compatibility issues
-            return Err(anyhow::anyhow!(
-                "L'enregistrement vidéo n'est pas encore supporté sur macOS. \
-                Cette fonctionnalité est disponible sur Windows uniquement."
-            ));
+            return Err(GalphaError::Unsupported(
+                "Video recording is not supported on macOS yet. It is currently available on Windows only.".to_string()
+            ).into());
         }
 
         #[cfg(target_os = "windows")]
@@ -1,56 +1,143 @@
 use reqwest::Client;
 use anyhow::{Result, Context};
 use tracing::{info, debug, warn};
-use super::{Account, MatchDetails, Summoner};
+use super::{Account, MatchDetails, MatchTimeline, Platform, RegionalRoute, Summoner};
+use crate::error::GalphaError;
+use super::match_query::{MatchIdPager, MatchIdQuery};
+use super::rate_limiter::RateLimiter;
+use futures_util::stream::{self, StreamExt};
+use std::sync::Arc;
 use tokio::time::{sleep, Duration};
 
+/// Riot API client, meant to be built once per API key and shared.
+/// The platform is given on each call so one client serves every region.
 pub struct RiotApiClient {
     client: Client,
     api_key: String,
-    region: String, // e.g., "euw1"
-    regional_endpoint: String, // e.g., "europe"
+    platform_base_url: String,
+    regional_base_url: String,
+    limiter: Arc<RateLimiter>,
 }
 
 /// Retry configuration for handling rate limits
 const MAX_RETRIES: u32 = 3;
-const INITIAL_BACKOFF_MS: u64 = 2000; // 2 seconds initial backoff
-const RATE_LIMIT_DELAY_MS: u64 = 1500; // 1.5 seconds between requests
+/// Match details downloaded at the same time by `get_matches_batch`
+const BATCH_CONCURRENCY: usize = 10;
+
+/// `{routing}` is replaced by the platform ("euw1") or regional ("europe") routing value
+const DEFAULT_BASE_URL: &str = "https://{routing}.api.riotgames.com";
+const DEFAULT_TIMEOUT_SECS: u64 = 15;
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 5;
+
+pub struct RiotApiClientBuilder {
+    api_key: String,
+    platform_base_url: String,
+    regional_base_url: String,
+    http_client: Option<Client>,
+    timeout: Duration,
+    connect_timeout: Duration,
+}
+
+impl RiotApiClientBuilder {
+    /// Base URL of platform endpoints (summoner-v4, league-v4), may contain `{routing}`
+    pub fn platform_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.platform_base_url = base_url.into();
+        self
+    }
+
+    /// Base URL of regional endpoints (account-v1, match-v5), may contain `{routing}`
+    pub fn regional_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.regional_base_url = base_url.into();
+        self
+    }
+
+    /// Send every request to the same host, e.g. a local stub server
+    pub fn base_url(self, base_url: impl Into<String>) -> Self {
+        let base_url = base_url.into();
+        self.platform_base_url(base_url.clone()).regional_base_url(base_url)
+    }
+
+    /// Use an existing HTTP client (the timeouts below are then ignored)
+    pub fn http_client(mut self, client: Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    pub fn build(self) -> Result<RiotApiClient> {
+        let client = match self.http_client {
+            Some(client) => client,
+            None => Client::builder()
+                .timeout(self.timeout)
+                .connect_timeout(self.connect_timeout)
+                .build()
+                .context("Failed to build HTTP client")?,
+        };
+
+        Ok(RiotApiClient {
+            client,
+            api_key: self.api_key,
+            platform_base_url: self.platform_base_url.trim_end_matches('/').to_string(),
+            regional_base_url: self.regional_base_url.trim_end_matches('/').to_string(),
+            limiter: Arc::new(RateLimiter::new()),
+        })
+    }
+}
 
 impl RiotApiClient {
-    /// Create a new Riot API client
-    pub fn new(api_key: String, region: String) -> Self {
-        let regional_endpoint = Self::get_regional_endpoint(&region);
+    /// Create a new Riot API client for the production endpoints
+    pub fn new(api_key: String) -> Result<Self> {
+        Self::builder(api_key).build()
+    }
 
-        Self {
-            client: Client::new(),
+    pub fn builder(api_key: String) -> RiotApiClientBuilder {
+        RiotApiClientBuilder {
             api_key,
-            region,
-            regional_endpoint,
+            platform_base_url: DEFAULT_BASE_URL.to_string(),
+            regional_base_url: DEFAULT_BASE_URL.to_string(),
+            http_client: None,
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+            connect_timeout: Duration::from_secs(DEFAULT_CONNECT_TIMEOUT_SECS),
         }
     }
 
-    /// Get regional endpoint from platform region
-    fn get_regional_endpoint(region: &str) -> String {
-        match region.to_lowercase().as_str() {
-            "euw1" | "eun1" | "tr1" | "ru" => "europe",
-            "na1" | "br1" | "la1" | "la2" => "americas",
-            "kr" | "jp1" => "asia",
-            "oc1" => "sea",
-            _ => "europe",
-        }
-        .to_string()
+    /// Whether this client was built for the given key
+    pub fn uses_api_key(&self, api_key: &str) -> bool {
+        self.api_key == api_key
+    }
+
+    fn platform_url(&self, platform: Platform, path: &str) -> String {
+        format!("{}{}", self.platform_base_url.replace("{routing}", platform.id()), path)
     }
 
-    /// Make an API request with retry logic for rate limiting
+    fn regional_url(&self, route: RegionalRoute, path: &str) -> String {
+        format!("{}{}", self.regional_base_url.replace("{routing}", route.as_str()), path)
+    }
+
+    /// match-v5 cluster of a match: the platform in its ID, else the given one
+    fn match_route(platform: Platform, match_id: &str) -> RegionalRoute {
+        Platform::from_match_id(match_id).unwrap_or(platform).match_route()
+    }
+
+    /// Make an API request, waiting on the rate limiter and retrying on 429
     async fn make_request_with_retry(
         &self,
+        routing: &str,
         url: &str,
         request_name: &str,
     ) -> Result<reqwest::Response> {
-        // Rate limiting: wait before making request
-        sleep(Duration::from_millis(RATE_LIMIT_DELAY_MS)).await;
-
         for attempt in 0..MAX_RETRIES {
+            self.limiter.acquire(routing, request_name).await;
+
             debug!("Making request to {} (attempt {}/{})", request_name, attempt + 1, MAX_RETRIES);
             debug!("Request URL: {}", url);
 
@@ -66,32 +153,40 @@ impl RiotApiClient {
 
             // Success case
             if status.is_success() {
+                self.limiter.update_from_headers(routing, request_name, response.headers());
                 return Ok(response);
             }
 
-            // Rate limit case - retry with exponential backoff
+            // Rate limit case - wait for as long as Riot asks, then retry
             if status.as_u16() == 429 {
+                let retry_after = self.limiter.on_rate_limited(routing, request_name, response.headers());
+
                 if attempt < MAX_RETRIES - 1 {
-                    // Calculate exponential backoff: 2s, 4s, 8s
-                    let backoff_ms = INITIAL_BACKOFF_MS * 2_u64.pow(attempt);
                     warn!(
-                        "Rate limit hit for {}. Retrying in {}ms (attempt {}/{})",
+                        "Rate limit hit for {}. Retrying in {:?} (attempt {}/{})",
                         request_name,
-                        backoff_ms,
+                        retry_after,
                         attempt + 1,
                         MAX_RETRIES
                     );
-                    sleep(Duration::from_millis(backoff_ms)).await;
+                    sleep(retry_after).await;
                     continue;
                 } else {
-                    let body = response.text().await.unwrap_or_default();
-                    anyhow::bail!("Riot API request failed with status {}: {}", status, body);
+                    return Err(GalphaError::RiotRateLimited { retry_after: Some(retry_after.as_secs()) }.into());
                 }
             }
 
+            self.limiter.update_from_headers(routing, request_name, response.headers());
+
             // Other errors - fail immediately
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Riot API request failed with status {}: {}", status, body);
+            match status.as_u16() {
+                401 | 403 => return Err(GalphaError::RiotForbidden.into()),
+                404 => return Err(GalphaError::NotFound(format!("{} ({})", request_name, url)).into()),
+                _ => {
+                    let body = response.text().await.unwrap_or_default();
+                    return Err(GalphaError::RiotApi { status: status.as_u16(), body }.into());
+                }
+            }
         }
 
         anyhow::bail!("Max retries exceeded for {}", request_name)
@@ -100,19 +195,20 @@ impl RiotApiClient {
     /// Get account by Riot ID (game name + tag)
     pub async fn get_account_by_riot_id(
         &self,
+        platform: Platform,
         game_name: &str,
         tag_line: &str,
     ) -> Result<Account> {
-        let url = format!(
-            "https://{}.api.riotgames.com/riot/account/v1/accounts/by-riot-id/{}/{}",
-            self.regional_endpoint,
+        let route = platform.account_route();
+        let url = self.regional_url(route, &format!(
+            "/riot/account/v1/accounts/by-riot-id/{}/{}",
             urlencoding::encode(game_name),
             urlencoding::encode(tag_line)
-        );
+        ));
 
         debug!("Fetching account for {}#{}", game_name, tag_line);
 
-        let response = self.make_request_with_retry(&url, "get_account_by_riot_id").await?;
+        let response = self.make_request_with_retry(route.as_str(), &url, "get_account_by_riot_id").await?;
 
         let account: Account = response
             .json()
@@ -127,18 +223,31 @@ impl RiotApiClient {
     /// Get match IDs for a player
     pub async fn get_match_ids(
         &self,
+        platform: Platform,
         puuid: &str,
         start: usize,
         count: usize,
     ) -> Result<Vec<String>> {
-        let url = format!(
-            "https://{}.api.riotgames.com/lol/match/v5/matches/by-puuid/{}/ids?start={}&count={}",
-            self.regional_endpoint, puuid, start, count
-        );
+        let query = MatchIdQuery::new().start(start).count(count);
+        self.get_match_ids_with_query(platform, puuid, &query).await
+    }
 
-        debug!("Fetching match IDs for PUUID: {} (start: {}, count: {})", puuid, start, count);
+    /// Get one page of match IDs for a player, newest first
+    pub async fn get_match_ids_with_query(
+        &self,
+        platform: Platform,
+        puuid: &str,
+        query: &MatchIdQuery,
+    ) -> Result<Vec<String>> {
+        let route = platform.match_route();
+        let url = self.regional_url(route, &format!(
+            "/lol/match/v5/matches/by-puuid/{}/ids?{}",
+            puuid, query.to_query_string()
+        ));
 
-        let response = self.make_request_with_retry(&url, "get_match_ids").await?;
+        debug!("Fetching match IDs for PUUID: {} ({:?})", puuid, query);
+
+        let response = self.make_request_with_retry(route.as_str(), &url, "get_match_ids").await?;
 
         let match_ids: Vec<String> = response
             .json()
@@ -150,16 +259,22 @@ impl RiotApiClient {
         Ok(match_ids)
     }
 
-    /// Get detailed match information
-    pub async fn get_match_details(&self, match_id: &str) -> Result<MatchDetails> {
-        let url = format!(
-            "https://{}.api.riotgames.com/lol/match/v5/matches/{}",
-            self.regional_endpoint, match_id
-        );
+    /// Walk a player's whole match history matching the query, 100 IDs at a time
+    pub fn match_id_pages(&self, platform: Platform, puuid: &str, query: MatchIdQuery) -> MatchIdPager<'_> {
+        MatchIdPager::new(self, platform, puuid, query)
+    }
+
+    /// Get detailed match information, from the cluster of the platform in the match ID
+    pub async fn get_match_details(&self, platform: Platform, match_id: &str) -> Result<MatchDetails> {
+        let route = Self::match_route(platform, match_id);
+        let url = self.regional_url(route, &format!(
+            "/lol/match/v5/matches/{}",
+            match_id
+        ));
 
         debug!("Fetching match details for: {}", match_id);
 
-        let response = self.make_request_with_retry(&url, "get_match_details").await?;
+        let response = self.make_request_with_retry(route.as_str(), &url, "get_match_details").await?;
 
         let match_details: MatchDetails = response
             .json()
@@ -171,56 +286,76 @@ impl RiotApiClient {
         Ok(match_details)
     }
 
+    /// Get the minute-by-minute timeline of a match
+    pub async fn get_match_timeline(&self, platform: Platform, match_id: &str) -> Result<MatchTimeline> {
+        let timeline: MatchTimeline = serde_json::from_value(self.get_match_timeline_json(platform, match_id).await?)
+            .context("Failed to parse match timeline")?;
+
+        debug!("Retrieved {} timeline frames for: {}", timeline.info.frames.len(), match_id);
+
+        Ok(timeline)
+    }
+
+    /// The timeline as received, with the events `TimelineEvent` has no variant for
+    pub async fn get_match_timeline_json(&self, platform: Platform, match_id: &str) -> Result<serde_json::Value> {
+        let route = Self::match_route(platform, match_id);
+        let url = self.regional_url(route, &format!(
+            "/lol/match/v5/matches/{}/timeline",
+            match_id
+        ));
+
+        debug!("Fetching match timeline for: {}", match_id);
+
+        let response = self.make_request_with_retry(route.as_str(), &url, "get_match_timeline").await?;
+
+        response.json().await.context("Failed to parse match timeline")
+    }
+
     /// Get multiple match details in batch
     pub async fn get_matches_batch(
         &self,
+        platform: Platform,
         match_ids: Vec<String>,
     ) -> Vec<Result<MatchDetails>> {
-        let mut results = Vec::new();
-
-        for match_id in match_ids {
-            let result = self.get_match_details(&match_id).await;
-            results.push(result);
-            // Rate limiting is handled in make_request_with_retry
-        }
-
-        results
+        // The rate limiter paces the requests, results keep the order of match_ids
+        stream::iter(match_ids)
+            .map(|match_id| async move { self.get_match_details(platform, &match_id).await })
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await
     }
 
     /// Get summoner by PUUID
-    pub async fn get_summoner_by_puuid(&self, puuid: &str) -> Result<Summoner> {
-        let url = format!(
-            "https://{}.api.riotgames.com/lol/summoner/v4/summoners/by-puuid/{}",
-            self.region, puuid
-        );
+    pub async fn get_summoner_by_puuid(&self, platform: Platform, puuid: &str) -> Result<Summoner> {
+        let url = self.platform_url(platform, &format!(
+            "/lol/summoner/v4/summoners/by-puuid/{}",
+            puuid
+        ));
 
         debug!("Fetching summoner by PUUID: {}", puuid);
 
-        let response = self.make_request_with_retry(&url, "get_summoner_by_puuid").await?;
-
-        // Get the raw response text first to see what we're getting
-        let response_text = response.text().await.context("Failed to get response text")?;
-        info!("Raw Summoner API response: {}", response_text);
+        let response = self.make_request_with_retry(platform.id(), &url, "get_summoner_by_puuid").await?;
 
-        // Try to parse it
-        let summoner: Summoner = serde_json::from_str(&response_text)
-            .context(format!("Failed to parse summoner data. Response was: {}", response_text))?;
+        let summoner: Summoner = response
+            .json()
+            .await
+            .context("Failed to parse summoner data")?;
 
-        info!("Summoner data fetched - ID: {:?}, Level: {}, PUUID: {}", summoner.id.as_deref().unwrap_or("None"), summoner.summoner_level, summoner.puuid);
+        debug!("Retrieved summoner for PUUID: {} (level {})", puuid, summoner.summoner_level);
 
         Ok(summoner)
     }
 
     /// Get ranked stats for a summoner by summoner ID (deprecated - use get_ranked_stats_by_puuid instead)
-    pub async fn get_ranked_stats(&self, summoner_id: &str) -> Result<Vec<serde_json::Value>> {
-        let url = format!(
-            "https://{}.api.riotgames.com/lol/league/v4/entries/by-summoner/{}",
-            self.region, summoner_id
-        );
+    pub async fn get_ranked_stats(&self, platform: Platform, summoner_id: &str) -> Result<Vec<serde_json::Value>> {
+        let url = self.platform_url(platform, &format!(
+            "/lol/league/v4/entries/by-summoner/{}",
+            summoner_id
+        ));
 
         debug!("Fetching ranked stats for summoner ID: {}", summoner_id);
 
-        let response = self.make_request_with_retry(&url, "get_ranked_stats").await?;
+        let response = self.make_request_with_retry(platform.id(), &url, "get_ranked_stats").await?;
 
         let stats = response
             .json()
@@ -231,15 +366,15 @@ impl RiotApiClient {
     }
 
     /// Get ranked stats for a summoner by PUUID (recommended method)
-    pub async fn get_ranked_stats_by_puuid(&self, puuid: &str) -> Result<Vec<serde_json::Value>> {
-        let url = format!(
-            "https://{}.api.riotgames.com/lol/league/v4/entries/by-puuid/{}",
-            self.region, puuid
-        );
+    pub async fn get_ranked_stats_by_puuid(&self, platform: Platform, puuid: &str) -> Result<Vec<serde_json::Value>> {
+        let url = self.platform_url(platform, &format!(
+            "/lol/league/v4/entries/by-puuid/{}",
+            puuid
+        ));
 
         debug!("Fetching ranked stats for PUUID: {}", puuid);
 
-        let response = self.make_request_with_retry(&url, "get_ranked_stats_by_puuid").await?;
+        let response = self.make_request_with_retry(platform.id(), &url, "get_ranked_stats_by_puuid").await?;
 
         let stats = response
             .json()
@@ -251,3 +386,155 @@ impl RiotApiClient {
         Ok(stats)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::mock_server::{MockRiot, MOCK_API_KEY, MOCK_PUUID};
+    use super::super::TimelineEvent;
+
+    fn client(mock: &MockRiot) -> RiotApiClient {
+        RiotApiClient::builder(MOCK_API_KEY.to_string())
+            .base_url(mock.base_url())
+            .timeout(Duration::from_secs(5))
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn fetches_account_from_regional_host() {
+        let mock = MockRiot::start().await.unwrap();
+        let account = client(&mock).get_account_by_riot_id(Platform::Euw1, "Galnir", "EUW").await.unwrap();
+
+        assert_eq!(account.puuid, MOCK_PUUID);
+        assert_eq!(account.game_name, "Galnir");
+        assert_eq!(mock.requests(), vec!["/europe/riot/account/v1/accounts/by-riot-id/Galnir/EUW"]);
+    }
+
+    #[tokio::test]
+    async fn fetches_summoner_and_league_from_platform_host() {
+        let mock = MockRiot::start().await.unwrap();
+        let client = client(&mock);
+
+        let summoner = client.get_summoner_by_puuid(Platform::Euw1, MOCK_PUUID).await.unwrap();
+        assert_eq!(summoner.summoner_level, 287);
+        assert!(summoner.id.is_none());
+
+        let entries = client.get_ranked_stats_by_puuid(Platform::Euw1, MOCK_PUUID).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["queueType"], "RANKED_SOLO_5x5");
+
+        assert!(mock.requests().iter().all(|r| r.starts_with("/euw1/")));
+    }
+
+    #[tokio::test]
+    async fn fetches_match_ids_and_details() {
+        let mock = MockRiot::start().await.unwrap();
+        let client = client(&mock);
+
+        let ids = client.get_match_ids(Platform::Euw1, MOCK_PUUID, 0, 5).await.unwrap();
+        assert_eq!(ids.len(), 5);
+        assert!(mock.requests()[0].ends_with("/ids?start=0&count=5"));
+
+        let details = client.get_match_details(Platform::Euw1, &ids[0]).await.unwrap();
+        assert_eq!(details.metadata.match_id, "EUW1_7212345678");
+        assert_eq!(details.info.participants.len(), 10);
+        assert_eq!(details.info.teams.len(), 2);
+
+        let me = details.info.participants.iter().find(|p| p.puuid == MOCK_PUUID).unwrap();
+        assert_eq!(me.champion_name, "Ahri");
+        assert_eq!((me.kills, me.deaths, me.assists), (9, 3, 8));
+        assert_eq!(me.perks.styles.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn fetches_match_timeline() {
+        let mock = MockRiot::start().await.unwrap();
+        let timeline = client(&mock).get_match_timeline(Platform::Euw1, "EUW1_7212345678").await.unwrap();
+
+        assert_eq!(timeline.info.frame_interval, 60000);
+        assert_eq!(timeline.info.frames.len(), 11);
+
+        let me = timeline.participant_id(MOCK_PUUID).unwrap();
+        assert_eq!(me, 3);
+        let at_ten = timeline.participant_frame_at(me, 10).unwrap();
+        assert_eq!(at_ten.total_gold, 4007);
+        assert_eq!(at_ten.cs(), 66);
+
+        let first_blood = timeline.events()
+            .find_map(|e| match e {
+                TimelineEvent::ChampionKill(kill) => Some(kill),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!((first_blood.killer_id, first_blood.victim_id), (3, 8));
+
+        assert!(timeline.events().any(|e| matches!(e, TimelineEvent::EliteMonsterKill(m) if m.monster_sub_type.as_deref() == Some("FIRE_DRAGON"))));
+        assert!(timeline.events().any(|e| matches!(e, TimelineEvent::BuildingKill(b) if b.lane_type == "BOT_LANE")));
+        assert!(timeline.events().any(|e| matches!(e, TimelineEvent::WardPlaced(w) if w.ward_type == "CONTROL_WARD")));
+        assert!(timeline.events().any(|e| matches!(e, TimelineEvent::SkillLevelUp(_))));
+        assert!(timeline.events().any(|e| matches!(e, TimelineEvent::Other)));
+        assert_eq!(
+            timeline.events().filter(|e| matches!(e, TimelineEvent::ItemPurchased(i) if i.participant_id == me)).count(),
+            3
+        );
+    }
+
+    #[tokio::test]
+    async fn routes_by_platform_and_match_id() {
+        let mock = MockRiot::start().await.unwrap();
+        mock.set_fixture("/americas/riot/account/v1/accounts/by-riot-id/Galnir/OCE", "account.json").unwrap();
+        let client = client(&mock);
+
+        client.get_account_by_riot_id(Platform::Oc1, "Galnir", "OCE").await.unwrap();
+        let _ = client.get_match_ids(Platform::Sg2, MOCK_PUUID, 0, 5).await;
+        // The match ID names the platform, whatever the caller's region
+        client.get_match_details(Platform::Na1, "EUW1_7212345678").await.unwrap();
+
+        let requests = mock.requests();
+        assert!(requests[0].starts_with("/americas/"));
+        assert!(requests[1].starts_with("/sea/"));
+        assert_eq!(requests.last().unwrap(), "/europe/lol/match/v5/matches/EUW1_7212345678");
+    }
+
+    #[tokio::test]
+    async fn keeps_unmodelled_timeline_events_in_raw_json() {
+        let mock = MockRiot::start().await.unwrap();
+        let timeline = client(&mock).get_match_timeline_json(Platform::Euw1, "EUW1_7212345678").await.unwrap();
+
+        let events: Vec<&serde_json::Value> = timeline["info"]["frames"].as_array().unwrap()
+            .iter()
+            .flat_map(|frame| frame["events"].as_array().unwrap())
+            .collect();
+        let sold = events.iter().find(|e| e["type"] == "ITEM_SOLD").unwrap();
+        assert!(sold["itemId"].is_number());
+        assert!(events.iter().any(|e| e["type"] == "WARD_KILL" && e["killerId"].is_number()));
+    }
+
+    #[tokio::test]
+    async fn batch_keeps_match_order() {
+        let mock = MockRiot::start().await.unwrap();
+        let ids = vec!["EUW1_7212345678".to_string(), "EUW1_1".to_string()];
+
+        let results = client(&mock).get_matches_batch(Platform::Euw1, ids).await;
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1].as_ref().unwrap_err().downcast_ref(), Some(GalphaError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn maps_error_statuses() {
+        let mock = MockRiot::start().await.unwrap();
+
+        let wrong_key = RiotApiClient::builder("RGAPI-wrong".to_string())
+            .base_url(mock.base_url())
+            .build()
+            .unwrap();
+        let error = wrong_key.get_summoner_by_puuid(Platform::Euw1, MOCK_PUUID).await.unwrap_err();
+        assert!(matches!(error.downcast_ref(), Some(GalphaError::RiotForbidden)));
+
+        mock.set_response("/euw1/lol/summoner/v4/summoners/by-puuid/limited", 429, &[("Retry-After", "0"), ("X-Rate-Limit-Type", "method")], "{}");
+        let error = client(&mock).get_summoner_by_puuid(Platform::Euw1, "limited").await.unwrap_err();
+        assert!(matches!(error.downcast_ref(), Some(GalphaError::RiotRateLimited { retry_after: Some(0) })));
+        assert_eq!(mock.requests().len(), 1 + MAX_RETRIES as usize);
+    }
+}
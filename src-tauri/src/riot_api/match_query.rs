@@ -0,0 +1,285 @@
+use anyhow::Result;
+use futures_util::stream::{self, Stream};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use super::{Platform, RiotApiClient};
+
+/// Largest `count` accepted by match-v5 `/by-puuid/{puuid}/ids`
+pub const MAX_MATCH_IDS_PER_PAGE: usize = 100;
+
+/// Filters of match-v5 `/by-puuid/{puuid}/ids`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchIdQuery {
+    pub queue: Option<i32>,
+    /// "ranked", "normal", "tourney" or "tutorial"
+    #[serde(rename = "type")]
+    pub match_type: Option<String>,
+    /// Epoch seconds. Riot ignores games before June 16th, 2021.
+    pub start_time: Option<i64>,
+    /// Epoch seconds
+    pub end_time: Option<i64>,
+    #[serde(default)]
+    pub start: usize,
+    #[serde(default = "default_count")]
+    pub count: usize,
+}
+
+fn default_count() -> usize {
+    20
+}
+
+impl Default for MatchIdQuery {
+    fn default() -> Self {
+        Self {
+            queue: None,
+            match_type: None,
+            start_time: None,
+            end_time: None,
+            start: 0,
+            count: default_count(),
+        }
+    }
+}
+
+impl MatchIdQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn queue(mut self, queue_id: i32) -> Self {
+        self.queue = Some(queue_id);
+        self
+    }
+
+    pub fn match_type(mut self, match_type: &str) -> Self {
+        self.match_type = Some(match_type.to_string());
+        self
+    }
+
+    pub fn start_time(mut self, epoch_secs: i64) -> Self {
+        self.start_time = Some(epoch_secs);
+        self
+    }
+
+    pub fn end_time(mut self, epoch_secs: i64) -> Self {
+        self.end_time = Some(epoch_secs);
+        self
+    }
+
+    pub fn start(mut self, start: usize) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// Clamped to 100, Riot rejects larger pages
+    pub fn count(mut self, count: usize) -> Self {
+        self.count = count.min(MAX_MATCH_IDS_PER_PAGE);
+        self
+    }
+
+    pub fn to_query_string(&self) -> String {
+        let mut params = vec![
+            format!("start={}", self.start),
+            format!("count={}", self.count.min(MAX_MATCH_IDS_PER_PAGE)),
+        ];
+
+        if let Some(queue) = self.queue {
+            params.push(format!("queue={}", queue));
+        }
+        if let Some(match_type) = &self.match_type {
+            params.push(format!("type={}", urlencoding::encode(match_type)));
+        }
+        if let Some(start_time) = self.start_time {
+            params.push(format!("startTime={}", start_time));
+        }
+        if let Some(end_time) = self.end_time {
+            params.push(format!("endTime={}", end_time));
+        }
+
+        params.join("&")
+    }
+}
+
+/// Walks a player's history, newest first, one page of up to 100 IDs at a time.
+/// Stops at the end of the history, at `stop_at` (a match already cached) or after `limit` IDs.
+pub struct MatchIdPager<'a> {
+    client: &'a RiotApiClient,
+    platform: Platform,
+    puuid: String,
+    query: MatchIdQuery,
+    stop_at: Option<String>,
+    limit: Option<usize>,
+    fetched: usize,
+    done: bool,
+}
+
+impl<'a> MatchIdPager<'a> {
+    pub(crate) fn new(client: &'a RiotApiClient, platform: Platform, puuid: &str, query: MatchIdQuery) -> Self {
+        Self {
+            client,
+            platform,
+            puuid: puuid.to_string(),
+            query: query.count(MAX_MATCH_IDS_PER_PAGE),
+            stop_at: None,
+            limit: None,
+            fetched: 0,
+            done: false,
+        }
+    }
+
+    /// Stop before this match ID, e.g. the last match already in the database
+    pub fn stop_at(mut self, match_id: Option<String>) -> Self {
+        self.stop_at = match_id;
+        self
+    }
+
+    /// Stop after this many IDs
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Next page of IDs, `None` once the history is exhausted
+    pub async fn next_page(&mut self) -> Result<Option<Vec<String>>> {
+        if self.done || self.limit.is_some_and(|limit| self.fetched >= limit) {
+            return Ok(None);
+        }
+
+        let mut query = self.query.clone();
+        if let Some(limit) = self.limit {
+            query = query.count(limit - self.fetched);
+        }
+
+        let mut ids = self.client.get_match_ids_with_query(self.platform, &self.puuid, &query).await?;
+        let full_page = ids.len() == query.count;
+
+        if let Some(stop_at) = &self.stop_at {
+            if let Some(position) = ids.iter().position(|id| id == stop_at) {
+                debug!("Reached cached match {} after {} new IDs", stop_at, self.fetched + position);
+                ids.truncate(position);
+                self.done = true;
+            }
+        }
+
+        self.fetched += ids.len();
+        self.query.start += query.count;
+
+        if !full_page || self.limit.is_some_and(|limit| self.fetched >= limit) {
+            self.done = true;
+        }
+
+        if ids.is_empty() && self.done {
+            return Ok(None);
+        }
+
+        Ok(Some(ids))
+    }
+
+    /// Fetch every remaining page
+    pub async fn collect_all(mut self) -> Result<Vec<String>> {
+        let mut all = Vec::new();
+
+        while let Some(page) = self.next_page().await? {
+            all.extend(page);
+        }
+
+        Ok(all)
+    }
+
+    /// The remaining IDs one by one, fetching pages as needed
+    pub fn into_stream(self) -> impl Stream<Item = Result<String>> + 'a {
+        stream::try_unfold((self, Vec::new().into_iter()), |(mut pager, mut page)| async move {
+            loop {
+                if let Some(id) = page.next() {
+                    return Ok(Some((id, (pager, page))));
+                }
+
+                match pager.next_page().await? {
+                    Some(ids) => page = ids.into_iter(),
+                    None => return Ok(None),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::mock_server::{MockRiot, MOCK_API_KEY, MOCK_PUUID};
+
+    /// 140 IDs of ranked solo history, newest first, served as two pages
+    fn serve_history(mock: &MockRiot) -> Vec<String> {
+        let ids: Vec<String> = (0..140).map(|i| format!("EUW1_72123{:05}", 99999 - i)).collect();
+        let path = format!("/europe/lol/match/v5/matches/by-puuid/{}/ids", MOCK_PUUID);
+
+        let first = serde_json::to_string(&ids[..100]).unwrap();
+        let second = serde_json::to_string(&ids[100..]).unwrap();
+        mock.set_response(&format!("{}?start=0&count=100&queue=420", path), 200, &[], &first);
+        mock.set_response(&format!("{}?start=100&count=100&queue=420", path), 200, &[], &second);
+
+        ids
+    }
+
+    fn client(mock: &MockRiot) -> RiotApiClient {
+        RiotApiClient::builder(MOCK_API_KEY.to_string())
+            .base_url(mock.base_url())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn builds_query_string() {
+        let query = MatchIdQuery::new()
+            .queue(420)
+            .match_type("ranked")
+            .start_time(1736467200)
+            .end_time(1760659200)
+            .count(250);
+
+        assert_eq!(
+            query.to_query_string(),
+            "start=0&count=100&queue=420&type=ranked&startTime=1736467200&endTime=1760659200"
+        );
+        assert_eq!(MatchIdQuery::new().to_query_string(), "start=0&count=20");
+    }
+
+    #[tokio::test]
+    async fn pages_through_the_whole_history() {
+        let mock = MockRiot::start().await.unwrap();
+        let ids = serve_history(&mock);
+        let client = client(&mock);
+
+        let all = client.match_id_pages(Platform::Euw1, MOCK_PUUID, MatchIdQuery::new().queue(420))
+            .collect_all()
+            .await
+            .unwrap();
+
+        assert_eq!(all, ids);
+        assert_eq!(mock.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn stops_at_cached_match_and_limit() {
+        let mock = MockRiot::start().await.unwrap();
+        let ids = serve_history(&mock);
+        let client = client(&mock);
+
+        let new_ids = client.match_id_pages(Platform::Euw1, MOCK_PUUID, MatchIdQuery::new().queue(420))
+            .stop_at(Some(ids[120].clone()))
+            .collect_all()
+            .await
+            .unwrap();
+        assert_eq!(new_ids, ids[..120]);
+
+        let mut pager = client.match_id_pages(Platform::Euw1, MOCK_PUUID, MatchIdQuery::new().queue(420))
+            .stop_at(Some(ids[0].clone()));
+        assert!(pager.next_page().await.unwrap().is_none());
+
+        let limited: Vec<String> = futures_util::TryStreamExt::try_collect(
+            client.match_id_pages(Platform::Euw1, MOCK_PUUID, MatchIdQuery::new().queue(420)).limit(100).into_stream()
+        ).await.unwrap();
+        assert_eq!(limited, ids[..100]);
+    }
+}
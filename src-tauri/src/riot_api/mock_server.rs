@@ -0,0 +1,131 @@
+//! Local stand-in for the Riot API replaying recorded fixtures. Every routing
+//! value is served by the same host under a `/{routing}` path prefix.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use anyhow::{Context, Result};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+use crate::mock_http::{read_request, write_response};
+
+const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/riot");
+
+pub const MOCK_API_KEY: &str = "RGAPI-mock-key";
+pub const MOCK_PUUID: &str = "Gx3kQ9vR2mN7pL4wT8yB1cF6hJ0dS5aE-zK2uV9oI3rW7nM4qX8tY1gH6bP0lC5fD2sA9eR3jU7kO4iN8mQ";
+
+#[derive(Clone)]
+struct MockResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+#[derive(Default)]
+struct MockState {
+    /// "/routing/path" or "/routing/path?query" -> response
+    routes: Mutex<HashMap<String, MockResponse>>,
+    /// Every request received, as "/routing/path?query"
+    requests: Mutex<Vec<String>>,
+    /// Answer 403 to requests without `MOCK_API_KEY`
+    requires_api_key: bool,
+}
+
+pub struct MockRiot {
+    pub port: u16,
+    state: Arc<MockState>,
+}
+
+impl MockRiot {
+    /// Start a mock API serving the recorded fixtures for the EUW1 test account
+    pub async fn start() -> Result<Self> {
+        let mock = Self::listen(true).await?;
+
+        mock.set_fixture("/europe/riot/account/v1/accounts/by-riot-id/Galnir/EUW", "account.json")?;
+        mock.set_fixture(&format!("/euw1/lol/summoner/v4/summoners/by-puuid/{}", MOCK_PUUID), "summoner.json")?;
+        mock.set_fixture(&format!("/euw1/lol/league/v4/entries/by-puuid/{}", MOCK_PUUID), "league-entries.json")?;
+        mock.set_fixture(&format!("/europe/lol/match/v5/matches/by-puuid/{}/ids", MOCK_PUUID), "match-ids.json")?;
+        mock.set_fixture("/europe/lol/match/v5/matches/EUW1_7212345678", "match.json")?;
+        mock.set_fixture("/europe/lol/match/v5/matches/EUW1_7212345678/timeline", "timeline.json")?;
+
+        Ok(mock)
+    }
+
+    /// Start an empty host open to every request, like Data Dragon
+    pub async fn start_public() -> Result<Self> {
+        Self::listen(false).await
+    }
+
+    async fn listen(requires_api_key: bool) -> Result<Self> {
+        let state = Arc::new(MockState { requires_api_key, ..MockState::default() });
+
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let port = listener.local_addr()?.port();
+
+        let server_state = state.clone();
+        tokio::spawn(async move {
+            while let Ok((tcp, _)) = listener.accept().await {
+                let state = server_state.clone();
+
+                tokio::spawn(async move {
+                    let _ = handle_connection(tcp, state).await;
+                });
+            }
+        });
+
+        Ok(Self { port, state })
+    }
+
+    /// Base URL template to hand to `RiotApiClientBuilder::base_url`
+    pub fn base_url(&self) -> String {
+        format!("http://127.0.0.1:{}/{{routing}}", self.port)
+    }
+
+    pub fn set_response(&self, path: &str, status: u16, headers: &[(&str, &str)], body: &str) {
+        let response = MockResponse {
+            status,
+            headers: headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            body: body.to_string(),
+        };
+        self.state.routes.lock().unwrap().insert(path.to_string(), response);
+    }
+
+    pub fn set_fixture(&self, path: &str, fixture: &str) -> Result<()> {
+        let body = std::fs::read_to_string(Path::new(FIXTURES_DIR).join(fixture))
+            .context(format!("Missing fixture {}", fixture))?;
+        self.set_response(path, 200, &[("X-App-Rate-Limit", "20:1,100:120"), ("X-App-Rate-Limit-Count", "1:1,1:120")], &body);
+        Ok(())
+    }
+
+    pub fn requests(&self) -> Vec<String> {
+        self.state.requests.lock().unwrap().clone()
+    }
+}
+
+async fn handle_connection<S>(mut stream: S, state: Arc<MockState>) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let request = read_request(&mut stream).await?;
+
+    state.requests.lock().unwrap().push(request.target.clone());
+
+    if state.requires_api_key && request.headers.get("x-riot-token").map(String::as_str) != Some(MOCK_API_KEY) {
+        let body = "{\"status\":{\"message\":\"Forbidden\",\"status_code\":403}}";
+        return write_response(&mut stream, 403, &[], body).await;
+    }
+
+    // A route registered with its query string wins over the bare path
+    let route = {
+        let routes = state.routes.lock().unwrap();
+        routes.get(&request.target).or_else(|| routes.get(request.path())).cloned()
+    };
+
+    let response = route.unwrap_or_else(|| MockResponse {
+        status: 404,
+        headers: Vec::new(),
+        body: "{\"status\":{\"message\":\"Data not found\",\"status_code\":404}}".to_string(),
+    });
+
+    write_response(&mut stream, response.status, &response.headers, &response.body).await
+}
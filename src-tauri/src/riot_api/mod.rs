@@ -1,5 +1,12 @@
 pub mod client;
+pub mod match_query;
 pub mod models;
+pub mod platform;
+pub mod rate_limiter;
+#[cfg(test)]
+pub(crate) mod mock_server;
 
-pub use client::RiotApiClient;
+pub use client::{RiotApiClient, RiotApiClientBuilder};
+pub use match_query::{MatchIdPager, MatchIdQuery};
 pub use models::*;
+pub use platform::{Platform, RegionalRoute};
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -182,6 +183,197 @@ pub struct Objective {
     pub kills: i32,
 }
 
+// Match timeline (match-v5 `/matches/{matchId}/timeline`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchTimeline {
+    pub metadata: MatchMetadata,
+    pub info: TimelineInfo,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineInfo {
+    /// ms between two frames, 60000 on live servers
+    pub frame_interval: i64,
+    pub frames: Vec<TimelineFrame>,
+    #[serde(default)]
+    pub game_id: i64,
+    #[serde(default)]
+    pub participants: Vec<TimelineParticipant>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineParticipant {
+    pub participant_id: i32,
+    pub puuid: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineFrame {
+    pub timestamp: i64,
+    /// Keyed by participant ID ("1" to "10")
+    pub participant_frames: HashMap<String, ParticipantFrame>,
+    pub events: Vec<TimelineEvent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParticipantFrame {
+    pub participant_id: i32,
+    pub current_gold: i32,
+    pub total_gold: i32,
+    pub gold_per_second: i32,
+    pub xp: i32,
+    pub level: i32,
+    pub minions_killed: i32,
+    pub jungle_minions_killed: i32,
+    pub position: Position,
+    #[serde(default)]
+    pub time_enemy_spent_controlled: i32,
+}
+
+impl ParticipantFrame {
+    pub fn cs(&self) -> i32 {
+        self.minions_killed + self.jungle_minions_killed
+    }
+}
+
+/// Map coordinates, (0, 0) is the bottom left corner of the blue side
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TimelineEvent {
+    ChampionKill(ChampionKillEvent),
+    WardPlaced(WardPlacedEvent),
+    ItemPurchased(ItemPurchasedEvent),
+    EliteMonsterKill(EliteMonsterKillEvent),
+    BuildingKill(BuildingKillEvent),
+    SkillLevelUp(SkillLevelUpEvent),
+    /// Events we don't model (LEVEL_UP, WARD_KILL, ITEM_SOLD, GAME_END, ...)
+    #[serde(other)]
+    Other,
+}
+
+impl TimelineEvent {
+    /// Event time in ms since the game started, `None` for unmodelled events
+    pub fn timestamp(&self) -> Option<i64> {
+        match self {
+            TimelineEvent::ChampionKill(e) => Some(e.timestamp),
+            TimelineEvent::WardPlaced(e) => Some(e.timestamp),
+            TimelineEvent::ItemPurchased(e) => Some(e.timestamp),
+            TimelineEvent::EliteMonsterKill(e) => Some(e.timestamp),
+            TimelineEvent::BuildingKill(e) => Some(e.timestamp),
+            TimelineEvent::SkillLevelUp(e) => Some(e.timestamp),
+            TimelineEvent::Other => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChampionKillEvent {
+    pub timestamp: i64,
+    /// 0 when executed by a turret or minion
+    pub killer_id: i32,
+    pub victim_id: i32,
+    #[serde(default)]
+    pub assisting_participant_ids: Vec<i32>,
+    pub position: Position,
+    #[serde(default)]
+    pub bounty: i32,
+    #[serde(default)]
+    pub shutdown_bounty: i32,
+    #[serde(default)]
+    pub kill_streak_length: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WardPlacedEvent {
+    pub timestamp: i64,
+    pub creator_id: i32,
+    pub ward_type: String, // "YELLOW_TRINKET", "CONTROL_WARD", "SIGHT_WARD", "BLUE_TRINKET", "UNDEFINED"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemPurchasedEvent {
+    pub timestamp: i64,
+    pub participant_id: i32,
+    pub item_id: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EliteMonsterKillEvent {
+    pub timestamp: i64,
+    pub killer_id: i32,
+    pub killer_team_id: i32,
+    pub monster_type: String, // "DRAGON", "RIFTHERALD", "BARON_NASHOR", "HORDE", "ATAKHAN"
+    pub monster_sub_type: Option<String>, // e.g. "FIRE_DRAGON", "ELDER_DRAGON"
+    #[serde(default)]
+    pub assisting_participant_ids: Vec<i32>,
+    pub position: Position,
+    #[serde(default)]
+    pub bounty: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildingKillEvent {
+    pub timestamp: i64,
+    pub killer_id: i32,
+    /// Team that lost the building
+    pub team_id: i32,
+    pub building_type: String, // "TOWER_BUILDING", "INHIBITOR_BUILDING"
+    pub lane_type: String, // "TOP_LANE", "MID_LANE", "BOT_LANE"
+    pub tower_type: Option<String>, // "OUTER_TURRET", "INNER_TURRET", "BASE_TURRET", "NEXUS_TURRET"
+    #[serde(default)]
+    pub assisting_participant_ids: Vec<i32>,
+    pub position: Position,
+    #[serde(default)]
+    pub bounty: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillLevelUpEvent {
+    pub timestamp: i64,
+    pub participant_id: i32,
+    /// 1 = Q, 2 = W, 3 = E, 4 = R
+    pub skill_slot: i32,
+    pub level_up_type: String, // "NORMAL", "EVOLVE"
+}
+
+impl MatchTimeline {
+    /// Participant ID (1-10) of a player in this game
+    pub fn participant_id(&self, puuid: &str) -> Option<i32> {
+        self.info.participants.iter()
+            .find(|p| p.puuid == puuid)
+            .map(|p| p.participant_id)
+    }
+
+    /// A participant's state at the given minute (e.g. 10 for gold@10)
+    pub fn participant_frame_at(&self, participant_id: i32, minute: usize) -> Option<&ParticipantFrame> {
+        self.info.frames.get(minute)?
+            .participant_frames
+            .get(&participant_id.to_string())
+    }
+
+    /// Every event of the game, in order
+    pub fn events(&self) -> impl Iterator<Item = &TimelineEvent> {
+        self.info.frames.iter().flat_map(|frame| frame.events.iter())
+    }
+}
+
 // Queue types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum QueueType {
@@ -209,6 +401,11 @@ impl From<i32> for QueueType {
 }
 
 impl QueueType {
+    /// Queue ID used by match-v5
+    pub fn id(self) -> i32 {
+        self as i32
+    }
+
     pub fn name(&self) -> &str {
         match self {
             QueueType::RankedSolo => "Ranked Solo/Duo",
@@ -0,0 +1,176 @@
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use crate::error::GalphaError;
+
+/// Regional routing values, the hosts of account-v1 and match-v5
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegionalRoute {
+    Americas,
+    Asia,
+    Europe,
+    Sea,
+}
+
+impl RegionalRoute {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RegionalRoute::Americas => "americas",
+            RegionalRoute::Asia => "asia",
+            RegionalRoute::Europe => "europe",
+            RegionalRoute::Sea => "sea",
+        }
+    }
+}
+
+impl fmt::Display for RegionalRoute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// League of Legends platform, the host of summoner-v4 and league-v4.
+/// (De)serialized as its lowercase ID, e.g. "euw1".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Platform {
+    Br1,
+    Eun1,
+    Euw1,
+    Jp1,
+    Kr,
+    La1,
+    La2,
+    Me1,
+    Na1,
+    Oc1,
+    Ph2,
+    Ru,
+    Sg2,
+    Th2,
+    Tr1,
+    Tw2,
+    Vn2,
+}
+
+impl Platform {
+    pub const ALL: [Platform; 17] = [
+        Platform::Br1, Platform::Eun1, Platform::Euw1, Platform::Jp1, Platform::Kr, Platform::La1,
+        Platform::La2, Platform::Me1, Platform::Na1, Platform::Oc1, Platform::Ph2, Platform::Ru,
+        Platform::Sg2, Platform::Th2, Platform::Tr1, Platform::Tw2, Platform::Vn2,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Platform::Br1 => "br1",
+            Platform::Eun1 => "eun1",
+            Platform::Euw1 => "euw1",
+            Platform::Jp1 => "jp1",
+            Platform::Kr => "kr",
+            Platform::La1 => "la1",
+            Platform::La2 => "la2",
+            Platform::Me1 => "me1",
+            Platform::Na1 => "na1",
+            Platform::Oc1 => "oc1",
+            Platform::Ph2 => "ph2",
+            Platform::Ru => "ru",
+            Platform::Sg2 => "sg2",
+            Platform::Th2 => "th2",
+            Platform::Tr1 => "tr1",
+            Platform::Tw2 => "tw2",
+            Platform::Vn2 => "vn2",
+        }
+    }
+
+    /// Cluster of match-v5. OCE and the South East Asia servers are on "sea".
+    pub fn match_route(self) -> RegionalRoute {
+        match self {
+            Platform::Br1 | Platform::La1 | Platform::La2 | Platform::Na1 => RegionalRoute::Americas,
+            Platform::Jp1 | Platform::Kr => RegionalRoute::Asia,
+            Platform::Eun1 | Platform::Euw1 | Platform::Me1 | Platform::Ru | Platform::Tr1 => RegionalRoute::Europe,
+            Platform::Oc1 | Platform::Ph2 | Platform::Sg2 | Platform::Th2 | Platform::Tw2 | Platform::Vn2 => RegionalRoute::Sea,
+        }
+    }
+
+    /// Cluster of account-v1, which has no "sea": OCE uses americas, the SEA servers asia
+    pub fn account_route(self) -> RegionalRoute {
+        match self.match_route() {
+            RegionalRoute::Sea if self == Platform::Oc1 => RegionalRoute::Americas,
+            RegionalRoute::Sea => RegionalRoute::Asia,
+            route => route,
+        }
+    }
+
+    /// Platform a match was played on, from the prefix of its ID (`EUW1_7212345678`)
+    pub fn from_match_id(match_id: &str) -> Option<Self> {
+        let (prefix, _) = match_id.split_once('_')?;
+        prefix.parse().ok()
+    }
+}
+
+impl FromStr for Platform {
+    type Err = GalphaError;
+
+    /// Case-insensitive, so match ID prefixes parse too
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let id = s.trim().to_lowercase();
+        Platform::ALL
+            .into_iter()
+            .find(|platform| platform.id() == id)
+            .ok_or_else(|| GalphaError::UnknownPlatform(s.to_string()))
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+impl Serialize for Platform {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.id())
+    }
+}
+
+impl<'de> Deserialize<'de> for Platform {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        id.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_account_and_match_requests() {
+        let routes = |platform: Platform| (platform.account_route().as_str(), platform.match_route().as_str());
+
+        assert_eq!(routes(Platform::Na1), ("americas", "americas"));
+        assert_eq!(routes(Platform::Kr), ("asia", "asia"));
+        assert_eq!(routes(Platform::Euw1), ("europe", "europe"));
+        assert_eq!(routes(Platform::Me1), ("europe", "europe"));
+        assert_eq!(routes(Platform::Oc1), ("americas", "sea"));
+        for platform in [Platform::Ph2, Platform::Sg2, Platform::Th2, Platform::Tw2, Platform::Vn2] {
+            assert_eq!(routes(platform), ("asia", "sea"));
+        }
+    }
+
+    #[test]
+    fn parses_ids_and_match_ids() {
+        for platform in Platform::ALL {
+            assert_eq!(platform.id().parse::<Platform>().unwrap(), platform);
+        }
+        assert_eq!("EUW1".parse::<Platform>().unwrap(), Platform::Euw1);
+        assert_eq!("euw".parse::<Platform>().unwrap_err().code(), "UNKNOWN_PLATFORM");
+
+        assert_eq!(Platform::from_match_id("EUW1_7212345678"), Some(Platform::Euw1));
+        assert_eq!(Platform::from_match_id("VN2_123"), Some(Platform::Vn2));
+        assert_eq!(Platform::from_match_id("7212345678"), None);
+        assert_eq!(Platform::from_match_id("XX1_123"), None);
+
+        assert_eq!(serde_json::to_string(&Platform::Sg2).unwrap(), "\"sg2\"");
+        assert_eq!(serde_json::from_str::<Platform>("\"TW2\"").unwrap(), Platform::Tw2);
+    }
+}
@@ -0,0 +1,302 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use reqwest::header::HeaderMap;
+use tokio::time::{sleep, Duration, Instant};
+use tracing::{debug, warn};
+
+/// Limits assumed for a routing value until Riot tells us the real ones (development key)
+const DEFAULT_APP_LIMITS: &str = "20:1,100:120";
+/// Used on a 429 without `Retry-After` (e.g. an underlying service limit)
+const DEFAULT_RETRY_AFTER_SECS: u64 = 1;
+
+/// One `count:seconds` window, e.g. `100:120` is 100 requests every 2 minutes
+#[derive(Debug, Clone)]
+struct Bucket {
+    limit: u32,
+    window: Duration,
+    count: u32,
+    window_start: Instant,
+}
+
+impl Bucket {
+    fn new(limit: u32, window: Duration, now: Instant) -> Self {
+        Self {
+            limit,
+            window,
+            count: 0,
+            window_start: now,
+        }
+    }
+
+    fn refresh(&mut self, now: Instant) {
+        if now.duration_since(self.window_start) >= self.window {
+            self.count = 0;
+            self.window_start = now;
+        }
+    }
+
+    /// Time to wait before a token is available, `None` if one is available now
+    fn wait_time(&mut self, now: Instant) -> Option<Duration> {
+        self.refresh(now);
+
+        if self.count < self.limit {
+            None
+        } else {
+            Some(self.window_start + self.window - now)
+        }
+    }
+}
+
+/// The buckets of a single `X-*-Rate-Limit` header, plus a block set by a 429
+#[derive(Debug, Clone, Default)]
+struct BucketSet {
+    buckets: Vec<Bucket>,
+    blocked_until: Option<Instant>,
+}
+
+impl BucketSet {
+    fn from_limits(limits: &[(u32, u64)], now: Instant) -> Self {
+        Self {
+            buckets: limits.iter()
+                .map(|&(limit, secs)| Bucket::new(limit, Duration::from_secs(secs), now))
+                .collect(),
+            blocked_until: None,
+        }
+    }
+
+    fn wait_time(&mut self, now: Instant) -> Option<Duration> {
+        let blocked = self.blocked_until
+            .filter(|until| *until > now)
+            .map(|until| until - now);
+
+        self.buckets.iter_mut()
+            .filter_map(|bucket| bucket.wait_time(now))
+            .chain(blocked)
+            .max()
+    }
+
+    fn take(&mut self) {
+        for bucket in &mut self.buckets {
+            bucket.count += 1;
+        }
+    }
+
+    /// Apply the limits and counts reported by Riot
+    fn update(&mut self, limits: &[(u32, u64)], counts: &[(u32, u64)], now: Instant) {
+        let same_limits = limits.len() == self.buckets.len()
+            && limits.iter().zip(&self.buckets)
+                .all(|(&(limit, secs), bucket)| bucket.limit == limit && bucket.window.as_secs() == secs);
+
+        if !same_limits {
+            debug!("Rate limits changed to {:?}", limits);
+            *self = Self {
+                blocked_until: self.blocked_until,
+                ..Self::from_limits(limits, now)
+            };
+        }
+
+        // Riot's count can be ahead of ours (other processes using the key) or behind (requests in flight)
+        for &(count, secs) in counts {
+            if let Some(bucket) = self.buckets.iter_mut().find(|b| b.window.as_secs() == secs) {
+                bucket.refresh(now);
+                bucket.count = bucket.count.max(count);
+            }
+        }
+    }
+
+    fn block_for(&mut self, duration: Duration, now: Instant) {
+        let until = now + duration;
+        self.blocked_until = Some(self.blocked_until.map_or(until, |current| current.max(until)));
+    }
+}
+
+#[derive(Debug, Default)]
+struct LimiterState {
+    /// Application limits, per routing value ("euw1", "europe", ...)
+    app: HashMap<String, BucketSet>,
+    /// Method limits, per (routing value, method)
+    methods: HashMap<(String, String), BucketSet>,
+}
+
+/// Token buckets following Riot's `X-App-Rate-Limit` / `X-Method-Rate-Limit` headers.
+/// Cheap to share: callers wait in `acquire` and then run concurrently.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    state: Mutex<LimiterState>,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait until both the application and method buckets have a token, and take it
+    pub async fn acquire(&self, routing: &str, method: &str) {
+        loop {
+            let wait = self.try_acquire(routing, method, Instant::now());
+
+            match wait {
+                None => return,
+                Some(wait) => {
+                    debug!("Rate limiter: waiting {:?} for {} on {}", wait, method, routing);
+                    sleep(wait).await;
+                }
+            }
+        }
+    }
+
+    fn try_acquire(&self, routing: &str, method: &str, now: Instant) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+
+        let app = state.app
+            .entry(routing.to_string())
+            .or_insert_with(|| BucketSet::from_limits(&parse_limits(DEFAULT_APP_LIMITS), now));
+        let method_set = state.methods
+            .entry((routing.to_string(), method.to_string()))
+            .or_default();
+
+        let wait = app.wait_time(now).into_iter()
+            .chain(method_set.wait_time(now))
+            .max();
+
+        if wait.is_none() {
+            app.take();
+            method_set.take();
+        }
+
+        wait
+    }
+
+    /// Sync the buckets with the headers of any response (successful or not)
+    pub fn update_from_headers(&self, routing: &str, method: &str, headers: &HeaderMap) {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(limits) = header_limits(headers, "X-App-Rate-Limit") {
+            let counts = header_limits(headers, "X-App-Rate-Limit-Count").unwrap_or_default();
+            state.app.entry(routing.to_string()).or_default().update(&limits, &counts, now);
+        }
+
+        if let Some(limits) = header_limits(headers, "X-Method-Rate-Limit") {
+            let counts = header_limits(headers, "X-Method-Rate-Limit-Count").unwrap_or_default();
+            state.methods.entry((routing.to_string(), method.to_string())).or_default().update(&limits, &counts, now);
+        }
+    }
+
+    /// Handle a 429: block the limit that was hit and return how long to wait before retrying
+    pub fn on_rate_limited(&self, routing: &str, method: &str, headers: &HeaderMap) -> Duration {
+        self.update_from_headers(routing, method, headers);
+
+        let retry_after = header_str(headers, "Retry-After")
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        let limit_type = header_str(headers, "X-Rate-Limit-Type").unwrap_or("service");
+
+        warn!("Riot {} rate limit hit for {} on {} (Retry-After: {:?})", limit_type, method, routing, retry_after);
+
+        let Some(retry_after) = retry_after else {
+            return Duration::from_secs(DEFAULT_RETRY_AFTER_SECS);
+        };
+
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+
+        match limit_type {
+            "application" => state.app.entry(routing.to_string()).or_default().block_for(retry_after, now),
+            "method" => state.methods.entry((routing.to_string(), method.to_string())).or_default().block_for(retry_after, now),
+            // Service limits are shared with other apps, only this request backs off
+            _ => {}
+        }
+
+        retry_after
+    }
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+fn header_limits(headers: &HeaderMap, name: &str) -> Option<Vec<(u32, u64)>> {
+    header_str(headers, name).map(parse_limits)
+}
+
+/// Parse `20:1,100:120` into `[(20, 1), (100, 120)]`
+fn parse_limits(value: &str) -> Vec<(u32, u64)> {
+    value.split(',')
+        .filter_map(|pair| {
+            let (count, secs) = pair.trim().split_once(':')?;
+            Some((count.parse().ok()?, secs.parse().ok()?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    #[test]
+    fn parses_limit_headers() {
+        assert_eq!(parse_limits("20:1,100:120"), vec![(20, 1), (100, 120)]);
+        assert_eq!(parse_limits(" 500:10 "), vec![(500, 10)]);
+        assert!(parse_limits("garbage").is_empty());
+    }
+
+    #[test]
+    fn waits_once_a_window_is_full() {
+        let limiter = RateLimiter::new();
+        let now = Instant::now();
+
+        for _ in 0..20 {
+            assert!(limiter.try_acquire("euw1", "get_match_details", now).is_none());
+        }
+
+        let wait = limiter.try_acquire("euw1", "get_match_details", now).unwrap();
+        assert_eq!(wait, Duration::from_secs(1));
+
+        // Other routing values have their own buckets
+        assert!(limiter.try_acquire("europe", "get_match_details", now).is_none());
+
+        // The 1s window reset, the 100:120 one still has room
+        assert!(limiter.try_acquire("euw1", "get_match_details", now + Duration::from_secs(1)).is_none());
+    }
+
+    #[test]
+    fn follows_method_limits_and_counts_from_headers() {
+        let limiter = RateLimiter::new();
+        let now = Instant::now();
+
+        limiter.update_from_headers("europe", "get_match_ids", &headers(&[
+            ("X-Method-Rate-Limit", "2:10"),
+            ("X-Method-Rate-Limit-Count", "1:10"),
+        ]));
+
+        assert!(limiter.try_acquire("europe", "get_match_ids", now).is_none());
+        assert!(limiter.try_acquire("europe", "get_match_ids", now).is_some());
+        assert!(limiter.try_acquire("europe", "get_match_details", now).is_none());
+    }
+
+    #[test]
+    fn retry_after_blocks_the_limit_that_was_hit() {
+        let limiter = RateLimiter::new();
+
+        let wait = limiter.on_rate_limited("euw1", "get_summoner_by_puuid", &headers(&[
+            ("Retry-After", "7"),
+            ("X-Rate-Limit-Type", "application"),
+        ]));
+        assert_eq!(wait, Duration::from_secs(7));
+
+        let now = Instant::now();
+        assert!(limiter.try_acquire("euw1", "get_ranked_stats_by_puuid", now).is_some());
+        assert!(limiter.try_acquire("na1", "get_ranked_stats_by_puuid", now).is_none());
+    }
+}
@@ -0,0 +1,364 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::Utc;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::hkdf::{Salt, HKDF_SHA256};
+use ring::rand::{SecureRandom, SystemRandom};
+use sqlx::{FromRow, SqlitePool};
+use tracing::{info, warn};
+
+pub const RIOT_API_KEY: &str = "riot_api_key";
+pub const DISCORD_TOKEN: &str = "discord_token";
+
+const KEY_FILE_NAME: &str = "master.key";
+const KEY_LEN: usize = 32;
+/// HKDF info of the data keys, the master keys never encrypt anything themselves
+const KEY_CONTEXT: &[u8] = b"galpha secrets v1";
+
+#[derive(Clone)]
+struct MasterKey {
+    id: String,
+    bytes: [u8; KEY_LEN],
+}
+
+impl MasterKey {
+    fn generate(rng: &SystemRandom) -> Result<Self> {
+        let mut bytes = [0u8; KEY_LEN];
+        rng.fill(&mut bytes).map_err(|_| anyhow!("No system randomness"))?;
+
+        let mut id = [0u8; 8];
+        rng.fill(&mut id).map_err(|_| anyhow!("No system randomness"))?;
+        let id = id.iter().map(|b| format!("{:02x}", b)).collect();
+
+        Ok(Self { id, bytes })
+    }
+
+    fn data_key(&self) -> Result<LessSafeKey> {
+        let prk = Salt::new(HKDF_SHA256, &[]).extract(&self.bytes);
+        let okm = prk.expand(&[KEY_CONTEXT], &AES_256_GCM)
+            .map_err(|_| anyhow!("Failed to derive the data key"))?;
+
+        Ok(LessSafeKey::new(UnboundKey::from(okm)))
+    }
+
+    /// The secret name is authenticated so rows can't be swapped
+    fn seal(&self, rng: &SystemRandom, name: &str, value: &str) -> Result<(Vec<u8>, Vec<u8>)> {
+        let mut nonce = [0u8; NONCE_LEN];
+        rng.fill(&mut nonce).map_err(|_| anyhow!("No system randomness"))?;
+
+        let mut ciphertext = value.as_bytes().to_vec();
+        self.data_key()?
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(name.as_bytes()), &mut ciphertext)
+            .map_err(|_| anyhow!("Failed to encrypt secret {}", name))?;
+
+        Ok((nonce.to_vec(), ciphertext))
+    }
+
+    fn open(&self, row: &SecretRow) -> Result<String> {
+        let nonce = Nonce::try_assume_unique_for_key(&row.nonce)
+            .map_err(|_| anyhow!("Invalid nonce for secret {}", row.name))?;
+
+        let mut buffer = row.ciphertext.clone();
+        let plaintext = self.data_key()?
+            .open_in_place(nonce, Aad::from(row.name.as_bytes()), &mut buffer)
+            .map_err(|_| anyhow!("Failed to decrypt secret {}", row.name))?;
+
+        Ok(String::from_utf8(plaintext.to_vec())?)
+    }
+}
+
+#[derive(FromRow)]
+struct SecretRow {
+    name: String,
+    key_id: String,
+    nonce: Vec<u8>,
+    ciphertext: Vec<u8>,
+}
+
+/// Values encrypted at rest in the `secrets` table.
+/// Keys come from a master key file readable by the current user only, one `id:base64` line per key,
+/// the first one encrypting new values. Older keys only stay there while a rotation runs.
+pub struct Secrets {
+    key_file: PathBuf,
+    /// Loaded, or created, on first use
+    keys: Mutex<Option<Vec<MasterKey>>>,
+    rng: SystemRandom,
+}
+
+impl Secrets {
+    pub fn new(key_file: PathBuf) -> Self {
+        Self {
+            key_file,
+            keys: Mutex::new(None),
+            rng: SystemRandom::new(),
+        }
+    }
+
+    /// Next to the default database, but never inside it or its backups
+    pub fn default_key_file() -> PathBuf {
+        dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("galpha")
+            .join(KEY_FILE_NAME)
+    }
+
+    pub async fn get(&self, pool: &SqlitePool, name: &str) -> Result<Option<String>> {
+        let row = sqlx::query_as::<_, SecretRow>(
+            "SELECT name, key_id, nonce, ciphertext FROM secrets WHERE name = ?"
+        )
+        .bind(name)
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let keys = self.keys()?;
+        let key = keys.iter()
+            .find(|key| key.id == row.key_id)
+            .with_context(|| format!("Master key {} of secret {} is missing", row.key_id, name))?;
+
+        Ok(Some(key.open(&row)?))
+    }
+
+    pub async fn set(&self, pool: &SqlitePool, name: &str, value: &str) -> Result<()> {
+        let key = self.keys()?.remove(0);
+        let (nonce, ciphertext) = key.seal(&self.rng, name, value)?;
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO secrets (name, key_id, nonce, ciphertext, updated_at) VALUES (?, ?, ?, ?, ?)"
+        )
+        .bind(name)
+        .bind(&key.id)
+        .bind(nonce)
+        .bind(ciphertext)
+        .bind(Utc::now().timestamp())
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(&self, pool: &SqlitePool, name: &str) -> Result<()> {
+        sqlx::query("DELETE FROM secrets WHERE name = ?")
+            .bind(name)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Re-encrypt every secret with a new master key, returns how many there were.
+    /// The key file holds both keys until the database is committed, so a crash at any point leaves every secret readable.
+    pub async fn rotate(&self, pool: &SqlitePool) -> Result<usize> {
+        let current = self.keys()?;
+        let new_key = MasterKey::generate(&self.rng)?;
+
+        let mut keys = vec![new_key.clone()];
+        keys.extend(current);
+        write_key_file(&self.key_file, &keys)?;
+        *self.keys.lock().unwrap() = Some(keys.clone());
+
+        let mut tx = pool.begin().await?;
+        let rows = sqlx::query_as::<_, SecretRow>("SELECT name, key_id, nonce, ciphertext FROM secrets")
+            .fetch_all(&mut *tx)
+            .await?;
+
+        for row in &rows {
+            let key = keys.iter()
+                .find(|key| key.id == row.key_id)
+                .with_context(|| format!("Master key {} of secret {} is missing", row.key_id, row.name))?;
+            let (nonce, ciphertext) = new_key.seal(&self.rng, &row.name, &key.open(row)?)?;
+
+            sqlx::query("UPDATE secrets SET key_id = ?, nonce = ?, ciphertext = ?, updated_at = ? WHERE name = ?")
+                .bind(&new_key.id)
+                .bind(nonce)
+                .bind(ciphertext)
+                .bind(Utc::now().timestamp())
+                .bind(&row.name)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        write_key_file(&self.key_file, std::slice::from_ref(&new_key))?;
+        *self.keys.lock().unwrap() = Some(vec![new_key]);
+        info!("Rotated the master key of {} secrets", rows.len());
+
+        Ok(rows.len())
+    }
+
+    fn keys(&self) -> Result<Vec<MasterKey>> {
+        let mut keys = self.keys.lock().unwrap();
+
+        if keys.is_none() {
+            *keys = Some(if self.key_file.exists() {
+                read_key_file(&self.key_file)?
+            } else {
+                let key = MasterKey::generate(&self.rng)?;
+                write_key_file(&self.key_file, std::slice::from_ref(&key))?;
+                info!("Created master key file at {:?}", self.key_file);
+                vec![key]
+            });
+        }
+
+        Ok(keys.clone().unwrap_or_default())
+    }
+}
+
+fn read_key_file(path: &Path) -> Result<Vec<MasterKey>> {
+    if !has_strict_permissions(path)? {
+        warn!("Master key file {:?} was readable by other users, restricting it", path);
+        restrict_permissions(path)?;
+    }
+
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read master key file {:?}", path))?;
+
+    let keys = content.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (id, encoded) = line.split_once(':').context("Malformed master key file")?;
+            let bytes = STANDARD.decode(encoded).context("Malformed master key file")?;
+            let bytes = <[u8; KEY_LEN]>::try_from(bytes).map_err(|_| anyhow!("Malformed master key file"))?;
+            Ok(MasterKey { id: id.to_string(), bytes })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if keys.is_empty() {
+        bail!("Master key file {:?} is empty", path);
+    }
+
+    Ok(keys)
+}
+
+/// Write to a temporary file created with strict permissions, then rename over the old one
+fn write_key_file(path: &Path, keys: &[MasterKey]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let content: String = keys.iter()
+        .map(|key| format!("{}:{}\n", key.id, STANDARD.encode(key.bytes)))
+        .collect();
+
+    let temp_path = path.with_extension("key.tmp");
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options.open(&temp_path)
+        .with_context(|| format!("Failed to write master key file {:?}", temp_path))?;
+    restrict_permissions(&temp_path)?;
+    file.write_all(content.as_bytes())?;
+    file.sync_all()?;
+    drop(file);
+
+    fs::rename(&temp_path, path)
+        .with_context(|| format!("Failed to replace master key file {:?}", path))?;
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn has_strict_permissions(path: &Path) -> Result<bool> {
+    use std::os::unix::fs::PermissionsExt;
+    Ok(fs::metadata(path)?.permissions().mode() & 0o077 == 0)
+}
+
+#[cfg(unix)]
+fn restrict_permissions(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    Ok(())
+}
+
+/// The per-user app data directory is already private on Windows
+#[cfg(not(unix))]
+fn has_strict_permissions(_path: &Path) -> Result<bool> {
+    Ok(true)
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+
+    async fn setup() -> (Database, Secrets, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(Some(dir.path().join("galpha.db"))).await.unwrap();
+        let secrets = Secrets::new(dir.path().join(KEY_FILE_NAME));
+        (db, secrets, dir)
+    }
+
+    #[tokio::test]
+    async fn encrypts_values_at_rest() {
+        let (db, secrets, dir) = setup().await;
+
+        secrets.set(db.pool(), RIOT_API_KEY, "RGAPI-secret").await.unwrap();
+        assert_eq!(secrets.get(db.pool(), RIOT_API_KEY).await.unwrap().as_deref(), Some("RGAPI-secret"));
+        assert_eq!(secrets.get(db.pool(), DISCORD_TOKEN).await.unwrap(), None);
+
+        let (ciphertext,): (Vec<u8>,) = sqlx::query_as("SELECT ciphertext FROM secrets")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert!(!String::from_utf8_lossy(&ciphertext).contains("RGAPI-secret"));
+
+        // Only this install's key file can read them
+        let other = Secrets::new(dir.path().join("other.key"));
+        assert!(other.get(db.pool(), RIOT_API_KEY).await.is_err());
+
+        let reloaded = Secrets::new(dir.path().join(KEY_FILE_NAME));
+        assert_eq!(reloaded.get(db.pool(), RIOT_API_KEY).await.unwrap().as_deref(), Some("RGAPI-secret"));
+
+        secrets.delete(db.pool(), RIOT_API_KEY).await.unwrap();
+        assert_eq!(secrets.get(db.pool(), RIOT_API_KEY).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn rotates_the_master_key() {
+        let (db, secrets, dir) = setup().await;
+        secrets.set(db.pool(), RIOT_API_KEY, "RGAPI-secret").await.unwrap();
+        secrets.set(db.pool(), DISCORD_TOKEN, "{}").await.unwrap();
+        let old_file = fs::read_to_string(dir.path().join(KEY_FILE_NAME)).unwrap();
+
+        assert_eq!(secrets.rotate(db.pool()).await.unwrap(), 2);
+
+        let new_file = fs::read_to_string(dir.path().join(KEY_FILE_NAME)).unwrap();
+        assert_ne!(old_file, new_file);
+        assert_eq!(new_file.lines().count(), 1);
+
+        let reloaded = Secrets::new(dir.path().join(KEY_FILE_NAME));
+        assert_eq!(reloaded.get(db.pool(), RIOT_API_KEY).await.unwrap().as_deref(), Some("RGAPI-secret"));
+        assert_eq!(reloaded.get(db.pool(), DISCORD_TOKEN).await.unwrap().as_deref(), Some("{}"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn keeps_the_key_file_private() {
+        use std::os::unix::fs::PermissionsExt;
+        let (db, secrets, dir) = setup().await;
+        let key_file = dir.path().join(KEY_FILE_NAME);
+
+        secrets.set(db.pool(), RIOT_API_KEY, "RGAPI-secret").await.unwrap();
+        assert_eq!(fs::metadata(&key_file).unwrap().permissions().mode() & 0o777, 0o600);
+
+        fs::set_permissions(&key_file, fs::Permissions::from_mode(0o644)).unwrap();
+        let reloaded = Secrets::new(key_file.clone());
+        reloaded.get(db.pool(), RIOT_API_KEY).await.unwrap();
+        assert_eq!(fs::metadata(&key_file).unwrap().permissions().mode() & 0o777, 0o600);
+    }
+}
@@ -0,0 +1,237 @@
+use std::fs;
+use std::path::PathBuf;
+use anyhow::{Context, Result};
+use reqwest::{Client, Response};
+use tokio::time::Duration;
+use tracing::{debug, info, warn};
+use super::models::{StaticData, FILES};
+
+const DEFAULT_BASE_URL: &str = "https://ddragon.leagueoflegends.com";
+const LOCALE: &str = "en_US";
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+/// A patch being downloaded, renamed to its version once every file parsed
+const PARTIAL_SUFFIX: &str = ".partial";
+
+/// Data Dragon files, downloaded once per patch into `<dir>/<version>/`
+#[derive(Debug, Clone)]
+pub struct DataDragon {
+    client: Client,
+    base_url: String,
+    dir: PathBuf,
+}
+
+impl DataDragon {
+    pub fn new(dir: PathBuf) -> Result<Self> {
+        Self::with_base_url(dir, DEFAULT_BASE_URL)
+    }
+
+    /// Point the client at another host, e.g. a local stub server
+    pub fn with_base_url(dir: PathBuf, base_url: &str) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            dir,
+        })
+    }
+
+    /// Next to the database, in the local data directory
+    pub fn default_dir() -> PathBuf {
+        dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("galpha")
+            .join("ddragon")
+    }
+
+    /// Static data of the latest patch, downloaded if it isn't cached yet.
+    /// When Data Dragon can't be reached, the newest readable cached patch is used.
+    pub async fn load(&self) -> Result<StaticData> {
+        let e = match self.load_latest().await {
+            Ok(data) => return Ok(data),
+            Err(e) => e,
+        };
+
+        for version in self.cached_versions() {
+            match StaticData::read(&self.dir.join(&version), version.clone()) {
+                Ok(data) => {
+                    warn!("Failed to update Data Dragon, using patch {} from the cache: {:#}", version, e);
+                    return Ok(data);
+                }
+                Err(read_error) => warn!("Skipping cached Data Dragon {}: {:#}", version, read_error),
+            }
+        }
+
+        Err(e.context("No Data Dragon patch is cached"))
+    }
+
+    async fn load_latest(&self) -> Result<StaticData> {
+        let versions: Vec<String> = self.get(&format!("{}/api/versions.json", self.base_url)).await?
+            .json().await
+            .context("Failed to parse Data Dragon versions")?;
+        let version = versions.into_iter().next().context("Data Dragon listed no versions")?;
+
+        let dir = self.dir.join(&version);
+        if dir.is_dir() {
+            debug!("Data Dragon {} is cached", version);
+            match StaticData::read(&dir, version.clone()) {
+                Ok(data) => return Ok(data),
+                Err(e) => {
+                    warn!("Cached Data Dragon {} is unreadable, downloading it again: {:#}", version, e);
+                    fs::remove_dir_all(&dir).context("Failed to clear the unreadable patch")?;
+                }
+            }
+        }
+
+        self.download(version).await
+    }
+
+    /// Download every file of a patch, its directory only appears once they all parse
+    async fn download(&self, version: String) -> Result<StaticData> {
+        info!("Downloading Data Dragon {}", version);
+
+        let partial = self.dir.join(format!("{}{}", version, PARTIAL_SUFFIX));
+        if partial.exists() {
+            fs::remove_dir_all(&partial).context("Failed to clear an interrupted download")?;
+        }
+        fs::create_dir_all(&partial)
+            .with_context(|| format!("Failed to create {}", partial.display()))?;
+
+        for file in FILES {
+            let url = format!("{}/cdn/{}/data/{}/{}", self.base_url, version, LOCALE, file);
+            let body = self.get(&url).await?
+                .bytes().await
+                .with_context(|| format!("Failed to download {}", file))?;
+            fs::write(partial.join(file), &body)
+                .with_context(|| format!("Failed to save {}", file))?;
+        }
+
+        let data = StaticData::read(&partial, version.clone())?;
+        fs::rename(&partial, self.dir.join(&version)).context("Failed to save the Data Dragon patch")?;
+
+        Ok(data)
+    }
+
+    /// Patches downloaded completely, newest first
+    fn cached_versions(&self) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+
+        let mut versions: Vec<(Vec<u32>, String)> = entries
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter_map(|name| Some((version_key(&name)?, name)))
+            .collect();
+        versions.sort_by(|a, b| b.cmp(a));
+        versions.into_iter().map(|(_, name)| name).collect()
+    }
+
+    async fn get(&self, url: &str) -> Result<Response> {
+        debug!("Fetching {}", url);
+
+        let response = self.client
+            .get(url)
+            .send()
+            .await
+            .context("Failed to reach Data Dragon")?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Data Dragon request for {} failed with status {}", url, response.status());
+        }
+
+        Ok(response)
+    }
+}
+
+/// "14.2.1" -> [14, 2, 1], `None` for anything else (e.g. "14.2.1.partial")
+fn version_key(version: &str) -> Option<Vec<u32>> {
+    version.split('.').map(|part| part.parse().ok()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::riot_api::mock_server::MockRiot;
+
+    const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/ddragon");
+
+    async fn mock_ddragon() -> MockRiot {
+        let mock = MockRiot::start_public().await.unwrap();
+        let fixture = |file: &str| fs::read_to_string(format!("{}/{}", FIXTURES_DIR, file)).unwrap();
+
+        mock.set_response("/api/versions.json", 200, &[], &fixture("versions.json"));
+        for file in FILES {
+            mock.set_response(&format!("/cdn/14.2.1/data/en_US/{}", file), 200, &[], &fixture(file));
+        }
+
+        mock
+    }
+
+    #[tokio::test]
+    async fn downloads_each_patch_once() {
+        let mock = mock_ddragon().await;
+        let dir = tempfile::tempdir().unwrap();
+        let ddragon = DataDragon::with_base_url(dir.path().to_path_buf(), &format!("http://127.0.0.1:{}", mock.port)).unwrap();
+
+        let data = ddragon.load().await.unwrap();
+        assert_eq!(data.version, "14.2.1");
+        assert_eq!(data.champion(103).unwrap().name, "Ahri");
+        assert_eq!(mock.requests().len(), 1 + FILES.len());
+        assert!(dir.path().join("14.2.1").join("runesReforged.json").is_file());
+        assert!(!dir.path().join("14.2.1.partial").exists());
+
+        // Only the versions are checked again
+        ddragon.load().await.unwrap();
+        assert_eq!(mock.requests().len(), 2 + FILES.len());
+    }
+
+    #[tokio::test]
+    async fn works_offline_from_the_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let offline = DataDragon::with_base_url(dir.path().to_path_buf(), "http://127.0.0.1:1").unwrap();
+        assert!(offline.load().await.is_err());
+
+        let mock = mock_ddragon().await;
+        let online = DataDragon::with_base_url(dir.path().to_path_buf(), &format!("http://127.0.0.1:{}", mock.port)).unwrap();
+        online.load().await.unwrap();
+        // Left by an interrupted download of an older patch
+        fs::create_dir_all(dir.path().join("13.24.1.partial")).unwrap();
+
+        let data = offline.load().await.unwrap();
+        assert_eq!(data.version, "14.2.1");
+        assert_eq!(data.item(3089).unwrap().name, "Rabadon's Deathcap");
+        assert_eq!(data.summoner_spell(4).unwrap().name, "Flash");
+    }
+
+    #[tokio::test]
+    async fn replaces_an_unreadable_cached_patch() {
+        let mock = mock_ddragon().await;
+        let dir = tempfile::tempdir().unwrap();
+        let online = DataDragon::with_base_url(dir.path().to_path_buf(), &format!("http://127.0.0.1:{}", mock.port)).unwrap();
+        online.load().await.unwrap();
+
+        // An older patch to fall back to, then the latest one gets damaged
+        fs::create_dir_all(dir.path().join("14.1.1")).unwrap();
+        for file in FILES {
+            fs::copy(dir.path().join("14.2.1").join(file), dir.path().join("14.1.1").join(file)).unwrap();
+        }
+        fs::write(dir.path().join("14.2.1").join("champion.json"), "{").unwrap();
+
+        let offline = DataDragon::with_base_url(dir.path().to_path_buf(), "http://127.0.0.1:1").unwrap();
+        assert_eq!(offline.load().await.unwrap().version, "14.1.1");
+
+        let data = online.load().await.unwrap();
+        assert_eq!(data.version, "14.2.1");
+        assert_eq!(data.champion(103).unwrap().name, "Ahri");
+        assert_eq!(mock.requests().len(), 2 + 2 * FILES.len());
+    }
+
+    #[test]
+    fn orders_versions_numerically() {
+        assert!(version_key("14.10.1") > version_key("14.9.1"));
+        assert_eq!(version_key("14.2.1.partial"), None);
+        assert_eq!(version_key("lolpatch_3.7"), None);
+    }
+}
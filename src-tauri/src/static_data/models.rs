@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Files of `/cdn/{version}/data/{locale}/` kept for each patch
+pub const FILES: [&str; 4] = ["champion.json", "item.json", "runesReforged.json", "summoner.json"];
+
+/// Data Dragon keys numeric IDs as strings, e.g. `"key": "103"`
+fn numeric_key<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i32, D::Error> {
+    let key = String::deserialize(deserializer)?;
+    key.parse().map_err(serde::de::Error::custom)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Image {
+    /// File name under `/cdn/{version}/img/{group}/`, e.g. "Ahri.png"
+    pub full: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Champion {
+    /// Name used in asset paths and `championName` of matches, e.g. "MonkeyKing"
+    pub id: String,
+    /// `championId` of matches
+    #[serde(deserialize_with = "numeric_key")]
+    pub key: i32,
+    pub name: String,
+    pub title: String,
+    pub tags: Vec<String>,
+    pub image: Image,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemGold {
+    pub base: i32,
+    pub total: i32,
+    pub sell: i32,
+    pub purchasable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Item {
+    /// `item0..6` of matches, Data Dragon only has it as the key of the item
+    #[serde(default)]
+    pub id: i32,
+    pub name: String,
+    /// HTML markup
+    pub description: String,
+    #[serde(default)]
+    pub plaintext: String,
+    pub gold: ItemGold,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub image: Image,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rune {
+    /// `perk` of the match selections
+    pub id: i32,
+    pub key: String,
+    pub name: String,
+    /// Path under `/cdn/img/`
+    pub icon: String,
+    pub short_desc: String,
+    pub long_desc: String,
+    /// Tree the rune belongs to, not part of Data Dragon
+    #[serde(default)]
+    pub tree_id: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuneSlot {
+    pub runes: Vec<Rune>,
+}
+
+/// Rune path, e.g. Domination. Its ID is the `style` of the match perks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuneTree {
+    pub id: i32,
+    pub key: String,
+    pub name: String,
+    pub icon: String,
+    /// The keystones first
+    pub slots: Vec<RuneSlot>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SummonerSpell {
+    /// Name used in asset paths, e.g. "SummonerFlash"
+    pub id: String,
+    /// `summoner1Id` and `summoner2Id` of matches
+    #[serde(deserialize_with = "numeric_key")]
+    pub key: i32,
+    pub name: String,
+    pub description: String,
+    /// Game modes the spell is available in, e.g. "CLASSIC"
+    #[serde(default)]
+    pub modes: Vec<String>,
+    pub image: Image,
+}
+
+/// `champion.json`, `item.json` and `summoner.json` share this layout
+#[derive(Deserialize)]
+struct DataFile<T> {
+    data: HashMap<String, T>,
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(dir: &Path, file: &str) -> Result<T> {
+    let path = dir.join(file);
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Static data of one patch, indexed by the numeric IDs stored in matches
+#[derive(Debug, Clone, Default)]
+pub struct StaticData {
+    pub version: String,
+    champions: HashMap<i32, Champion>,
+    items: HashMap<i32, Item>,
+    rune_trees: HashMap<i32, RuneTree>,
+    runes: HashMap<i32, Rune>,
+    summoner_spells: HashMap<i32, SummonerSpell>,
+}
+
+impl StaticData {
+    /// Read the `FILES` of a patch directory
+    pub fn read(dir: &Path, version: String) -> Result<Self> {
+        let champions: DataFile<Champion> = read_json(dir, "champion.json")?;
+        let items: DataFile<Item> = read_json(dir, "item.json")?;
+        let mut rune_trees: Vec<RuneTree> = read_json(dir, "runesReforged.json")?;
+        let summoner_spells: DataFile<SummonerSpell> = read_json(dir, "summoner.json")?;
+
+        let items = items.data
+            .into_iter()
+            .filter_map(|(id, mut item)| {
+                item.id = id.parse().ok()?;
+                Some((item.id, item))
+            })
+            .collect();
+
+        let mut runes = HashMap::new();
+        for tree in &mut rune_trees {
+            for rune in tree.slots.iter_mut().flat_map(|slot| slot.runes.iter_mut()) {
+                rune.tree_id = tree.id;
+                runes.insert(rune.id, rune.clone());
+            }
+        }
+
+        Ok(Self {
+            version,
+            champions: champions.data.into_values().map(|c| (c.key, c)).collect(),
+            items,
+            rune_trees: rune_trees.into_iter().map(|t| (t.id, t)).collect(),
+            runes,
+            summoner_spells: summoner_spells.data.into_values().map(|s| (s.key, s)).collect(),
+        })
+    }
+
+    pub fn champion(&self, id: i32) -> Option<&Champion> {
+        self.champions.get(&id)
+    }
+
+    /// By Data Dragon ID ("MonkeyKing") or display name ("Wukong"), ignoring case
+    pub fn champion_by_name(&self, name: &str) -> Option<&Champion> {
+        self.champions.values()
+            .find(|c| c.id.eq_ignore_ascii_case(name) || c.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn item(&self, id: i32) -> Option<&Item> {
+        self.items.get(&id)
+    }
+
+    /// Keystones and minor runes. Stat shards (5001-5011) aren't in Data Dragon.
+    pub fn rune(&self, id: i32) -> Option<&Rune> {
+        self.runes.get(&id)
+    }
+
+    pub fn rune_tree(&self, id: i32) -> Option<&RuneTree> {
+        self.rune_trees.get(&id)
+    }
+
+    pub fn summoner_spell(&self, id: i32) -> Option<&SummonerSpell> {
+        self.summoner_spells.get(&id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/ddragon");
+
+    #[test]
+    fn looks_up_match_ids() {
+        let data = StaticData::read(Path::new(FIXTURES_DIR), "14.2.1".to_string()).unwrap();
+
+        assert_eq!(data.champion(103).unwrap().name, "Ahri");
+        assert_eq!(data.champion(62).unwrap().id, "MonkeyKing");
+        assert_eq!(data.champion_by_name("wukong").unwrap().key, 62);
+        assert_eq!(data.champion_by_name("MonkeyKing").unwrap().name, "Wukong");
+        assert!(data.champion(0).is_none());
+
+        let deathcap = data.item(3089).unwrap();
+        assert_eq!((deathcap.id, deathcap.name.as_str(), deathcap.gold.total), (3089, "Rabadon's Deathcap", 3600));
+        assert_eq!(data.item(1001).unwrap().plaintext, "Slightly increases Move Speed");
+
+        let electrocute = data.rune(8112).unwrap();
+        assert_eq!((electrocute.name.as_str(), electrocute.tree_id), ("Electrocute", 8100));
+        assert_eq!(data.rune_tree(electrocute.tree_id).unwrap().name, "Domination");
+        assert_eq!(data.rune(8226).unwrap().tree_id, 8200);
+        assert!(data.rune(5008).is_none());
+
+        assert_eq!(data.summoner_spell(4).unwrap().name, "Flash");
+        assert_eq!(data.summoner_spell(14).unwrap().id, "SummonerDot");
+    }
+}
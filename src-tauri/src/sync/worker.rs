@@ -0,0 +1,533 @@
+use std::collections::HashSet;
+use std::pin::Pin;
+use std::sync::Arc;
+use anyhow::Result;
+use futures_util::stream::{self, StreamExt};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{watch, Mutex, Notify, RwLock};
+use tokio::time::{interval_at, sleep, Duration, Instant, Interval, MissedTickBehavior, Sleep};
+use tracing::{debug, info, warn};
+use crate::config::{AppConfig, Config};
+use crate::database::{Database, DbMatch, DbRankedSnapshot, MatchCacheMetadata, TrackedAccount};
+use crate::lcu::{GameflowMonitor, GameflowPhase};
+use crate::riot_api::{MatchIdQuery, Platform, RiotApiClient};
+
+pub const SYNC_PROGRESS_EVENT: &str = "match-sync-progress";
+
+/// Match details downloaded at the same time, the rate limiter does the pacing
+const SYNC_CONCURRENCY: usize = 10;
+/// Upper bound of IDs listed in one incremental sync, in case `last_match_id` left the history
+const MAX_NEW_MATCHES: usize = 500;
+/// match-v5 only has the game a little while after the end of game screen
+const END_OF_GAME_DELAY_SECS: u64 = 60;
+
+/// A player whose history is kept in sync
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackedPuuid {
+    pub puuid: String,
+    pub region: Platform,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncStage {
+    Started,
+    Downloading,
+    Finished,
+    Failed,
+}
+
+/// Payload of the `match-sync-progress` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncProgress {
+    pub puuid: String,
+    pub stage: SyncStage,
+    pub downloaded: usize,
+    pub failed: usize,
+    /// Matches missing from the database when the sync started
+    pub total: usize,
+    pub error: Option<String>,
+}
+
+impl SyncProgress {
+    fn new(puuid: &str, stage: SyncStage) -> Self {
+        Self {
+            puuid: puuid.to_string(),
+            stage,
+            downloaded: 0,
+            failed: 0,
+            total: 0,
+            error: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    pub new_matches: usize,
+    pub failed: usize,
+}
+
+/// Every tracked account is kept in sync, except those paused or stored with an unknown platform
+pub async fn tracked_puuids(pool: &SqlitePool) -> Result<Vec<TrackedPuuid>> {
+    let tracked = TrackedAccount::list(pool).await?
+        .into_iter()
+        .filter(|account| account.sync_enabled)
+        .filter_map(|account| match account.region.parse() {
+            Ok(region) => Some(TrackedPuuid { puuid: account.puuid, region }),
+            Err(e) => {
+                warn!("Not syncing {}: {}", account.puuid, e);
+                None
+            }
+        })
+        .collect();
+
+    Ok(tracked)
+}
+
+/// Download the matches played since the last sync of a PUUID.
+/// The first sync only goes back `initial_match_count` matches.
+pub async fn sync_matches<F>(
+    client: &RiotApiClient,
+    pool: &SqlitePool,
+    target: &TrackedPuuid,
+    settings: &AppConfig,
+    on_progress: F,
+) -> Result<SyncReport>
+where
+    F: Fn(SyncProgress),
+{
+    let metadata = MatchCacheMetadata::get(pool, &target.puuid).await?;
+    let previous_cursor = metadata.as_ref().and_then(|m| m.last_match_id.clone());
+    // Without a cursor (e.g. the oldest match of the first sync failed) the first sync is redone
+    let limit = match previous_cursor {
+        Some(_) => MAX_NEW_MATCHES,
+        None => settings.initial_match_count,
+    };
+
+    let listed = client.match_id_pages(target.region, &target.puuid, MatchIdQuery::new())
+        .stop_at(previous_cursor.clone())
+        .limit(limit)
+        .collect_all()
+        .await?;
+
+    let mut missing = Vec::new();
+    for match_id in &listed {
+        if !DbMatch::exists(pool, match_id).await? {
+            missing.push(match_id.clone());
+        }
+    }
+
+    let mut progress = SyncProgress::new(&target.puuid, SyncStage::Started);
+    progress.total = missing.len();
+    on_progress(progress.clone());
+
+    let mut failed_ids = HashSet::new();
+    let mut downloads = stream::iter(missing)
+        .map(|match_id| async move {
+            let details = client.get_match_details(target.region, &match_id).await;
+            (match_id, details)
+        })
+        .buffer_unordered(SYNC_CONCURRENCY);
+
+    while let Some((match_id, details)) = downloads.next().await {
+        let stored = match details {
+            Ok(details) => DbMatch::store(pool, &details).await,
+            Err(e) => Err(e),
+        };
+
+        match stored {
+            Ok(()) => progress.downloaded += 1,
+            Err(e) => {
+                warn!("Failed to sync match {}: {:#}", match_id, e);
+                failed_ids.insert(match_id);
+                progress.failed += 1;
+            }
+        }
+
+        progress.stage = SyncStage::Downloading;
+        on_progress(progress.clone());
+    }
+
+    // Everything older than the cursor is stored: keep it before the oldest failure so it's retried
+    let cursor = match listed.iter().rposition(|id| failed_ids.contains(id)) {
+        Some(oldest_failure) => listed.get(oldest_failure + 1).cloned(),
+        None => listed.first().cloned(),
+    }
+    .or(previous_cursor);
+
+    let total_cached = metadata.map_or(0, |m| m.total_cached) + progress.downloaded as i32;
+    MatchCacheMetadata::upsert(pool, &target.puuid, cursor.as_deref(), total_cached).await?;
+
+    progress.stage = SyncStage::Finished;
+    on_progress(progress.clone());
+
+    info!("Synced {} new matches for {} ({} failed)", progress.downloaded, target.puuid, progress.failed);
+
+    Ok(SyncReport {
+        new_matches: progress.downloaded,
+        failed: progress.failed,
+    })
+}
+
+/// Record the current rank of every queue, returns how many queues changed
+pub async fn snapshot_ranked(client: &RiotApiClient, pool: &SqlitePool, target: &TrackedPuuid) -> Result<usize> {
+    let entries = client.get_ranked_stats_by_puuid(target.region, &target.puuid).await?;
+    DbRankedSnapshot::store_league_entries(pool, &target.puuid, &entries).await
+}
+
+/// Syncs every tracked PUUID on a timer, after each game, and on request
+#[derive(Clone, Default)]
+pub struct MatchSyncWorker {
+    trigger: Arc<Notify>,
+}
+
+impl MatchSyncWorker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sync now instead of waiting for the timer
+    pub fn request_sync(&self) {
+        self.trigger.notify_one();
+    }
+
+    pub async fn run<F>(
+        self,
+        db: Arc<Mutex<Option<Database>>>,
+        riot_client: Arc<RwLock<Option<Arc<RiotApiClient>>>>,
+        gameflow: GameflowMonitor,
+        mut config: watch::Receiver<Config>,
+        on_progress: F,
+    )
+    where
+        F: Fn(SyncProgress) + Send + Sync + 'static,
+    {
+        let mut transitions = gameflow.subscribe();
+        let mut settings = config.borrow_and_update().app.clone();
+        // Keeps its schedule whatever else wakes the loop up
+        let mut timer = sync_timer(settings.refresh_interval);
+        // Set after a game, the other branches keep running until it fires
+        let mut end_of_game: Option<Pin<Box<Sleep>>> = None;
+
+        loop {
+            tokio::select! {
+                _ = timer.tick() => {}
+                _ = self.trigger.notified() => {}
+                _ = async { end_of_game.as_mut().unwrap().await }, if end_of_game.is_some() => {
+                    end_of_game = None;
+                }
+                changed = config.changed() => match changed {
+                    Ok(()) => {
+                        let previous_interval = settings.refresh_interval;
+                        settings = config.borrow_and_update().app.clone();
+
+                        if settings.refresh_interval != previous_interval {
+                            timer = sync_timer(settings.refresh_interval);
+                        }
+                        continue;
+                    }
+                    Err(_) => break,
+                },
+                transition = transitions.recv() => match transition {
+                    Ok(transition) if transition.current == GameflowPhase::EndOfGame => {
+                        end_of_game = Some(Box::pin(sleep(Duration::from_secs(END_OF_GAME_DELAY_SECS))));
+                        continue;
+                    }
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                },
+            }
+
+            Self::sync_all(&db, &riot_client, &settings, &on_progress).await;
+        }
+    }
+
+    async fn sync_all<F>(
+        db: &Arc<Mutex<Option<Database>>>,
+        riot_client: &Arc<RwLock<Option<Arc<RiotApiClient>>>>,
+        settings: &AppConfig,
+        on_progress: &F,
+    )
+    where
+        F: Fn(SyncProgress),
+    {
+        // Clone the pool so commands aren't blocked on the database lock during the sync
+        let Some(pool) = db.lock().await.as_ref().map(|db| db.pool().clone()) else {
+            debug!("Database not initialized, skipping match sync");
+            return;
+        };
+
+        let Some(client) = riot_client.read().await.clone() else {
+            debug!("Riot API client not initialized, skipping match sync");
+            return;
+        };
+
+        let tracked = match tracked_puuids(&pool).await {
+            Ok(tracked) => tracked,
+            Err(e) => {
+                warn!("Failed to load tracked PUUIDs: {}", e);
+                return;
+            }
+        };
+
+        for target in tracked {
+            if let Err(e) = sync_matches(&client, &pool, &target, settings, on_progress).await {
+                warn!("Match sync failed for {}: {:#}", target.puuid, e);
+
+                let mut progress = SyncProgress::new(&target.puuid, SyncStage::Failed);
+                progress.error = Some(format!("{:#}", e));
+                on_progress(progress);
+            }
+
+            // Runs after every game too, the worker syncs on EndOfGame. Taken even when the
+            // match sync failed, the rank doesn't depend on it.
+            if let Err(e) = snapshot_ranked(&client, &pool, &target).await {
+                warn!("Failed to snapshot ranked stats for {}: {:#}", target.puuid, e);
+            }
+        }
+    }
+}
+
+/// Ticks every `refresh_interval` seconds, starting one interval from now
+fn sync_timer(refresh_interval: u64) -> Interval {
+    let period = Duration::from_secs(refresh_interval.max(1));
+    let mut timer = interval_at(Instant::now() + period, period);
+    timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    timer
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex as StdMutex;
+    use super::*;
+    use crate::database::DbSummoner;
+    use crate::riot_api::mock_server::{MockRiot, MOCK_API_KEY, MOCK_PUUID};
+
+    const MATCH_FIXTURE: &str = include_str!("../../tests/fixtures/riot/match.json");
+
+    fn ids_path() -> String {
+        format!("/europe/lol/match/v5/matches/by-puuid/{}/ids", MOCK_PUUID)
+    }
+
+    /// Serve the recorded match under another ID
+    fn serve_match(mock: &MockRiot, match_id: &str) {
+        let mut details: serde_json::Value = serde_json::from_str(MATCH_FIXTURE).unwrap();
+        details["metadata"]["matchId"] = serde_json::json!(match_id);
+
+        mock.set_response(&format!("/europe/lol/match/v5/matches/{}", match_id), 200, &[], &details.to_string());
+    }
+
+    fn serve_ids(mock: &MockRiot, query: &str, ids: &[&str]) {
+        let body = serde_json::to_string(ids).unwrap();
+        mock.set_response(&format!("{}?{}", ids_path(), query), 200, &[], &body);
+    }
+
+    async fn setup() -> (MockRiot, RiotApiClient, Database, tempfile::TempDir) {
+        let mock = MockRiot::start().await.unwrap();
+        let client = RiotApiClient::builder(MOCK_API_KEY.to_string())
+            .base_url(mock.base_url())
+            .build()
+            .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(Some(dir.path().join("galpha.db"))).await.unwrap();
+
+        let summoner = DbSummoner {
+            puuid: MOCK_PUUID.to_string(),
+            game_name: "Galnir".to_string(),
+            tag_line: "EUW".to_string(),
+            summoner_id: None,
+            account_id: None,
+            summoner_level: Some(412),
+            profile_icon_id: Some(6269),
+            last_updated: 0,
+        };
+        summoner.insert_or_update(db.pool()).await.unwrap();
+
+        (mock, client, db, dir)
+    }
+
+    fn target() -> TrackedPuuid {
+        TrackedPuuid {
+            puuid: MOCK_PUUID.to_string(),
+            region: Platform::Euw1,
+        }
+    }
+
+    #[tokio::test]
+    async fn syncs_initial_matches_then_only_new_ones() {
+        let (mock, client, db, _dir) = setup().await;
+        let settings = AppConfig { initial_match_count: 2, ..AppConfig::default() };
+        for id in ["EUW1_7212345680", "EUW1_7212345679", "EUW1_7212345678"] {
+            serve_match(&mock, id);
+        }
+
+        serve_ids(&mock, "start=0&count=2", &["EUW1_7212345679", "EUW1_7212345678"]);
+        let events = StdMutex::new(Vec::new());
+        let report = sync_matches(&client, db.pool(), &target(), &settings, |p| events.lock().unwrap().push(p))
+            .await
+            .unwrap();
+
+        assert_eq!(report.new_matches, 2);
+        let events = events.into_inner().unwrap();
+        assert_eq!(events.first().unwrap().stage, SyncStage::Started);
+        assert_eq!(events.first().unwrap().total, 2);
+        assert_eq!(events.last().unwrap().stage, SyncStage::Finished);
+        assert_eq!(events.last().unwrap().downloaded, 2);
+
+        let metadata = MatchCacheMetadata::get(db.pool(), MOCK_PUUID).await.unwrap().unwrap();
+        assert_eq!(metadata.last_match_id.as_deref(), Some("EUW1_7212345679"));
+        assert_eq!(metadata.total_cached, 2);
+
+        // Incremental syncs page until the cursor
+        serve_ids(&mock, "start=0&count=100", &["EUW1_7212345680", "EUW1_7212345679", "EUW1_7212345678"]);
+        let report = sync_matches(&client, db.pool(), &target(), &settings, |_| {}).await.unwrap();
+
+        assert_eq!(report.new_matches, 1);
+        assert!(DbMatch::exists(db.pool(), "EUW1_7212345680").await.unwrap());
+
+        let metadata = MatchCacheMetadata::get(db.pool(), MOCK_PUUID).await.unwrap().unwrap();
+        assert_eq!(metadata.last_match_id.as_deref(), Some("EUW1_7212345680"));
+        assert_eq!(metadata.total_cached, 3);
+
+        let detail_requests = mock.requests().iter().filter(|r| !r.contains("/by-puuid/")).count();
+        assert_eq!(detail_requests, 3);
+    }
+
+    #[tokio::test]
+    async fn retries_failed_downloads_on_next_sync() {
+        let (mock, client, db, _dir) = setup().await;
+        let settings = AppConfig { initial_match_count: 3, ..AppConfig::default() };
+        let history = ["EUW1_7212345680", "EUW1_7212345679", "EUW1_7212345678"];
+        serve_match(&mock, "EUW1_7212345679");
+
+        serve_ids(&mock, "start=0&count=3", &history);
+        let report = sync_matches(&client, db.pool(), &target(), &settings, |_| {}).await.unwrap();
+
+        // EUW1_7212345680 is not served (404) and the fixture route serves EUW1_7212345678
+        assert_eq!(report.new_matches, 2);
+        assert_eq!(report.failed, 1);
+
+        let metadata = MatchCacheMetadata::get(db.pool(), MOCK_PUUID).await.unwrap().unwrap();
+        assert_eq!(metadata.last_match_id.as_deref(), Some("EUW1_7212345679"));
+
+        serve_match(&mock, "EUW1_7212345680");
+        serve_ids(&mock, "start=0&count=100", &history);
+        let report = sync_matches(&client, db.pool(), &target(), &settings, |_| {}).await.unwrap();
+
+        assert_eq!(report.new_matches, 1);
+        assert_eq!(report.failed, 0);
+
+        let metadata = MatchCacheMetadata::get(db.pool(), MOCK_PUUID).await.unwrap().unwrap();
+        assert_eq!(metadata.last_match_id.as_deref(), Some("EUW1_7212345680"));
+        assert_eq!(metadata.total_cached, 3);
+    }
+
+    #[tokio::test]
+    async fn syncs_every_tracked_account() {
+        let (_mock, _client, db, _dir) = setup().await;
+        assert!(tracked_puuids(db.pool()).await.unwrap().is_empty());
+
+        TrackedAccount::add(db.pool(), MOCK_PUUID, "na1", None).await.unwrap();
+        TrackedAccount::add(db.pool(), MOCK_PUUID, "euw1", Some("main")).await.unwrap();
+        assert_eq!(tracked_puuids(db.pool()).await.unwrap(), vec![target()]);
+
+        // Stopping the sync keeps the account
+        assert!(TrackedAccount::set_sync_enabled(db.pool(), MOCK_PUUID, false).await.unwrap());
+        assert!(tracked_puuids(db.pool()).await.unwrap().is_empty());
+        let account = TrackedAccount::get(db.pool(), MOCK_PUUID).await.unwrap().unwrap();
+        assert_eq!(account.label.as_deref(), Some("main"));
+        assert!(account.is_primary);
+
+        // Starting it again resumes it
+        TrackedAccount::add(db.pool(), MOCK_PUUID, "euw1", None).await.unwrap();
+        assert_eq!(tracked_puuids(db.pool()).await.unwrap(), vec![target()]);
+
+        TrackedAccount::remove(db.pool(), MOCK_PUUID).await.unwrap();
+        assert!(tracked_puuids(db.pool()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn snapshots_the_rank_when_the_match_sync_fails() {
+        let (mock, client, db, _dir) = setup().await;
+        TrackedAccount::add(db.pool(), MOCK_PUUID, "euw1", None).await.unwrap();
+        mock.set_response(&ids_path(), 403, &[], "{}");
+
+        let pool = db.pool().clone();
+        let db = Arc::new(Mutex::new(Some(db)));
+        let client = Arc::new(RwLock::new(Some(Arc::new(client))));
+        let events = StdMutex::new(Vec::new());
+        MatchSyncWorker::sync_all(&db, &client, &AppConfig::default(), &|p| events.lock().unwrap().push(p)).await;
+
+        assert!(events.into_inner().unwrap().iter().any(|p| p.stage == SyncStage::Failed));
+        let solo = DbRankedSnapshot::latest(&pool, MOCK_PUUID, "RANKED_SOLO_5x5").await.unwrap();
+        assert!(solo.is_some());
+    }
+
+    #[tokio::test]
+    async fn syncs_on_request_while_waiting_after_a_game() {
+        let (mock, client, db, _dir) = setup().await;
+        TrackedAccount::add(db.pool(), MOCK_PUUID, "euw1", None).await.unwrap();
+        mock.set_response(&ids_path(), 403, &[], "{}");
+
+        let gameflow = GameflowMonitor::new();
+        let (_config_tx, config) = watch::channel(Config::default());
+        let worker = MatchSyncWorker::new();
+        tokio::spawn(worker.clone().run(
+            Arc::new(Mutex::new(Some(db))),
+            Arc::new(RwLock::new(Some(Arc::new(client)))),
+            gameflow.clone(),
+            config,
+            |_| {},
+        ));
+        sleep(Duration::from_millis(100)).await;
+
+        // The sync after the game waits a minute, a manual one must not
+        gameflow.transition(GameflowPhase::EndOfGame, &|_| {});
+        sleep(Duration::from_millis(100)).await;
+        worker.request_sync();
+
+        let listed = || mock.requests().iter().any(|r| r.contains("/ids"));
+        for _ in 0..100 {
+            if listed() {
+                break;
+            }
+            sleep(Duration::from_millis(20)).await;
+        }
+        assert!(listed());
+    }
+
+    #[tokio::test]
+    async fn redoes_the_first_sync_when_its_oldest_match_failed() {
+        let (mock, client, db, _dir) = setup().await;
+        let settings = AppConfig { initial_match_count: 2, ..AppConfig::default() };
+        serve_match(&mock, "EUW1_7212345680");
+
+        // EUW1_7212345679 is not served (404)
+        serve_ids(&mock, "start=0&count=2", &["EUW1_7212345680", "EUW1_7212345679"]);
+        let report = sync_matches(&client, db.pool(), &target(), &settings, |_| {}).await.unwrap();
+        assert_eq!(report.new_matches, 1);
+        assert_eq!(report.failed, 1);
+
+        let metadata = MatchCacheMetadata::get(db.pool(), MOCK_PUUID).await.unwrap().unwrap();
+        assert_eq!(metadata.last_match_id, None);
+        assert_eq!(metadata.total_cached, 1);
+
+        serve_match(&mock, "EUW1_7212345679");
+        let report = sync_matches(&client, db.pool(), &target(), &settings, |_| {}).await.unwrap();
+        assert_eq!(report.new_matches, 1);
+        assert_eq!(report.failed, 0);
+
+        // Listed `initial_match_count` again rather than `MAX_NEW_MATCHES`
+        assert!(mock.requests().iter().all(|r| !r.contains("/ids") || r.ends_with("start=0&count=2")));
+
+        let metadata = MatchCacheMetadata::get(db.pool(), MOCK_PUUID).await.unwrap().unwrap();
+        assert_eq!(metadata.last_match_id.as_deref(), Some("EUW1_7212345680"));
+        assert_eq!(metadata.total_cached, 2);
+    }
+}
@@ -1,24 +1,16 @@
 import { useState } from 'react';
+import type { SavedAccount } from '../types';
+import { errorMessage } from '../utils/errors';
 
-export interface SavedAccount {
-  puuid: string;
-  gameName: string;
-  tagLine: string;
-  summonerLevel: number;
-  profileIconId: number;
-  rank?: string;
-  tier?: string;
-  lp?: number;
-  wins?: number;
-  losses?: number;
-  lastPlayed?: string;
-}
+export type { SavedAccount };
 
 interface AccountSwitcherProps {
   accounts: SavedAccount[];
   currentAccount: SavedAccount | null;
   onSelectAccount: (account: SavedAccount) => void;
-  onAddAccount: () => void;
+  /** Look up and track a Riot ID */
+  onAddAccount: (gameName: string, tagLine: string) => Promise<unknown>;
+  onRemoveAccount: (puuid: string) => Promise<void>;
 }
 
 const getRankColor = (tier: string): string => {
@@ -42,8 +34,30 @@ export default function AccountSwitcher({
   currentAccount,
   onSelectAccount,
   onAddAccount,
+  onRemoveAccount,
 }: AccountSwitcherProps) {
   const [isOpen, setIsOpen] = useState(false);
+  const [isAdding, setIsAdding] = useState(false);
+  const [riotId, setRiotId] = useState('');
+  const [addError, setAddError] = useState<string | null>(null);
+
+  const handleAddSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    const [gameName, tagLine] = riotId.split('#').map((part) => part.trim());
+    if (!gameName || !tagLine) {
+      setAddError('Enter a Riot ID like Name#TAG');
+      return;
+    }
+
+    setAddError(null);
+    try {
+      await onAddAccount(gameName, tagLine);
+      setRiotId('');
+      setIsAdding(false);
+    } catch (err) {
+      setAddError(errorMessage(err));
+    }
+  };
 
   const getWinrate = (wins: number, losses: number): number => {
     const total = wins + losses;
@@ -137,14 +151,41 @@ export default function AccountSwitcher({
                     {account.wins || 0}W {account.losses || 0}L
                   </div>
                 </div>
+                <span
+                  role="button"
+                  title="Remove account"
+                  onClick={(e) => {
+                    e.stopPropagation();
+                    onRemoveAccount(account.puuid).catch((err) =>
+                      console.error('Failed to remove account:', err)
+                    );
+                  }}
+                  className="p-1 text-base-lighter hover:text-defeat transition-colors"
+                >
+                  ✕
+                </span>
               </button>
             ))}
 
+            {/* Add account form */}
+            {isAdding && (
+              <form onSubmit={handleAddSubmit} className="flex flex-col gap-2 p-3 mt-2">
+                <input
+                  autoFocus
+                  value={riotId}
+                  onChange={(e) => setRiotId(e.target.value)}
+                  placeholder="Name#TAG"
+                  className="w-full px-3 py-2 bg-base-dark rounded-lg border border-base-light/30 text-sm text-white focus:outline-none focus:border-accent-primary"
+                />
+                {addError && <span className="text-xs text-defeat">{addError}</span>}
+              </form>
+            )}
+
             {/* Add account button */}
             <button
               onClick={() => {
-                onAddAccount();
-                setIsOpen(false);
+                setAddError(null);
+                setIsAdding(!isAdding);
               }}
               className="w-full flex items-center justify-center gap-2 p-3 mt-2 rounded-lg border-2 border-dashed border-base-light/30 hover:border-accent-primary/50 hover:bg-accent-primary/5 transition-all group"
             >
@@ -1,5 +1,6 @@
 import { useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { errorMessage } from '../utils/errors';
 
 interface ApiKeySetupProps {
   onApiKeySet: () => void;
@@ -29,7 +30,7 @@ export function ApiKeySetup({ onApiKeySet, onSkip }: ApiKeySetupProps) {
       await invoke('set_api_key', { apiKey: apiKey.trim() });
       onApiKeySet();
     } catch (err) {
-      setError(err instanceof Error ? err.message : 'Failed to save API key');
+      setError(errorMessage(err));
     } finally {
       setSaving(false);
     }
@@ -1,5 +1,6 @@
-import { useState, useEffect } from 'react';
+import { useState, useMemo } from 'react';
 import AccountSwitcher, { SavedAccount } from './AccountSwitcher';
+import { useAccounts, toSavedAccount } from '../hooks/useAccounts';
 
 interface LauncherDashboardProps {
   isLolRunning: boolean;
@@ -10,58 +11,22 @@ export default function LauncherDashboard({
   isLolRunning,
   currentSummoner,
 }: LauncherDashboardProps) {
-  const [savedAccounts, setSavedAccounts] = useState<SavedAccount[]>([]);
-  const [selectedAccount, setSelectedAccount] = useState<SavedAccount | null>(null);
+  const { accounts, addAccount, removeAccount, switchAccount } = useAccounts();
+  const savedAccounts = useMemo(() => accounts.map(toSavedAccount), [accounts]);
+  const [selectedPuuid, setSelectedPuuid] = useState<string | null>(null);
 
-  // Load saved accounts from localStorage
-  useEffect(() => {
-    const stored = localStorage.getItem('galpha_accounts');
-    if (stored) {
-      const accounts: SavedAccount[] = JSON.parse(stored);
-      setSavedAccounts(accounts);
-
-      // Select highest rank account by default
-      if (accounts.length > 0) {
-        const highestRank = getHighestRankAccount(accounts);
-        setSelectedAccount(highestRank);
-      }
-    }
-  }, []);
-
-  const getHighestRankAccount = (accounts: SavedAccount[]): SavedAccount => {
-    const tierOrder = [
-      'IRON',
-      'BRONZE',
-      'SILVER',
-      'GOLD',
-      'PLATINUM',
-      'EMERALD',
-      'DIAMOND',
-      'MASTER',
-      'GRANDMASTER',
-      'CHALLENGER',
-    ];
-
-    return accounts.reduce((highest, current) => {
-      const currentTierIndex = tierOrder.indexOf(current.tier || 'UNRANKED');
-      const highestTierIndex = tierOrder.indexOf(highest.tier || 'UNRANKED');
-
-      if (currentTierIndex > highestTierIndex) {
-        return current;
-      } else if (currentTierIndex === highestTierIndex && (current.lp || 0) > (highest.lp || 0)) {
-        return current;
-      }
-      return highest;
-    });
-  };
+  // The primary account (listed first) until another one is picked
+  const selectedAccount =
+    savedAccounts.find((account) => account.puuid === selectedPuuid) || savedAccounts[0] || null;
 
   const handleSelectAccount = (account: SavedAccount) => {
-    setSelectedAccount(account);
+    setSelectedPuuid(account.puuid);
+    switchAccount(account.puuid).catch((err) => console.error('Failed to switch account:', err));
   };
 
-  const handleAddAccount = () => {
-    // TODO: Implement add account flow
-    console.log('Add account clicked');
+  const handleAddAccount = async (gameName: string, tagLine: string) => {
+    const account = await addAccount(gameName, tagLine);
+    setSelectedPuuid(account.puuid);
   };
 
   const displayAccount = isLolRunning && currentSummoner ? currentSummoner : selectedAccount;
@@ -95,6 +60,7 @@ export default function LauncherDashboard({
             currentAccount={selectedAccount}
             onSelectAccount={handleSelectAccount}
             onAddAccount={handleAddAccount}
+            onRemoveAccount={removeAccount}
           />
         </div>
 
@@ -2,6 +2,7 @@ import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { getChampionIconUrl, handleChampionIconError } from '../utils/championIcon';
 import type { RankedStats } from '../types';
+import { errorMessage } from '../utils/errors';
 
 interface ActiveGameInfo {
   phase: string;
@@ -131,7 +132,7 @@ export default function LiveGame() {
         setPlayersData(newPlayersData);
       } catch (err) {
         console.error('Failed to fetch active game:', err);
-        setError(err instanceof Error ? err.message : String(err));
+        setError(errorMessage(err));
       } finally {
         setIsLoading(false);
       }
@@ -7,8 +7,10 @@ import LiveGame from './LiveGame';
 import Replays from './Replays';
 import Settings from './Settings';
 import { useMatchHistory } from '../hooks/useMatchHistory';
+import { useAccounts, toSavedAccount } from '../hooks/useAccounts';
 import { getChampionIconUrl, handleChampionIconError, normalizeChampionName } from '../utils/championIcon';
-import type { Summoner, SavedAccount, DiscordUser, SummonerDetails, RankedStats } from '../types';
+import type { Summoner, SavedAccount, DiscordUser, SummonerDetails, RankedStats, LpPoint } from '../types';
+import { errorMessage } from '../utils/errors';
 
 interface MainDashboardProps {
   isLolRunning: boolean;
@@ -35,11 +37,25 @@ export default function MainDashboard({ isLolRunning, currentSummoner, apiError
     error: matchesError,
   } = useMatchHistory(currentAccount?.puuid || null, !!currentAccount);
 
-  // Load saved accounts from localStorage
-  const [savedAccounts] = useState<SavedAccount[]>(() => {
-    const stored = localStorage.getItem('galpha_accounts');
-    return stored ? JSON.parse(stored) : [];
-  });
+  // Solo/Duo LP history, refreshed whenever the rank is fetched again
+  const [lpHistory, setLpHistory] = useState<LpPoint[]>([]);
+  useEffect(() => {
+    if (!currentAccount?.puuid) {
+      setLpHistory([]);
+      return;
+    }
+
+    invoke<LpPoint[]>('get_lp_history', { puuid: currentAccount.puuid })
+      .then(setLpHistory)
+      .catch((error) => {
+        console.error('Failed to fetch LP history:', error);
+        setLpHistory([]);
+      });
+  }, [currentAccount?.puuid, currentAccount?.lp]);
+
+  // Accounts tracked by the backend
+  const { accounts: trackedAccounts, addAccount, switchAccount } = useAccounts();
+  const savedAccounts = useMemo(() => trackedAccounts.map(toSavedAccount), [trackedAccounts]);
 
   // Fetch ranked stats when LoL client account is detected
   useEffect(() => {
@@ -104,7 +120,7 @@ export default function MainDashboard({ isLolRunning, currentSummoner, apiError
           lastPlayed: new Date().toISOString(),
         });
       } catch (error) {
-        const errorMsg = error instanceof Error ? error.message : String(error);
+        const errorMsg = errorMessage(error);
         console.error('Failed to fetch LoL client account data:', error);
         setAccountError(errorMsg);
       } finally {
@@ -123,63 +139,36 @@ export default function MainDashboard({ isLolRunning, currentSummoner, apiError
     setAccountError(null);
 
     try {
-      // Si le compte a été ajouté manuellement, récupérer les données depuis l'API Riot
-      if (account.puuid.startsWith('manual-')) {
-        // Récupérer le compte par Riot ID
-        const accountData = await invoke<{ puuid: string; gameName: string; tagLine: string }>(
-          'get_account_by_riot_id',
-          {
-            gameName: account.gameName,
-            tagLine: account.tagLine,
-          }
-        );
-
-        console.log('Account data:', accountData);
-
-        // Récupérer les informations du summoner
-        const summonerData = await invoke<SummonerDetails>('get_summoner_by_puuid', {
-          puuid: accountData.puuid,
-        });
+      // Un Riot ID saisi à la main est recherché, sauvegardé et suivi par le backend
+      const puuid = account.puuid.startsWith('manual-')
+        ? (await addAccount(account.gameName, account.tagLine)).puuid
+        : account.puuid;
 
-        console.log('Summoner data:', summonerData);
-        console.log('Summoner data keys:', Object.keys(summonerData));
-        console.log('Summoner ID field:', summonerData.id);
-
-        // Récupérer les stats ranked (uniquement si on a un ID)
-        let rankedData: RankedStats[] = [];
-        if (summonerData.id) {
-          rankedData = await invoke<RankedStats[]>('get_ranked_stats', {
-            summonerId: summonerData.id,
-          });
-        } else {
-          console.warn('Summoner ID not available, skipping ranked stats');
-        }
+      // Le compte sélectionné devient le compte principal
+      const tracked = await switchAccount(puuid);
 
-        console.log('Ranked data:', rankedData);
+      let rankedData: RankedStats[] = [];
+      try {
+        rankedData = await invoke<RankedStats[]>('get_ranked_stats_by_puuid', { puuid });
+      } catch (error) {
+        console.error('Failed to fetch ranked stats:', error);
+      }
 
-        // Trouver les stats de la queue Solo/Duo
-        const soloQueue = Array.isArray(rankedData)
-          ? rankedData.find((queue) => queue.queueType === 'RANKED_SOLO_5x5')
-          : null;
+      // Trouver les stats de la queue Solo/Duo
+      const soloQueue = Array.isArray(rankedData)
+        ? rankedData.find((queue) => queue.queueType === 'RANKED_SOLO_5x5')
+        : null;
 
-        // Mettre à jour le compte sélectionné avec les vraies données
-        setSelectedAccount({
-          ...account,
-          puuid: accountData.puuid,
-          summonerLevel: summonerData.summonerLevel,
-          profileIconId: summonerData.profileIconId,
-          tier: soloQueue?.tier || '',
-          rank: soloQueue?.rank || '',
-          lp: soloQueue?.leaguePoints || 0,
-          wins: soloQueue?.wins || 0,
-          losses: soloQueue?.losses || 0,
-        });
-      } else {
-        // Pour les comptes déjà sauvegardés, utiliser directement les données
-        setSelectedAccount(account);
-      }
+      setSelectedAccount({
+        ...toSavedAccount(tracked),
+        tier: soloQueue?.tier || '',
+        rank: soloQueue?.rank || '',
+        lp: soloQueue?.leaguePoints || 0,
+        wins: soloQueue?.wins || 0,
+        losses: soloQueue?.losses || 0,
+      });
     } catch (error) {
-      const errorMsg = error instanceof Error ? error.message : String(error);
+      const errorMsg = errorMessage(error);
       console.error('Failed to fetch account data:', error);
       setAccountError(errorMsg);
     } finally {
@@ -468,7 +457,7 @@ export default function MainDashboard({ isLolRunning, currentSummoner, apiError
         lastPlayed: new Date().toISOString(),
       });
     } catch (error) {
-      const errorMsg = error instanceof Error ? error.message : String(error);
+      const errorMsg = errorMessage(error);
       console.error('Failed to search for player:', error);
       setAccountError(errorMsg);
     } finally {
@@ -699,7 +688,12 @@ export default function MainDashboard({ isLolRunning, currentSummoner, apiError
           </div>
 
           {/* Progression Charts */}
-          <div className="grid grid-cols-4 gap-4 mb-6">
+          <div className={`grid ${lpHistory.length > 1 ? 'grid-cols-5' : 'grid-cols-4'} gap-4 mb-6`}>
+            {lpHistory.length > 1 && (
+              <div className="bg-gradient-to-br from-base-dark to-base-darker rounded-2xl border border-base-medium p-5 shadow-lg hover:border-accent-primary/30 transition-all duration-300">
+                <ProgressionChart matches={matches} puuid={currentAccount?.puuid || ''} statType="lp" lpHistory={lpHistory} />
+              </div>
+            )}
             <div className="bg-gradient-to-br from-base-dark to-base-darker rounded-2xl border border-base-medium p-5 shadow-lg hover:border-accent-primary/30 transition-all duration-300">
               <ProgressionChart matches={matches} puuid={currentAccount?.puuid || ''} statType="kda" />
             </div>
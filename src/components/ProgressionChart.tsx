@@ -1,15 +1,26 @@
 import { useMemo } from 'react';
-import type { MatchData } from '../types';
+import type { LpPoint, MatchData } from '../types';
 
 interface ProgressionChartProps {
   matches: MatchData[];
   puuid: string;
-  statType: 'kda' | 'winrate' | 'cs' | 'damage';
+  statType: 'kda' | 'winrate' | 'cs' | 'damage' | 'lp';
+  /** Required for the 'lp' chart */
+  lpHistory?: LpPoint[];
 }
 
-export default function ProgressionChart({ matches, puuid, statType }: ProgressionChartProps) {
+export default function ProgressionChart({ matches, puuid, statType, lpHistory = [] }: ProgressionChartProps) {
   // Calculate progression data (last 20 matches)
   const progressionData = useMemo(() => {
+    if (statType === 'lp') {
+      // Last 20 rank changes, green when LP went up
+      return lpHistory.slice(-20).map((point, index, points) => ({
+        value: point.ladder_points,
+        label: `${point.tier} ${point.division}`,
+        isWin: index === 0 || point.ladder_points >= points[index - 1].ladder_points,
+      }));
+    }
+
     const recentMatches = matches.slice(0, 20).reverse(); // Get last 20, oldest first
     const data: { value: number; label: string; isWin: boolean }[] = [];
 
@@ -50,7 +61,7 @@ export default function ProgressionChart({ matches, puuid, statType }: Progressi
     });
 
     return data;
-  }, [matches, puuid, statType]);
+  }, [matches, puuid, statType, lpHistory]);
 
   // Calculate trend (positive if improving)
   const trend = useMemo(() => {
@@ -70,6 +81,7 @@ export default function ProgressionChart({ matches, puuid, statType }: Progressi
     winrate: 'Winrate',
     cs: 'CS/min',
     damage: 'Dégâts (k)',
+    lp: 'LP',
   };
 
   const statColors = {
@@ -77,6 +89,7 @@ export default function ProgressionChart({ matches, puuid, statType }: Progressi
     winrate: { positive: 'from-blue-500 to-cyan-500', line: '#3b82f6' },
     cs: { positive: 'from-yellow-500 to-orange-500', line: '#eab308' },
     damage: { positive: 'from-red-500 to-orange-500', line: '#ef4444' },
+    lp: { positive: 'from-emerald-500 to-teal-500', line: '#10b981' },
   };
 
   if (progressionData.length === 0) {
@@ -1,6 +1,7 @@
 import { useState, useEffect } from 'react';
 import { open } from '@tauri-apps/plugin-dialog';
 import { invoke } from '@tauri-apps/api/core';
+import { errorMessage } from '../utils/errors';
 
 interface SettingsState {
   autoRecording: boolean;
@@ -28,6 +29,7 @@ export default function Settings() {
 
   // Riot API Key state
   const [apiKey, setApiKey] = useState('');
+  const [savedApiKey, setSavedApiKey] = useState<string | null>(null);
   const [apiKeyLoading, setApiKeyLoading] = useState(false);
   const [apiKeySaved, setApiKeySaved] = useState(false);
   const [apiKeyError, setApiKeyError] = useState('');
@@ -51,10 +53,9 @@ export default function Settings() {
 
   const loadApiKey = async () => {
     try {
+      // Redacted, e.g. "RGAPI-…1a2b"
       const key = await invoke<string | null>('get_api_key');
-      if (key) {
-        setApiKey(key);
-      }
+      setSavedApiKey(key);
     } catch (error) {
       console.error('Failed to load API key:', error);
     }
@@ -83,7 +84,7 @@ export default function Settings() {
       // Reload the page to reinitialize the API client
       window.location.reload();
     } catch (error) {
-      setApiKeyError(error instanceof Error ? error.message : 'Erreur lors de la sauvegarde');
+      setApiKeyError(errorMessage(error));
       console.error('Failed to save API key:', error);
     } finally {
       setApiKeyLoading(false);
@@ -163,7 +164,7 @@ export default function Settings() {
                 type="text"
                 value={apiKey}
                 onChange={(e) => setApiKey(e.target.value)}
-                placeholder="RGAPI-xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx"
+                placeholder={savedApiKey ?? "RGAPI-xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx"}
                 className="flex-1 bg-base-darker border border-base-light rounded-lg px-4 py-3 text-white placeholder-gray-500 outline-none focus:border-accent-primary transition-colors font-mono text-sm"
               />
               <button
@@ -6,3 +6,4 @@ export { useRiotApi } from './useRiotApi';
 export { useLoLDetection } from './useLoLDetection';
 export { useMatchHistory } from './useMatchHistory';
 export { useLiveGameDetection } from './useLiveGameDetection';
+export { useAccounts, toSavedAccount } from './useAccounts';
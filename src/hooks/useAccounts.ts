@@ -0,0 +1,78 @@
+import { useState, useCallback, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import type { SavedAccount, TrackedAccount } from '../types';
+import { logger } from '../utils/logger';
+import { errorMessage } from '../utils/errors';
+
+/** Display shape of a tracked account, the rank is filled in by the caller */
+export function toSavedAccount(account: TrackedAccount): SavedAccount {
+  return {
+    puuid: account.puuid,
+    gameName: account.game_name,
+    tagLine: account.tag_line,
+    summonerLevel: account.summoner_level ?? 0,
+    profileIconId: account.profile_icon_id ?? 29,
+  };
+}
+
+/** Accounts tracked by the backend, primary first */
+export function useAccounts() {
+  const [accounts, setAccounts] = useState<TrackedAccount[]>([]);
+  const [isLoading, setIsLoading] = useState(true);
+  const [error, setError] = useState<string | null>(null);
+
+  const refresh = useCallback(async () => {
+    setIsLoading(true);
+    setError(null);
+
+    try {
+      setAccounts(await invoke<TrackedAccount[]>('list_accounts'));
+    } catch (err) {
+      logger.error('Failed to list accounts', err);
+      setError(errorMessage(err));
+    } finally {
+      setIsLoading(false);
+    }
+  }, []);
+
+  const addAccount = useCallback(
+    async (gameName: string, tagLine: string, label?: string) => {
+      const account = await invoke<TrackedAccount>('add_account', { gameName, tagLine, label });
+      await refresh();
+      return account;
+    },
+    [refresh]
+  );
+
+  const removeAccount = useCallback(
+    async (puuid: string) => {
+      await invoke('remove_account', { puuid });
+      await refresh();
+    },
+    [refresh]
+  );
+
+  /** Make an account the primary one */
+  const switchAccount = useCallback(
+    async (puuid: string) => {
+      const account = await invoke<TrackedAccount>('switch_account', { puuid });
+      await refresh();
+      return account;
+    },
+    [refresh]
+  );
+
+  useEffect(() => {
+    refresh();
+  }, [refresh]);
+
+  return {
+    accounts,
+    isLoading,
+    error,
+    refresh,
+    addAccount,
+    removeAccount,
+    switchAccount,
+  };
+}
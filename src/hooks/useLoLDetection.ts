@@ -1,10 +1,13 @@
 import { useEffect, useState, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { logger } from '../utils/logger';
-import type { Summoner } from '../types';
+import type { LcuCredentialSource, Summoner } from '../types';
+import { errorMessage, toAppError } from '../utils/errors';
 
 interface UseLoLDetectionReturn {
   isLolRunning: boolean;
+  /** How the client's port and password were found, once connected */
+  lcuSource: LcuCredentialSource | null;
   summoner: Summoner | null;
   isLoadingSummoner: boolean;
   error: string | null;
@@ -13,6 +16,7 @@ interface UseLoLDetectionReturn {
 
 export function useLoLDetection(enabled = true, pollInterval = 3000): UseLoLDetectionReturn {
   const [isLolRunning, setIsLolRunning] = useState(false);
+  const [lcuSource, setLcuSource] = useState<LcuCredentialSource | null>(null);
   const [summoner, setSummoner] = useState<Summoner | null>(null);
   const [isLoadingSummoner, setIsLoadingSummoner] = useState(false);
   const [error, setError] = useState<string | null>(null);
@@ -24,19 +28,30 @@ export function useLoLDetection(enabled = true, pollInterval = 3000): UseLoLDete
 
       if (!running) {
         setSummoner(null);
+        setLcuSource(null);
         logger.debug('League of Legends is not running');
       } else if (!summoner) {
         // LoL is running but we don't have summoner data yet
         logger.info('League of Legends detected');
       }
 
+      if (running && !lcuSource) {
+        try {
+          const source = await invoke<LcuCredentialSource>('get_lcu_connection');
+          setLcuSource(source);
+          logger.info('Connected to the League client', source);
+        } catch (err) {
+          logger.debug('League client not reachable yet', err);
+        }
+      }
+
       return running;
     } catch (err) {
       logger.error('Failed to check LoL status', err);
-      setError(err instanceof Error ? err.message : String(err));
+      setError(errorMessage(err));
       return false;
     }
-  }, [summoner]);
+  }, [summoner, lcuSource]);
 
   const fetchSummoner = useCallback(async () => {
     if (!isLolRunning) return;
@@ -71,10 +86,14 @@ export function useLoLDetection(enabled = true, pollInterval = 3000): UseLoLDete
         });
 
         logger.debug('Summoner saved to database');
+
+        // Keep the match history synced in the background
+        await invoke('start_match_sync', { puuid: data.puuid });
         break; // Success - exit retry loop
       } catch (err) {
-        const errorMsg = err instanceof Error ? err.message : String(err);
-        const isDecryptionError = errorMsg.includes('not available yet') || errorMsg.includes('Exception decrypting');
+        const appError = toAppError(err);
+        const errorMsg = appError.message;
+        const isDecryptionError = appError.code === 'LCU_NOT_READY';
 
         if (isDecryptionError && attempt < maxRetries) {
           // Wait before retrying
@@ -123,6 +142,7 @@ export function useLoLDetection(enabled = true, pollInterval = 3000): UseLoLDete
 
   return {
     isLolRunning,
+    lcuSource,
     summoner,
     isLoadingSummoner,
     error,
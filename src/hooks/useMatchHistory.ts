@@ -2,6 +2,7 @@ import { useState, useCallback, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import type { MatchData } from '../types';
 import { logger } from '../utils/logger';
+import { errorMessage } from '../utils/errors';
 
 export function useMatchHistory(puuid: string | null, enabled: boolean = false) {
   const [matches, setMatches] = useState<MatchData[]>([]);
@@ -93,7 +94,7 @@ export function useMatchHistory(puuid: string | null, enabled: boolean = false)
       setMatches(allMatches);
       logger.info(`Loaded ${allMatches.length} total matches (${cachedMatches?.length || 0} from cache)`);
     } catch (err) {
-      const errorMsg = err instanceof Error ? err.message : String(err);
+      const errorMsg = errorMessage(err);
       logger.error('Failed to fetch match history', err);
       setError(errorMsg);
     } finally {
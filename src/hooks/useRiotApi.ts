@@ -2,6 +2,7 @@ import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { logger } from '../utils/logger';
 import type { Region } from '../types';
+import { errorMessage } from '../utils/errors';
 
 interface UseRiotApiReturn {
   isInitialized: boolean;
@@ -23,24 +24,25 @@ export function useRiotApi(initialRegion: Region = 'euw1'): UseRiotApiReturn {
     setError(null);
 
     try {
-      // Try to get API key from database first
-      let apiKey: string | null = null;
+      // The backend keeps the configured key, only a redacted copy comes back
+      let configuredKey: string | null = null;
 
       try {
-        apiKey = await invoke<string | null>('get_api_key');
+        configuredKey = await invoke<string | null>('get_api_key');
       } catch (dbErr) {
         logger.warn('Failed to get API key from database', dbErr);
       }
 
       // Fallback to environment variable for development
-      if (!apiKey) {
+      let apiKey: string | null = null;
+      if (!configuredKey) {
         const envKey = import.meta.env.VITE_RIOT_API_KEY;
         if (envKey && envKey !== 'your_riot_api_key_here') {
           apiKey = envKey;
         }
       }
 
-      if (!apiKey) {
+      if (!configuredKey && !apiKey) {
         const errorMsg = 'Riot API key not configured';
         logger.error(errorMsg);
         setError(errorMsg);
@@ -56,7 +58,7 @@ export function useRiotApi(initialRegion: Region = 'euw1'): UseRiotApiReturn {
       setIsInitialized(true);
       logger.info('Riot API client initialized', { region: apiRegion });
     } catch (err) {
-      const errorMsg = err instanceof Error ? err.message : String(err);
+      const errorMsg = errorMessage(err);
       logger.error('Failed to initialize Riot API', err);
       setError(errorMsg);
       setIsInitialized(false);
@@ -11,6 +11,12 @@ export interface Summoner {
   profileIconId: number;
 }
 
+/** How the League client was found, from `get_lcu_connection` */
+export type LcuCredentialSource =
+  | { method: 'processArgs' }
+  | { method: 'lockfile'; path: string }
+  | { method: 'explicit' };
+
 export interface SummonerDetails {
   id?: string;
   accountId?: string;
@@ -34,6 +40,34 @@ export interface RankedStats {
   hotStreak?: boolean;
 }
 
+/** Ranked snapshot from `get_lp_history`, oldest first */
+export interface LpPoint {
+  taken_at: number;
+  tier: string;
+  division: string;
+  league_points: number;
+  /** LP on a single scale across tiers (100 per division) */
+  ladder_points: number;
+  wins: number;
+  losses: number;
+}
+
+/** Account from `list_accounts`, primary first */
+export interface TrackedAccount {
+  puuid: string;
+  region: string;
+  label: string | null;
+  is_primary: boolean;
+  /** False while `stop_match_sync` has paused it */
+  sync_enabled: boolean;
+  added_at: number;
+  game_name: string;
+  tag_line: string;
+  summoner_level: number | null;
+  profile_icon_id: number | null;
+  groups: string[];
+}
+
 export interface AccountData {
   puuid: string;
   gameName: string;
@@ -106,4 +140,55 @@ export interface ChampionStats {
 }
 
 export type QueueType = 'RANKED_SOLO_5x5' | 'RANKED_FLEX_SR' | 'NORMAL' | 'ARAM' | 'ALL';
-export type Region = 'euw1' | 'na1' | 'kr' | 'br1' | 'eun1' | 'jp1' | 'la1' | 'la2' | 'oc1' | 'ru' | 'tr1';
+export type Region = 'euw1' | 'na1' | 'kr' | 'br1' | 'eun1' | 'jp1' | 'la1' | 'la2' | 'me1' | 'oc1' | 'ph2' | 'ru' | 'sg2' | 'th2' | 'tr1' | 'tw2' | 'vn2';
+
+// Data Dragon static data of the latest patch
+export interface StaticImage {
+  full: string;
+}
+
+export interface Champion {
+  id: string;
+  key: number;
+  name: string;
+  title: string;
+  tags: string[];
+  image: StaticImage;
+}
+
+export interface Item {
+  id: number;
+  name: string;
+  description: string;
+  plaintext: string;
+  gold: { base: number; total: number; sell: number; purchasable: boolean };
+  tags: string[];
+  image: StaticImage;
+}
+
+export interface Rune {
+  id: number;
+  key: string;
+  name: string;
+  icon: string;
+  shortDesc: string;
+  longDesc: string;
+  treeId: number;
+}
+
+export interface RuneTree {
+  id: number;
+  key: string;
+  name: string;
+  icon: string;
+  slots: { runes: Rune[] }[];
+}
+
+export interface SummonerSpell {
+  id: string;
+  key: number;
+  name: string;
+  description: string;
+  modes: string[];
+  image: StaticImage;
+}
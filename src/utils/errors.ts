@@ -0,0 +1,21 @@
+import type { AppError } from '../types';
+
+/**
+ * Normalize anything thrown by `invoke` into an AppError.
+ * Backend commands reject with `{ code, message, details }`.
+ */
+export function toAppError(err: unknown): AppError {
+  if (err && typeof err === 'object' && 'code' in err && 'message' in err) {
+    return err as AppError;
+  }
+
+  if (err instanceof Error) {
+    return { code: 'UNKNOWN', message: err.message };
+  }
+
+  return { code: 'UNKNOWN', message: String(err) };
+}
+
+export function errorMessage(err: unknown): string {
+  return toAppError(err).message;
+}